@@ -0,0 +1,167 @@
+use crate::{Connector, DataSource, Error};
+use log::debug;
+use serde::{de::DeserializeOwned, Deserialize};
+
+/// Wraps a deserialized resource along with the per-entity control annotations returned by the API.
+///
+/// Can be used in place of `T` when calling [`DataSource::fetch`](`crate::DataSource::fetch`) or
+/// [`DataSource::fetch_paged`](`crate::DataSource::fetch_paged`), for example if you need the concrete
+/// type of an entity in a polymorphic entity set, or its ETag for concurrency control.
+///
+/// Both the OData 3.0 (`odata.type`) and OData 4.0 (`@odata.type`) annotation names are recognized.
+///
+/// When listing resources, each entity of the [`Page`](`crate::Page`) carries its own annotations,
+/// so the ETag of every row is available for subsequent conditional updates:
+/// ```no_run
+/// # use hyper::{Client, client::HttpConnector};
+/// # use hyper_openssl::{HttpsConnector};
+/// # use odata_simple_client::{Annotated, DataSource, ListRequest, Page};
+/// # use serde::Deserialize;
+/// # let client: Client<HttpsConnector<HttpConnector>> =
+/// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+/// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+/// #[derive(Deserialize)]
+/// struct Dokument {
+///     id: usize,
+/// }
+///
+/// # tokio_test::block_on(async {
+/// let page: Page<Annotated<Dokument>> = datasource
+///     .fetch_paged(ListRequest::new("Dokument"))
+///     .await
+///     .unwrap();
+///
+/// for dokument in page.value {
+///     println!("{}: {:?}", dokument.value.id, dokument.etag);
+/// }
+/// # });
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub struct Annotated<T> {
+    /// Url to the schema describing the entity, if included.
+    #[serde(rename = "@odata.context", alias = "odata.metadata", default)]
+    pub context: Option<String>,
+    /// Fully qualified name of the concrete type of the entity.
+    #[serde(rename = "@odata.type", alias = "odata.type", default)]
+    pub odata_type: Option<String>,
+    /// Canonical read link uniquely identifying the entity.
+    #[serde(rename = "@odata.id", alias = "odata.id", default)]
+    pub id: Option<String>,
+    /// ETag of the entity, for use with conditional requests.
+    #[serde(rename = "@odata.etag", alias = "odata.etag", default)]
+    pub etag: Option<String>,
+    /// The deserialized entity itself.
+    #[serde(flatten)]
+    pub value: T,
+}
+
+impl<T> Annotated<T> {
+    /// Discard the annotations, returning only the wrapped entity.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    /// Fetch the single resource addressed by a read `link`, such as the [`Annotated::id`] of an entity,
+    /// without having to reconstruct its key. Relative links are resolved against the base path of the DataSource.
+    pub async fn fetch_by_read_link<T>(&self, link: &str) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let uri = self.resolve_link(link)?;
+
+        debug!("following read link {}", uri);
+        let response = self.get_link(uri).await?;
+        self.deserialize_entity::<T>(response).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Annotated;
+    use crate::{mock, ListRequest, Page};
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Dokument {
+        titel: String,
+    }
+
+    #[test]
+    fn test_annotations_captured() {
+        let annotated: Annotated<Dokument> = serde_json::from_str(
+            r##"{
+                "@odata.type": "#FT.Dokument",
+                "@odata.etag": "W/\"1\"",
+                "titel": "Grund- og nærhedsnotat"
+            }"##,
+        )
+        .unwrap();
+
+        assert_eq!(annotated.odata_type.as_deref(), Some("#FT.Dokument"));
+        assert_eq!(annotated.etag.as_deref(), Some("W/\"1\""));
+        assert!(annotated.id.is_none());
+        assert_eq!(annotated.into_inner().titel, "Grund- og nærhedsnotat");
+    }
+
+    #[test]
+    fn test_v3_annotations_captured() {
+        let annotated: Annotated<Dokument> =
+            serde_json::from_str(r#"{"odata.type": "FT.Dokument", "titel": "Notat"}"#).unwrap();
+
+        assert_eq!(annotated.odata_type.as_deref(), Some("FT.Dokument"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_by_read_link() {
+        let (datasource, connector) = mock::datasource(|request| match request.uri.path() {
+            "/api/Dokument" => mock::json(
+                200,
+                r#"{"value": [{"@odata.id": "https://oda.ft.dk/api/Dokument(24)", "titel": "Notat"}]}"#,
+            ),
+            _ => mock::json(200, r#"{"titel": "Grund"}"#),
+        });
+
+        let page: Page<Annotated<Dokument>> = datasource
+            .fetch_paged(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+        let link = page.value[0].id.as_deref().unwrap();
+
+        let dokument: Dokument = datasource.fetch_by_read_link(link).await.unwrap();
+        assert_eq!(dokument.titel, "Grund");
+        let requests = connector.requests();
+        assert_eq!(requests[1].uri, "/api/Dokument(24)?$format=json");
+        assert_eq!(requests[1].headers["Accept"], "application/json");
+    }
+
+    #[tokio::test]
+    async fn test_page_etags_captured() {
+        let (datasource, _) = mock::datasource(|_| {
+            mock::json(
+                200,
+                r#"{"value": [
+                    {"@odata.etag": "W/\"1\"", "titel": "Notat"},
+                    {"@odata.etag": "W/\"2\"", "titel": "Grund"}
+                ]}"#,
+            )
+        });
+
+        let page: Page<Annotated<Dokument>> = datasource
+            .fetch_paged(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+
+        let etags: Vec<_> = page
+            .value
+            .iter()
+            .map(|dokument| dokument.etag.as_deref())
+            .collect();
+        assert_eq!(etags, [Some("W/\"1\""), Some("W/\"2\"")]);
+        assert_eq!(page.value[1].value.titel, "Grund");
+    }
+}
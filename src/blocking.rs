@@ -0,0 +1,150 @@
+//! Blocking interface to an OData API, for use outside of an async context. Requires the `blocking` feature to be enabled.
+//!
+//! The [`BlockingDataSource`] wraps a [`DataSource`] and runs each request to completion on an internal
+//! single-threaded tokio runtime, so no runtime has to be managed by the caller.
+//! ```no_run
+//! use hyper::{Client, client::HttpConnector};
+//! use hyper_openssl::{HttpsConnector};
+//! use odata_simple_client::{blocking::BlockingDataSource, DataSource, GetRequest};
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Dokument {
+//!     titel: String,
+//! }
+//!
+//! let client: Client<HttpsConnector<HttpConnector>> =
+//!     Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+//!
+//! let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+//! let datasource = BlockingDataSource::new(datasource).unwrap();
+//!
+//! let dokument: Dokument = datasource.fetch(GetRequest::new("Dokument", 24)).unwrap();
+//! ```
+//!
+//! The runtime is created once when constructing the [`BlockingDataSource`] and reused for every request,
+//! so connections are kept alive between sequential requests. Each call blocks the calling thread until the request
+//! completes, and since the runtime is single-threaded, requests from multiple threads are executed one at a time.
+//! Callers which already have a runtime can run the requests on it instead using [`BlockingDataSource::with_handle`].
+//!
+//! Calling any of the methods from within an async context will panic, since the runtime cannot block on itself.
+
+use crate::{Connector, DataSource, Error, GetRequest, ListRequest, Page};
+use serde::de::DeserializeOwned;
+use std::future::Future;
+use tokio::runtime::{Builder, Handle, Runtime};
+
+/// Runtime used by a [`BlockingDataSource`] to execute requests.
+#[derive(Debug)]
+enum Executor {
+    Owned(Runtime),
+    Shared(Handle),
+}
+
+/// Blocking wrapper around a [`DataSource`].
+#[derive(Debug)]
+pub struct BlockingDataSource<C>
+where
+    C: Connector,
+{
+    datasource: DataSource<C>,
+    executor: Executor,
+}
+
+impl<C> BlockingDataSource<C>
+where
+    C: Connector,
+{
+    /// Construct a BlockingDataSource from an existing [`DataSource`], along with the runtime used for executing requests.
+    pub fn new(datasource: DataSource<C>) -> Result<Self, Error> {
+        Ok(BlockingDataSource {
+            datasource,
+            executor: Executor::Owned(Builder::new_current_thread().enable_all().build()?),
+        })
+    }
+
+    /// Construct a BlockingDataSource from an existing [`DataSource`], executing requests on the
+    /// existing runtime of `handle`, rather than creating a new one.
+    ///
+    /// On a multi-threaded runtime, requests are driven by its worker threads while the calling thread blocks.
+    /// A current-thread runtime only drives IO while another thread is blocking on it using `Runtime::block_on`,
+    /// so requests won't make any progress otherwise.
+    pub fn with_handle(datasource: DataSource<C>, handle: Handle) -> Self {
+        BlockingDataSource {
+            datasource,
+            executor: Executor::Shared(handle),
+        }
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        match &self.executor {
+            Executor::Owned(runtime) => runtime.block_on(future),
+            Executor::Shared(handle) => handle.block_on(future),
+        }
+    }
+
+    /// Fetch a single resource using a [`GetRequest`]. See [`DataSource::fetch`].
+    pub fn fetch<T>(&self, request: GetRequest) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        self.block_on(self.datasource.fetch(request))
+    }
+
+    /// Fetch a [`Page`]d list of resources using a [`ListRequest`]. See [`DataSource::fetch_paged`].
+    pub fn fetch_paged<T>(&self, request: ListRequest) -> Result<Page<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        self.block_on(self.datasource.fetch_paged(request))
+    }
+
+    /// Retrieve the wrapped [`DataSource`].
+    pub fn into_inner(self) -> DataSource<C> {
+        self.datasource
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlockingDataSource;
+    use crate::{mock, GetRequest, ListRequest, Page};
+
+    #[test]
+    fn test_blocking_fetch() {
+        let (datasource, connector) = mock::datasource(|request| {
+            if request.uri.path().ends_with("(24)") {
+                mock::json(200, "24")
+            } else {
+                mock::json(200, r#"{"value": [1, 2]}"#)
+            }
+        });
+        let datasource = BlockingDataSource::new(datasource).unwrap();
+
+        let single: u32 = datasource.fetch(GetRequest::new("Dokument", 24)).unwrap();
+        assert_eq!(single, 24);
+
+        let page: Page<u32> = datasource
+            .fetch_paged(ListRequest::new("Dokument"))
+            .unwrap();
+        assert_eq!(page.value, vec![1, 2]);
+        assert_eq!(connector.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_blocking_with_handle() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(1)
+            .enable_all()
+            .build()
+            .unwrap();
+
+        let (datasource, _) = mock::datasource(|_| mock::json(200, "24"));
+        let datasource = BlockingDataSource::with_handle(datasource, runtime.handle().clone());
+
+        for _ in 0..2 {
+            let single: u32 = datasource.fetch(GetRequest::new("Dokument", 24)).unwrap();
+            assert_eq!(single, 24);
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{Connector, DataSource, Error, ListRequest};
+use futures_util::TryStreamExt;
+use serde::de::DeserializeOwned;
+use tokio::sync::mpsc::{self, Receiver};
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    /// Fetch every resource of `request` on a background task, following the `next_link` of each page,
+    /// and send them one at a time over a channel holding up to `buffer` resources. Requires the `channel` feature
+    /// to be enabled.
+    ///
+    /// The task waits for the receiver to make room before sending more resources, so no more than a page and the
+    /// buffered resources are held in memory at once. It stops after sending the first error, or once the
+    /// [`Receiver`] is dropped.
+    ///
+    /// Must be called from within a tokio runtime, since the task is spawned onto it. Panics if `buffer` is 0.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, ListRequest};
+    /// # use serde::Deserialize;
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let mut receiver = datasource.into_channel::<Dokument>(ListRequest::new("Dokument"), 100);
+    ///
+    /// while let Some(dokument) = receiver.recv().await {
+    ///     println!("{}", dokument.unwrap().titel);
+    /// }
+    /// # });
+    /// ```
+    pub fn into_channel<T>(&self, request: ListRequest, buffer: usize) -> Receiver<Result<T, Error>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(buffer);
+        let datasource = self.clone();
+
+        // Detach the task, it ends on its own once the receiver is dropped.
+        drop(tokio::spawn(async move {
+            let mut pages = Box::pin(datasource.stream_pages::<T>(request));
+
+            loop {
+                let page = match pages.try_next().await {
+                    Ok(Some(page)) => page,
+                    Ok(None) => break,
+                    Err(e) => {
+                        // There's nothing left to do if the receiver is gone.
+                        let _ = sender.send(Err(e)).await;
+                        break;
+                    }
+                };
+
+                for value in page.value {
+                    if sender.send(Ok(value)).await.is_err() {
+                        return;
+                    }
+                }
+            }
+        }));
+
+        receiver
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock, ListRequest};
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn test_into_channel() {
+        let (datasource, _) =
+            mock::datasource(|request| match request.uri.query().unwrap_or_default() {
+                query if query.contains("skip") => mock::json(200, r#"{"value": [3]}"#),
+                _ => mock::json(
+                    200,
+                    r#"{"value": [1, 2], "odata.nextLink": "Dokument?$skip=2"}"#,
+                ),
+            });
+
+        let mut receiver = datasource.into_channel::<Value>(ListRequest::new("Dokument"), 1);
+
+        let mut values = Vec::new();
+        while let Some(value) = receiver.recv().await {
+            values.push(value.unwrap());
+        }
+        assert_eq!(values, [1, 2, 3]);
+    }
+}
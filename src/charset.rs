@@ -0,0 +1,76 @@
+use crate::Error;
+use encoding_rs::{Encoding, UTF_8};
+use hyper::{header::CONTENT_TYPE, HeaderMap};
+
+/// Extract the `charset` parameter of a `Content-Type` header value, such as `application/json; charset=ISO-8859-1`.
+fn charset(content_type: &str) -> Option<&str> {
+    content_type.split(';').skip(1).find_map(|parameter| {
+        let (name, value) = parameter.split_once('=')?;
+        name.trim()
+            .eq_ignore_ascii_case("charset")
+            .then(|| value.trim().trim_matches('"'))
+    })
+}
+
+/// Decode the received `body` of a response, transcoding it to UTF-8 from the charset declared by the `Content-Type`
+/// of its `headers`. Responses without a (known) charset are assumed to be UTF-8. Malformed sequences are replaced
+/// with the unicode replacement character, rather than failing the request.
+pub(crate) fn decode_body(headers: &HeaderMap, body: Vec<u8>) -> Result<String, Error> {
+    let encoding = headers
+        .get(CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .and_then(charset)
+        .and_then(|label| Encoding::for_label(label.as_bytes()))
+        .unwrap_or(UTF_8);
+
+    let (content, _, _) = encoding.decode(&body);
+
+    Ok(content.into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::charset;
+    use crate::{mock, GetRequest};
+    use hyper::{Body, Response};
+    use serde_json::Value;
+
+    #[test]
+    fn test_charset_parameter() {
+        assert_eq!(
+            charset("application/json; charset=ISO-8859-1"),
+            Some("ISO-8859-1")
+        );
+        assert_eq!(
+            charset("application/json;odata=minimalmetadata;Charset=\"utf-8\""),
+            Some("utf-8")
+        );
+        assert_eq!(charset("application/json"), None);
+    }
+
+    #[tokio::test]
+    async fn test_latin1_response() {
+        let (datasource, connector) = mock::datasource(|_| {
+            // "titel": "Nærhedsnotat", encoded as ISO-8859-1.
+            let mut body = br#"{"titel": "N"#.to_vec();
+            body.push(0xE6);
+            body.extend_from_slice(br#"rhedsnotat"}"#);
+
+            Response::builder()
+                .header("Content-Type", "application/json; charset=ISO-8859-1")
+                .body(Body::from(body))
+                .unwrap()
+        });
+        let datasource = datasource.with_accept_charset("iso-8859-1");
+
+        let dokument: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(dokument["titel"], "Nærhedsnotat");
+        assert_eq!(
+            connector.requests()[0].headers["Accept-Charset"],
+            "iso-8859-1"
+        );
+    }
+}
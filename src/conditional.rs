@@ -0,0 +1,230 @@
+use crate::{content_length, path::Format, Connector, DataSource, Error, GetRequest, PathBuilder};
+use hyper::{
+    header::{HeaderValue, ETAG, IF_MODIFIED_SINCE, LAST_MODIFIED},
+    Body, Method, Response, StatusCode,
+};
+use serde::de::DeserializeOwned;
+use std::time::SystemTime;
+
+/// Metadata of a response, retrieved without its body using [`DataSource::head`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResponseMeta {
+    /// Status code returned by the API.
+    pub status: StatusCode,
+    /// Length of the body the API would have returned, according to its `Content-Length` header.
+    pub content_length: Option<u64>,
+    /// ETag of the resource, according to the `ETag` header.
+    pub etag: Option<String>,
+    /// Time the resource was last modified, according to the `Last-Modified` header.
+    pub last_modified: Option<SystemTime>,
+}
+
+/// Parse the `Last-Modified` header of `response`, if present.
+fn last_modified(response: &Response<Body>) -> Result<Option<SystemTime>, Error> {
+    response
+        .headers()
+        .get(LAST_MODIFIED)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| httpdate::parse_http_date(value).ok())
+                .ok_or(Error::Header("Last-Modified"))
+        })
+        .transpose()
+}
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    /// Retrieve the metadata of the response to a [`GetRequest`] using a `HEAD` request, without downloading its body,
+    /// for example to check whether a resource exists, or how large it is.
+    ///
+    /// The status is returned as-is, so a missing resource is reported as a `404 Not Found` status rather than an error.
+    /// Servers which don't support `HEAD` requests, responding with `405 Method Not Allowed` or `501 Not Implemented`,
+    /// are sent a `GET` request instead, whose body is discarded without being read.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, GetRequest};
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// # tokio_test::block_on(async {
+    /// let meta = datasource.head(GetRequest::new("Dokument", 24)).await.unwrap();
+    /// if meta.status.is_success() {
+    ///     println!("{:?} bytes, etag {:?}", meta.content_length, meta.etag);
+    /// }
+    /// # });
+    /// ```
+    pub async fn head(&self, request: impl Into<GetRequest>) -> Result<ResponseMeta, Error> {
+        let builder = Into::<PathBuilder>::into(request.into()).format(Format::Json);
+
+        let mut response = self.execute_method(builder.clone(), Method::HEAD).await?;
+        if matches!(
+            response.status(),
+            StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+        ) {
+            response = self.execute(builder).await?;
+        }
+
+        Ok(ResponseMeta {
+            status: response.status(),
+            content_length: content_length(&response),
+            etag: response
+                .headers()
+                .get(ETAG)
+                .map(|etag| etag.to_str().map(str::to_string))
+                .transpose()
+                .map_err(|_| Error::Header("ETag"))?,
+            last_modified: last_modified(&response)?,
+        })
+    }
+
+    /// Fetch a single resource using a [`GetRequest`], along with the time it was last modified,
+    /// according to the `Last-Modified` header of the response, if the API reports one.
+    ///
+    /// The time can later be used to revalidate the resource using [`DataSource::fetch_if_modified_since`].
+    pub async fn fetch_last_modified<T>(
+        &self,
+        request: GetRequest,
+    ) -> Result<(T, Option<SystemTime>), Error>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self
+            .execute(Into::<PathBuilder>::into(request).format(Format::Json))
+            .await?;
+
+        let last_modified = last_modified(&response)?;
+
+        let value = self.deserialize_entity::<T>(response).await?;
+        Ok((value, last_modified))
+    }
+
+    /// Fetch a single resource using a [`GetRequest`], only if it has been modified after `since`,
+    /// by sending an `If-Modified-Since` header along with the request.
+    ///
+    /// Returns `Ok(None)` if the API responds with `304 Not Modified`. Useful for revalidating cached resources
+    /// on services which report when resources were last modified, but don't support ETags.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, GetRequest};
+    /// # use serde::Deserialize;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let (cached, last_modified) = datasource
+    ///     .fetch_last_modified::<Dokument>(GetRequest::new("Dokument", 24))
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// if let Some(since) = last_modified {
+    ///     match datasource.fetch_if_modified_since::<Dokument>(GetRequest::new("Dokument", 24), since).await.unwrap() {
+    ///         Some(updated) => println!("updated: {}", updated.titel),
+    ///         None => println!("unchanged: {}", cached.titel),
+    ///     }
+    /// }
+    /// # });
+    /// ```
+    pub async fn fetch_if_modified_since<T>(
+        &self,
+        request: GetRequest,
+        since: SystemTime,
+    ) -> Result<Option<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let since = HeaderValue::try_from(httpdate::fmt_http_date(since))
+            .map_err(hyper::http::Error::from)?;
+        let builder = Into::<PathBuilder>::into(request)
+            .format(Format::Json)
+            .header(IF_MODIFIED_SINCE, since);
+
+        let response = self.execute(builder).await?;
+        if response.status() == StatusCode::NOT_MODIFIED {
+            return Ok(None);
+        }
+
+        self.deserialize_entity::<T>(response).await.map(Some)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock, GetRequest};
+    use hyper::{header::IF_MODIFIED_SINCE, Body, Method, Response, StatusCode};
+    use serde_json::Value;
+    use std::time::{Duration, SystemTime};
+
+    #[tokio::test]
+    async fn test_head() {
+        let (datasource, connector) = mock::datasource(|request| {
+            let mut response = mock::json(200, r#"{"id": 24}"#);
+            let _ = response
+                .headers_mut()
+                .insert("ETag", "W/\"1\"".parse().unwrap());
+            if request.method == Method::HEAD {
+                *response.status_mut() = StatusCode::METHOD_NOT_ALLOWED;
+            }
+            response
+        });
+
+        let meta = datasource
+            .head(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(meta.status, StatusCode::OK);
+        assert_eq!(meta.content_length, Some(10));
+        assert_eq!(meta.etag.as_deref(), Some("W/\"1\""));
+        assert_eq!(meta.last_modified, None);
+
+        let methods: Vec<_> = connector
+            .requests()
+            .into_iter()
+            .map(|request| request.method)
+            .collect();
+        assert_eq!(methods, [Method::HEAD, Method::GET]);
+    }
+
+    #[tokio::test]
+    async fn test_if_modified_since() {
+        let (datasource, connector) = mock::datasource(|request| {
+            if request.headers.contains_key(IF_MODIFIED_SINCE) {
+                Response::builder().status(304).body(Body::empty()).unwrap()
+            } else {
+                let mut response = mock::json(200, r#"{"id": 24}"#);
+                let _ = response.headers_mut().insert(
+                    "Last-Modified",
+                    "Sun, 06 Nov 1994 08:49:37 GMT".parse().unwrap(),
+                );
+                response
+            }
+        });
+
+        let (_, last_modified) = datasource
+            .fetch_last_modified::<Value>(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        let since = SystemTime::UNIX_EPOCH + Duration::from_secs(784111777);
+        assert_eq!(last_modified, Some(since));
+
+        let dokument = datasource
+            .fetch_if_modified_since::<Value>(GetRequest::new("Dokument", 24), since)
+            .await
+            .unwrap();
+        assert!(dokument.is_none());
+        assert_eq!(
+            connector.requests()[1].headers[IF_MODIFIED_SINCE],
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+    }
+}
@@ -0,0 +1,456 @@
+use crate::{
+    check_status, path::Format, Connector, DataSource, Error, GetRequest, PathBuilder, RawRequest,
+    ResourceType,
+};
+use futures_util::{stream, StreamExt};
+use hyper::{
+    header::{HeaderName, LOCATION},
+    Method,
+};
+use log::debug;
+use serde::{de::DeserializeOwned, Serialize};
+use std::future::Future;
+
+/// Request creating a new resource, by posting `entity` to the collection of its resource type.
+///
+/// Must be [`DataSource::create`]d using a [`DataSource`] to create the resource.
+///
+/// The entity is serialized as JSON using its own [`Serialize`] implementation, without renaming any properties,
+/// so properties named differently by the service, such as the PascalCase names of .NET-backed services,
+/// are matched using `serde` attributes on the entity type:
+/// ```rust
+/// use odata_simple_client::CreateRequest;
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// #[serde(rename_all = "PascalCase")]
+/// struct NewOrder {
+///     customer_name: String,
+///     #[serde(rename = "ShipToID")]
+///     ship_to_id: u32,
+/// }
+///
+/// let entity = NewOrder { customer_name: String::from("Alfreds Futterkiste"), ship_to_id: 12 };
+/// assert_eq!(
+///     serde_json::to_string(&entity).unwrap(),
+///     r#"{"CustomerName":"Alfreds Futterkiste","ShipToID":12}"#
+/// );
+/// let request = CreateRequest::new("Orders", entity);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CreateRequest<T> {
+    builder: PathBuilder,
+    entity: T,
+}
+
+impl<T> CreateRequest<T>
+where
+    T: Serialize,
+{
+    /// Constructs a POST request for `<DataSource Path>/resource_type`, with `entity` serialized as JSON in its body.
+    pub fn new<R>(resource_type: R, entity: T) -> Self
+    where
+        R: Into<ResourceType>,
+    {
+        CreateRequest {
+            builder: PathBuilder::new(resource_type.into().into()),
+            entity,
+        }
+    }
+
+    /// Insert the resource at the zero-based `position` of an ordered collection, using the `$index`
+    /// query option defined by OData 4.0, instead of appending it to the end.
+    pub fn index(mut self, position: u32) -> Self {
+        self.builder = self.builder.raw_param("$index", &position.to_string());
+        self
+    }
+}
+
+/// Create each of `items` using `create`, up to `concurrency` at a time, returning the result of each item in order.
+/// See [`DataSource::bulk_create`].
+pub(crate) async fn bulk_create<'a, T, F, Fut>(
+    items: &'a [T],
+    concurrency: usize,
+    create: F,
+) -> Vec<Result<(), Error>>
+where
+    F: Fn(&'a T) -> Fut,
+    Fut: Future<Output = Result<(), Error>>,
+{
+    let total = items.len();
+    let mut completed = 0;
+    stream::iter(items)
+        .map(create)
+        .buffered(concurrency.max(1))
+        .inspect(|result| {
+            completed += 1;
+            debug!(
+                "created {completed} of {total} resources{}",
+                if result.is_err() {
+                    ", last one failed"
+                } else {
+                    ""
+                }
+            );
+        })
+        .collect()
+        .await
+}
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    /// Create a resource using a [`CreateRequest`], and deserialize the created resource returned by the server.
+    ///
+    /// Any successful status, such as `201 Created`, is accepted. Other status codes, such as a `400 Bad Request`
+    /// when the entity fails validation, are returned as [`Error::Status`].
+    /// Servers responding with `204 No Content` instead of the created resource fail to deserialize,
+    /// unless `R` accepts an empty body.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{CreateRequest, DataSource};
+    /// # use serde::{Deserialize, Serialize};
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Serialize)]
+    /// struct NytDokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     id: usize,
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let entity = NytDokument { titel: String::from("Grundnotat") };
+    /// let created: Dokument = datasource
+    ///     .create(CreateRequest::new("Dokument", entity))
+    ///     .await
+    ///     .unwrap();
+    /// # });
+    /// ```
+    pub async fn create<T, R>(&self, request: CreateRequest<T>) -> Result<R, Error>
+    where
+        T: Serialize,
+        R: DeserializeOwned,
+    {
+        let body =
+            serde_json::to_vec(&request.entity).map_err(|e| Error::Serde(e, String::new()))?;
+        let response = self
+            .execute_with_body(
+                request.builder.format(Format::Json),
+                Method::POST,
+                Some(body),
+            )
+            .await?;
+        self.deserialize_entity(response).await
+    }
+
+    /// Create each of `items` as a resource of `resource_type`, posting up to `concurrency` of them at a time,
+    /// for services without `$batch` support.
+    ///
+    /// Unlike [`DataSource::create`], the created resources are neither requested nor deserialized, and an item
+    /// failing to be created doesn't stop the others. The result of each item is returned in the order of `items`,
+    /// and progress is logged at debug level as items complete. With the `rate-limiting` feature enabled,
+    /// use `RateLimitedDataSource::bulk_create` to also respect a rate limit.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::DataSource;
+    /// # use serde::Serialize;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Serialize)]
+    /// struct NytDokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let items = vec![
+    ///     NytDokument { titel: String::from("Grundnotat") },
+    ///     NytDokument { titel: String::from("Svar") },
+    /// ];
+    /// let results = datasource.bulk_create("Dokument", &items, 4).await;
+    /// println!("{} failed", results.iter().filter(|result| result.is_err()).count());
+    /// # });
+    /// ```
+    pub async fn bulk_create<T>(
+        &self,
+        resource_type: &str,
+        items: &[T],
+        concurrency: usize,
+    ) -> Vec<Result<(), Error>>
+    where
+        T: Serialize,
+    {
+        bulk_create(items, concurrency, |item| {
+            self.create_without_response(resource_type, item)
+        })
+        .await
+    }
+
+    /// Create `entity` as a resource of `resource_type`, asking the server not to return it.
+    pub(crate) async fn create_without_response<T>(
+        &self,
+        resource_type: &str,
+        entity: &T,
+    ) -> Result<(), Error>
+    where
+        T: Serialize,
+    {
+        let body = serde_json::to_vec(entity).map_err(|e| Error::Serde(e, String::new()))?;
+        let response = self
+            .execute_with_body(
+                PathBuilder::new(resource_type.to_string()).prefer("return=minimal"),
+                Method::POST,
+                Some(body),
+            )
+            .await?;
+        let _ = check_status(response).await?;
+        Ok(())
+    }
+
+    /// Create a resource using a [`CreateRequest`] like [`DataSource::create`], but ask the server not to return it
+    /// using `Prefer: return=minimal`, and return a [`GetRequest`] for the created resource instead.
+    ///
+    /// The request is parsed from the `Location` header of the response, or its `OData-EntityId` header
+    /// if missing, so the created resource can be fetched or related to others immediately.
+    /// Fails with [`Error::Header`] if neither header is present.
+    pub async fn create_minimal<T>(&self, request: CreateRequest<T>) -> Result<GetRequest, Error>
+    where
+        T: Serialize,
+    {
+        let body =
+            serde_json::to_vec(&request.entity).map_err(|e| Error::Serde(e, String::new()))?;
+        let response = self
+            .execute_with_body(
+                request.builder.prefer("return=minimal"),
+                Method::POST,
+                Some(body),
+            )
+            .await?;
+        let response = check_status(response).await?;
+
+        let location = [LOCATION, HeaderName::from_static("odata-entityid")]
+            .iter()
+            .find_map(|name| response.headers().get(name))
+            .and_then(|location| location.to_str().ok())
+            .ok_or(Error::Header("Location"))?;
+        let uri = self.resolve_link(location)?;
+
+        Ok(GetRequest {
+            builder: RawRequest::try_from(uri)?.into(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock, CreateRequest, Error, RedirectPolicy};
+    use hyper::{Body, Method, Response, StatusCode};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize)]
+    struct NytDokument {
+        titel: &'static str,
+    }
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Dokument {
+        id: usize,
+        titel: String,
+    }
+
+    #[tokio::test]
+    async fn test_create() {
+        let (datasource, connector) = mock::datasource(|request| {
+            let entity: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            match entity["titel"].as_str() {
+                Some("") => mock::json(400, r#"{"odata.error": {"message": "titel is required"}}"#),
+                _ => {
+                    let mut created = entity.as_object().unwrap().clone();
+                    let _ = created.insert(String::from("id"), 24.into());
+                    mock::json(201, &serde_json::Value::Object(created).to_string())
+                }
+            }
+        });
+
+        let created: Dokument = datasource
+            .create(CreateRequest::new(
+                "Dokument",
+                NytDokument {
+                    titel: "Grundnotat",
+                },
+            ))
+            .await
+            .unwrap();
+        assert_eq!(
+            created,
+            Dokument {
+                id: 24,
+                titel: String::from("Grundnotat")
+            }
+        );
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].uri, "/api/Dokument?$format=json");
+        assert_eq!(requests[0].headers["Content-Type"], "application/json");
+        assert_eq!(requests[0].body, br#"{"titel":"Grundnotat"}"#);
+
+        let error = datasource
+            .create::<_, Dokument>(CreateRequest::new("Dokument", NytDokument { titel: "" }))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Status {
+                code: StatusCode::BAD_REQUEST,
+                ..
+            }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_minimal() {
+        let (datasource, connector) = mock::datasource(|request| match request.method {
+            Method::POST => Response::builder()
+                .status(204)
+                .header("Location", "https://oda.ft.dk/api/Dokument(24)")
+                .body(Body::empty())
+                .unwrap(),
+            _ => mock::json(200, r#"{"id": 24, "titel": "Grundnotat"}"#),
+        });
+
+        let created = datasource
+            .create_minimal(CreateRequest::new(
+                "Dokument",
+                NytDokument {
+                    titel: "Grundnotat",
+                },
+            ))
+            .await
+            .unwrap();
+        let dokument: Dokument = datasource.fetch(created).await.unwrap();
+        assert_eq!(dokument.id, 24);
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].headers["Prefer"], "return=minimal");
+        assert_eq!(requests[1].uri, "/api/Dokument(24)?$format=json");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_create() {
+        let (datasource, connector) = mock::datasource(|request| {
+            let entity: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+            match entity["titel"].as_str() {
+                Some("") => mock::json(400, r#"{"odata.error": {"message": "titel is required"}}"#),
+                _ => Response::builder().status(204).body(Body::empty()).unwrap(),
+            }
+        });
+
+        let items = [
+            NytDokument {
+                titel: "Grundnotat",
+            },
+            NytDokument { titel: "" },
+            NytDokument { titel: "Svar" },
+        ];
+        let results = datasource.bulk_create("Dokument", &items, 2).await;
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_ok());
+        assert!(matches!(
+            results[1],
+            Err(Error::Status {
+                code: StatusCode::BAD_REQUEST,
+                ..
+            })
+        ));
+        assert!(results[2].is_ok());
+
+        let requests = connector.requests();
+        assert_eq!(requests.len(), 3);
+        assert!(requests.iter().all(|request| request.uri == "/api/Dokument"
+            && request.headers["Prefer"] == "return=minimal"));
+    }
+
+    #[tokio::test]
+    async fn test_create_index() {
+        let (datasource, connector) =
+            mock::datasource(|_| mock::json(201, r#"{"id": 24, "titel": "Grundnotat"}"#));
+
+        let _: Dokument = datasource
+            .create(
+                CreateRequest::new(
+                    "Dokument",
+                    NytDokument {
+                        titel: "Grundnotat",
+                    },
+                )
+                .index(2),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            connector.requests()[0].uri,
+            "/api/Dokument?$format=json&$index=2"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_without_defaults() {
+        let (datasource, connector) =
+            mock::datasource(|_| mock::json(201, r#"{"id": 24, "titel": "Grundnotat"}"#));
+        let datasource = datasource
+            .with_default_select(vec![String::from("id")])
+            .with_max_top(100);
+
+        let _: Dokument = datasource
+            .create(CreateRequest::new(
+                "Dokument",
+                NytDokument {
+                    titel: "Grundnotat",
+                },
+            ))
+            .await
+            .unwrap();
+        assert_eq!(connector.requests()[0].uri, "/api/Dokument?$format=json");
+    }
+
+    #[tokio::test]
+    async fn test_create_see_other() {
+        let (datasource, connector) = mock::datasource(|request| match request.method {
+            Method::POST => Response::builder()
+                .status(303)
+                .header("Location", "/api/Dokument(24)")
+                .body(Body::empty())
+                .unwrap(),
+            _ => mock::json(200, r#"{"id": 24, "titel": "Grundnotat"}"#),
+        });
+        let datasource = datasource.with_redirect_policy(RedirectPolicy::Limited(5));
+
+        let created: Dokument = datasource
+            .create(CreateRequest::new(
+                "Dokument",
+                NytDokument {
+                    titel: "Grundnotat",
+                },
+            ))
+            .await
+            .unwrap();
+        assert_eq!(created.id, 24);
+
+        let requests = connector.requests();
+        assert_eq!(requests.len(), 2);
+        assert_eq!(requests[1].method, Method::GET);
+        assert_eq!(requests[1].uri, "/api/Dokument(24)");
+        assert!(requests[1].body.is_empty());
+        assert!(!requests[1].headers.contains_key("Content-Type"));
+    }
+}
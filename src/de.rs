@@ -0,0 +1,252 @@
+//! Helpers for deserializing the loosely-typed JSON emitted by some OData services.
+//!
+//! Reference them from your own structs using serde's `deserialize_with` attribute:
+//! ```rust
+//! use serde::Deserialize;
+//!
+//! #[derive(Deserialize)]
+//! struct Dokument {
+//!     #[serde(deserialize_with = "odata_simple_client::de::string_or_number")]
+//!     id: u32,
+//!     #[serde(default, deserialize_with = "odata_simple_client::de::option_string_or_number")]
+//!     typeid: Option<u32>,
+//! }
+//!
+//! let dokument: Dokument = serde_json::from_str(r#"{"id": "24", "typeid": 5}"#).unwrap();
+//! assert_eq!(dokument.id, 24);
+//! assert_eq!(dokument.typeid, Some(5));
+//! ```
+use serde::{de::Error, Deserialize, Deserializer};
+use std::{fmt::Display, str::FromStr};
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StringOrValue<T> {
+    String(String),
+    Value(T),
+}
+
+impl<T> StringOrValue<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    fn into_value<E: Error>(self) -> Result<T, E> {
+        match self {
+            StringOrValue::String(string) => string.trim().parse().map_err(E::custom),
+            StringOrValue::Value(value) => Ok(value),
+        }
+    }
+}
+
+/// Deserialize a value which may be represented either as itself (usually a JSON number), or as a string
+/// containing it, such as `42` and `"42"`.
+pub fn string_or_number<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    T::Err: Display,
+{
+    StringOrValue::<T>::deserialize(deserializer)?.into_value()
+}
+
+/// Like [`string_or_number`], but for optional values. `null` is deserialized as `None`.
+///
+/// Combine with `#[serde(default)]` if the field may be missing entirely.
+pub fn option_string_or_number<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr + Deserialize<'de>,
+    T::Err: Display,
+{
+    Option::<StringOrValue<T>>::deserialize(deserializer)?
+        .map(StringOrValue::into_value)
+        .transpose()
+}
+
+/// Deserialize a string, removing any trailing whitespace, such as the stray newline ending the title of
+/// `Dokument(26)` in the Folketinget API.
+/// ```rust
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Dokument {
+///     #[serde(deserialize_with = "odata_simple_client::de::trimmed_string")]
+///     titel: String,
+/// }
+///
+/// let dokument: Dokument = serde_json::from_str(r#"{"titel": "Revideret grundnotat\n"}"#).unwrap();
+/// assert_eq!(dokument.titel, "Revideret grundnotat");
+/// ```
+pub fn trimmed_string<'de, D>(deserializer: D) -> Result<String, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let mut value = String::deserialize(deserializer)?;
+    value.truncate(value.trim_end().len());
+    Ok(value)
+}
+
+/// Like [`trimmed_string`], but for optional strings. `null` is deserialized as `None`.
+///
+/// Combine with `#[serde(default)]` if the field may be missing entirely.
+pub fn option_trimmed_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(
+        Option::<String>::deserialize(deserializer)?.map(|mut value| {
+            value.truncate(value.trim_end().len());
+            value
+        }),
+    )
+}
+
+/// Timestamp in the `/Date(1586937600000)/` form used by OData 2.0 and 3.0 verbose JSON, containing the number
+/// of milliseconds since the Unix epoch.
+///
+/// Timezone offsets such as `/Date(1586937600000+0060)/` are accepted, but not applied, since the milliseconds are
+/// already relative to UTC, and the offset in minutes only records the timezone the timestamp originated from.
+/// ```rust
+/// use odata_simple_client::de::EdmDate;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Dokument {
+///     dato: EdmDate,
+/// }
+///
+/// let dokument: Dokument = serde_json::from_str(r#"{"dato": "\/Date(1586937600000)\/"}"#).unwrap();
+/// assert_eq!(dokument.dato, EdmDate(1586937600000));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct EdmDate(pub i64);
+
+impl FromStr for EdmDate {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid Edm date `{value}`, expected `/Date(<milliseconds>)/`");
+
+        let inner = value
+            .strip_prefix("/Date(")
+            .and_then(|value| value.strip_suffix(")/"))
+            .ok_or_else(invalid)?;
+
+        // The sign of negative timestamps comes first, so any later sign introduces the offset.
+        let first = inner.chars().next().map_or(0, char::len_utf8);
+        let (milliseconds, offset) = match inner[first..].find(['+', '-']) {
+            Some(index) => inner.split_at(index + first),
+            None => (inner, ""),
+        };
+        if !offset.is_empty()
+            && (offset.len() < 2 || !offset[1..].bytes().all(|b| b.is_ascii_digit()))
+        {
+            return Err(invalid());
+        }
+
+        milliseconds.parse().map(EdmDate).map_err(|_| invalid())
+    }
+}
+
+impl<'de> Deserialize<'de> for EdmDate {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(D::Error::custom)
+    }
+}
+
+/// Deserialize the inline count of a page, which is a string in OData 3.0, and a number in OData 4.0.
+pub(crate) fn option_count<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(
+        Option::<StringOrValue<u64>>::deserialize(deserializer)?.map(|count| match count {
+            StringOrValue::String(count) => count,
+            StringOrValue::Value(count) => count.to_string(),
+        }),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EdmDate;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug)]
+    struct Loose {
+        #[serde(deserialize_with = "super::string_or_number")]
+        count: u64,
+        #[serde(default, deserialize_with = "super::option_string_or_number")]
+        price: Option<f64>,
+    }
+
+    #[test]
+    fn test_string_or_number() {
+        let loose: Loose = serde_json::from_str(r#"{"count": "42", "price": 1.5}"#).unwrap();
+        assert_eq!(loose.count, 42);
+        assert_eq!(loose.price, Some(1.5));
+
+        let loose: Loose = serde_json::from_str(r#"{"count": 42, "price": null}"#).unwrap();
+        assert_eq!(loose.count, 42);
+        assert_eq!(loose.price, None);
+
+        let loose: Loose = serde_json::from_str(r#"{"count": 42}"#).unwrap();
+        assert_eq!(loose.price, None);
+
+        assert!(serde_json::from_str::<Loose>(r#"{"count": "many"}"#).is_err());
+    }
+
+    #[derive(Deserialize, Debug)]
+    struct Untrimmed {
+        #[serde(deserialize_with = "super::trimmed_string")]
+        titel: String,
+        #[serde(default, deserialize_with = "super::option_trimmed_string")]
+        resume: Option<String>,
+    }
+
+    #[test]
+    fn test_trimmed_string() {
+        let untrimmed: Untrimmed =
+            serde_json::from_str(r#"{"titel": " Grundnotat \r\n", "resume": "Kort\t"}"#).unwrap();
+        assert_eq!(untrimmed.titel, " Grundnotat");
+        assert_eq!(untrimmed.resume.as_deref(), Some("Kort"));
+
+        let untrimmed: Untrimmed =
+            serde_json::from_str(r#"{"titel": "", "resume": null}"#).unwrap();
+        assert_eq!(untrimmed.titel, "");
+        assert_eq!(untrimmed.resume, None);
+    }
+
+    #[test]
+    fn test_edm_date() {
+        for (value, expected) in [
+            ("/Date(1586937600000)/", 1586937600000),
+            ("/Date(1586937600000+0060)/", 1586937600000),
+            ("/Date(1586937600000-0120)/", 1586937600000),
+            ("/Date(-86400000)/", -86400000),
+        ] {
+            assert_eq!(value.parse::<EdmDate>(), Ok(EdmDate(expected)));
+        }
+
+        for value in [
+            "2020-04-15",
+            "/Date()/",
+            "/Date(1586937600000+)/",
+            "/Date(1586937600000+01:00)/",
+            "/Date(é)/",
+            "/Date(é+0060)/",
+        ] {
+            assert!(value.parse::<EdmDate>().is_err(), "{value}");
+        }
+
+        let date: EdmDate = serde_json::from_str(r#""\/Date(1586937600000)\/""#).unwrap();
+        assert_eq!(date, EdmDate(1586937600000));
+        assert!(serde_json::from_str::<EdmDate>("1586937600000").is_err());
+    }
+}
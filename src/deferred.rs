@@ -0,0 +1,290 @@
+use crate::{Connector, DataSource, Error, Page};
+use log::debug;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+
+/// A collection-valued navigation property, which the API may have expanded only partially, or not at all.
+///
+/// Deserializing such a property into a plain `Vec` silently drops the link to the remaining part of the collection.
+/// `Deferred<T>` instead accepts a plain JSON array, an expanded collection in the verbose format
+/// (`{"results": [...], "__next": "..."}`), as well as a deferred collection (`{"__deferred": {"uri": "..."}}`),
+/// keeping the link to follow using [`DataSource::fetch_link`].
+/// ```rust
+/// use odata_simple_client::Deferred;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Fil {
+///     id: usize,
+/// }
+///
+/// #[derive(Deserialize)]
+/// struct Dokument {
+///     #[serde(rename = "Fil")]
+///     filer: Deferred<Fil>,
+/// }
+///
+/// let dokument: Dokument = serde_json::from_str(
+///     r#"{"Fil": {"results": [{"id": 1}], "__next": "Dokument(24)/Fil?$skip=1"}}"#
+/// ).unwrap();
+///
+/// assert_eq!(dokument.filer.values().len(), 1);
+/// assert!(!dokument.filer.is_complete());
+/// assert_eq!(dokument.filer.link(), Some("Dokument(24)/Fil?$skip=1"));
+/// ```
+///
+/// OData 4.0 instead annotates a partial expansion with a sibling `Fil@odata.nextLink` property,
+/// which can be captured using a separate `#[serde(rename = "Fil@odata.nextLink")]` field.
+///
+/// Partial expansions can only be detected by properties deserialized as `Deferred`, which log the link of the
+/// remaining part at debug level, so plain `Vec` properties of a collection the API may page are silently truncated.
+/// Use [`DataSource::fetch_deferred`] to retrieve the rest of the collection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Deferred<T> {
+    /// The collection was expanded, possibly only partially, in which case `next_link` points to the remainder.
+    Expanded {
+        /// The expanded part of the collection.
+        values: Vec<T>,
+        /// Link to the remaining part of the collection, if any.
+        next_link: Option<String>,
+    },
+    /// The collection was not expanded, and must be fetched separately from `uri`.
+    Deferred {
+        /// Link to the collection.
+        uri: String,
+    },
+}
+
+#[derive(Deserialize)]
+struct DeferredUri {
+    uri: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Representation<T> {
+    Deferred {
+        #[serde(rename = "__deferred")]
+        deferred: DeferredUri,
+    },
+    Verbose {
+        results: Vec<T>,
+        #[serde(rename = "__next")]
+        next_link: Option<String>,
+    },
+    Plain(Vec<T>),
+}
+
+impl<'de, T> Deserialize<'de> for Deferred<T>
+where
+    T: Deserialize<'de>,
+{
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Representation::deserialize(deserializer)? {
+            Representation::Deferred { deferred } => Deferred::Deferred { uri: deferred.uri },
+            Representation::Verbose { results, next_link } => {
+                if let Some(next_link) = &next_link {
+                    debug!(
+                        "collection was only partially expanded, the remainder is at {next_link}"
+                    );
+                }
+                Deferred::Expanded {
+                    values: results,
+                    next_link,
+                }
+            }
+            Representation::Plain(values) => Deferred::Expanded {
+                values,
+                next_link: None,
+            },
+        })
+    }
+}
+
+impl<T> Deferred<T> {
+    /// The part of the collection which was included in the response, if any.
+    pub fn values(&self) -> &[T] {
+        match self {
+            Deferred::Expanded { values, .. } => values,
+            Deferred::Deferred { .. } => &[],
+        }
+    }
+
+    /// Whether the entire collection was included in the response.
+    pub fn is_complete(&self) -> bool {
+        matches!(
+            self,
+            Deferred::Expanded {
+                next_link: None,
+                ..
+            }
+        )
+    }
+
+    /// Link to the part of the collection which was not included in the response, if any.
+    pub fn link(&self) -> Option<&str> {
+        match self {
+            Deferred::Expanded { next_link, .. } => next_link.as_deref(),
+            Deferred::Deferred { uri } => Some(uri),
+        }
+    }
+}
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    /// Retrieve the entire collection of a [`Deferred`] navigation property, following its link,
+    /// and the `next_link` of every subsequent page, until the collection is complete.
+    ///
+    /// The values which were already included in the response are returned first.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, Deferred, ListRequest, Page};
+    /// # use serde::Deserialize;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Deserialize)]
+    /// struct Fil {
+    ///     id: usize,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     #[serde(rename = "Fil")]
+    ///     filer: Deferred<Fil>,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let page: Page<Dokument> = datasource
+    ///     .fetch_paged(ListRequest::new("Dokument").expand(["Fil"]))
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// for dokument in page.value {
+    ///     let filer = datasource.fetch_deferred(dokument.filer).await.unwrap();
+    ///     println!("{} files", filer.len());
+    /// }
+    /// # });
+    /// ```
+    pub async fn fetch_deferred<T>(&self, deferred: Deferred<T>) -> Result<Vec<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let (mut values, mut link) = match deferred {
+            Deferred::Expanded { values, next_link } => (values, next_link),
+            Deferred::Deferred { uri } => {
+                let page = self.fetch_link::<T>(&uri).await?;
+                (page.value, page.next_link)
+            }
+        };
+
+        while let Some(next_link) = link {
+            let mut page = self.fetch_next::<T>(&next_link).await?;
+            values.append(&mut page.value);
+            link = page.next_link;
+        }
+
+        Ok(values)
+    }
+
+    /// Fetch the [`Page`] of resources at `link`, such as the [`Deferred::link`] of a partially expanded collection.
+    ///
+    /// Relative links are resolved against the base path of the DataSource, like the `next_link` of a [`Page`],
+    /// and JSON is asked for like [`DataSource::fetch_paged`] does, since deferred links carry no `$format`.
+    pub async fn fetch_link<T>(&self, link: &str) -> Result<Page<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let uri = self.resolve_link(link)?;
+
+        debug!("following link {}", uri);
+        let response = self.get_link(uri).await?;
+        self.deserialize_page::<T>(response).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Deferred;
+    use crate::{mock, FormatNegotiation, Page};
+    use serde_json::Value;
+
+    #[test]
+    fn test_representations() {
+        let plain: Deferred<u32> = serde_json::from_str("[1, 2]").unwrap();
+        assert_eq!(plain.values(), [1, 2]);
+        assert!(plain.is_complete());
+        assert_eq!(plain.link(), None);
+
+        let deferred: Deferred<u32> = serde_json::from_str(
+            r#"{"__deferred": {"uri": "https://oda.ft.dk/api/Dokument(24)/Fil"}}"#,
+        )
+        .unwrap();
+        assert!(deferred.values().is_empty());
+        assert!(!deferred.is_complete());
+        assert_eq!(
+            deferred.link(),
+            Some("https://oda.ft.dk/api/Dokument(24)/Fil")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_fetch_deferred() {
+        let (datasource, connector) =
+            mock::datasource(|request| match request.uri.query().unwrap_or_default() {
+                query if query.contains("$skip=2") => mock::json(200, r#"{"value": [3]}"#),
+                _ => mock::json(
+                    200,
+                    r#"{"value": [2], "odata.nextLink": "Dokument(24)/Fil?$skip=2"}"#,
+                ),
+            });
+
+        let deferred: Deferred<u32> =
+            serde_json::from_str(r#"{"results": [1], "__next": "Dokument(24)/Fil?$skip=1"}"#)
+                .unwrap();
+        let values = datasource.fetch_deferred(deferred).await.unwrap();
+        assert_eq!(values, [1, 2, 3]);
+        assert_eq!(connector.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_link() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"value": [2]}"#));
+
+        let deferred: Deferred<u32> =
+            serde_json::from_str(r#"{"results": [1], "__next": "Dokument(24)/Fil?$skip=1"}"#)
+                .unwrap();
+        let page: Page<Value> = datasource
+            .fetch_link(deferred.link().unwrap())
+            .await
+            .unwrap();
+        assert_eq!(page.value, [2]);
+
+        let page: Page<Value> = datasource
+            .clone()
+            .with_format_negotiation(FormatNegotiation::AcceptHeader)
+            .fetch_link("Dokument(24)/Fil?$skip=1")
+            .await
+            .unwrap();
+        assert_eq!(page.value, [2]);
+
+        let deferred: Deferred<u32> = serde_json::from_str(
+            r#"{"__deferred": {"uri": "https://oda.ft.dk/api/Dokument(24)/Fil"}}"#,
+        )
+        .unwrap();
+        let values = datasource.fetch_deferred(deferred).await.unwrap();
+        assert_eq!(values, [2]);
+
+        let requests = connector.requests();
+        assert_eq!(
+            requests[0].uri,
+            "/api/Dokument(24)/Fil?$skip=1&$format=json"
+        );
+        assert_eq!(requests[0].headers["Accept"], "application/json");
+        assert_eq!(requests[1].uri, "/api/Dokument(24)/Fil?$skip=1");
+        assert_eq!(requests[1].headers["Accept"], "application/json");
+        assert_eq!(requests[2].uri, "/api/Dokument(24)/Fil?$format=json");
+    }
+}
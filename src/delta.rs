@@ -0,0 +1,152 @@
+use crate::{Connector, DataSource, Error, ListRequest, Page};
+use log::warn;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+
+/// Storage for the delta links returned by the API, used by [`DataSource::sync_delta`] to fetch only the
+/// changes made to an entity set since it was last synchronized.
+///
+/// Delta links are stored per resource type. An implementation persisting them to a file or database
+/// allows synchronization to resume across restarts, while the implementation for [`HashMap`] keeps them in memory.
+pub trait DeltaStore {
+    /// Load the delta link saved for `resource_type`, if it has been synchronized before.
+    fn load(&self, resource_type: &str) -> Result<Option<String>, Error>;
+
+    /// Save the `delta_link` returned by the latest synchronization of `resource_type`.
+    fn save(&mut self, resource_type: &str, delta_link: &str) -> Result<(), Error>;
+}
+
+impl DeltaStore for HashMap<String, String> {
+    fn load(&self, resource_type: &str) -> Result<Option<String>, Error> {
+        Ok(self.get(resource_type).cloned())
+    }
+
+    fn save(&mut self, resource_type: &str, delta_link: &str) -> Result<(), Error> {
+        let _ = self.insert(resource_type.to_string(), delta_link.to_string());
+        Ok(())
+    }
+}
+
+impl<T> Page<T> {
+    /// Link returned on the last page of a collection when tracking changes, which retrieves the changes made
+    /// to the collection since. See [`DataSource::sync_delta`].
+    pub fn delta_link(&self) -> Option<&str> {
+        self.extra
+            .get("@odata.deltaLink")
+            .or_else(|| self.extra.get("odata.deltaLink"))
+            .and_then(serde_json::Value::as_str)
+    }
+}
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    /// Synchronize `resource_type`, returning the resources changed since the delta link saved in `store`,
+    /// and saving the new delta link once every page of changes has been fetched.
+    ///
+    /// If no delta link has been saved yet, every resource is returned, requesting change tracking using the
+    /// `Prefer: odata.track-changes` header. Deleted resources are returned as entries annotated with
+    /// `@odata.removed`, so `T` should be able to represent them, for example using an
+    /// [`OpenEntity`](`crate::OpenEntity`) or a [`serde_json::Value`].
+    ///
+    /// If the API doesn't return a delta link, because it doesn't support change tracking, a warning is logged
+    /// and the saved delta link is left untouched.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::DataSource;
+    /// # use serde_json::Value;
+    /// # use std::collections::HashMap;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "services.odata.org", Some(String::from("/V4/TripPinService"))).unwrap();
+    /// # tokio_test::block_on(async {
+    /// let mut store = HashMap::new();
+    /// let everything: Vec<Value> = datasource.sync_delta("People", &mut store).await.unwrap();
+    /// let changes: Vec<Value> = datasource.sync_delta("People", &mut store).await.unwrap();
+    /// # });
+    /// ```
+    pub async fn sync_delta<T, S>(
+        &self,
+        resource_type: &str,
+        store: &mut S,
+    ) -> Result<Vec<T>, Error>
+    where
+        T: DeserializeOwned,
+        S: DeltaStore,
+    {
+        let mut page = match store.load(resource_type)? {
+            Some(delta_link) => self.fetch_next::<T>(&delta_link).await?,
+            None => {
+                let mut request = ListRequest::new(resource_type);
+                request.builder = request.builder.prefer("odata.track-changes");
+                self.fetch_paged::<T>(request).await?
+            }
+        };
+
+        let mut changes = Vec::new();
+        loop {
+            changes.append(&mut page.value);
+            match page.next_link.take() {
+                Some(next_link) => page = self.fetch_next(&next_link).await?,
+                None => break,
+            }
+        }
+
+        match page.delta_link() {
+            Some(delta_link) => store.save(resource_type, delta_link)?,
+            None => warn!("no delta link returned for {resource_type}, changes can't be tracked"),
+        }
+
+        Ok(changes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DeltaStore;
+    use crate::mock;
+    use std::collections::HashMap;
+
+    #[tokio::test]
+    async fn test_sync_delta() {
+        let (datasource, connector) =
+            mock::datasource(|request| match request.uri.query().unwrap_or_default() {
+                query if query.contains("deltatoken=2") => mock::json(
+                    200,
+                    r#"{"value": [], "@odata.deltaLink": "People?$deltatoken=2"}"#,
+                ),
+                query if query.contains("deltatoken=1") => mock::json(
+                    200,
+                    r#"{"value": [3], "@odata.deltaLink": "People?$deltatoken=2"}"#,
+                ),
+                query if query.contains("skip") => mock::json(
+                    200,
+                    r#"{"value": [2], "@odata.deltaLink": "People?$deltatoken=1"}"#,
+                ),
+                _ => mock::json(
+                    200,
+                    r#"{"value": [1], "@odata.nextLink": "People?$skip=1"}"#,
+                ),
+            });
+
+        let mut store = HashMap::new();
+        let everything: Vec<u32> = datasource.sync_delta("People", &mut store).await.unwrap();
+        assert_eq!(everything, vec![1, 2]);
+        assert_eq!(
+            store.load("People").unwrap().as_deref(),
+            Some("People?$deltatoken=1")
+        );
+
+        let changes: Vec<u32> = datasource.sync_delta("People", &mut store).await.unwrap();
+        assert_eq!(changes, vec![3]);
+        let changes: Vec<u32> = datasource.sync_delta("People", &mut store).await.unwrap();
+        assert!(changes.is_empty());
+
+        let requests = connector.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].headers["Prefer"], "odata.track-changes");
+        assert_eq!(requests[2].uri, "/api/People?$deltatoken=1");
+    }
+}
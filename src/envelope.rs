@@ -0,0 +1,94 @@
+use crate::{json, Page};
+use serde::{de::DeserializeOwned, Deserialize};
+
+/// Shape of the JSON documents returned by the API. Use [`DataSource::with_json_envelope`](`crate::DataSource::with_json_envelope`) to set it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonEnvelope {
+    /// Entities are returned as-is, and lists of entities are wrapped in an object with a `value` property,
+    /// as done by OData 4.0, and OData 3.0 using the minimal (JSON light) format.
+    #[default]
+    Minimal,
+    /// Everything is wrapped in an object with a `d` property, and lists of entities are wrapped in an additional
+    /// `results` object, as done by the verbose format of OData 2.0 and 3.0.
+    Verbose,
+}
+
+#[derive(Deserialize)]
+struct Verbose<T> {
+    d: T,
+}
+
+#[derive(Deserialize)]
+struct VerboseCollection<T> {
+    results: Vec<T>,
+    #[serde(rename = "__count")]
+    count: Option<String>,
+    #[serde(rename = "__next")]
+    next_link: Option<String>,
+}
+
+impl JsonEnvelope {
+    pub(crate) fn entity<T>(self, content: &str) -> serde_json::Result<T>
+    where
+        T: DeserializeOwned,
+    {
+        match self {
+            JsonEnvelope::Minimal => json::from_str(content),
+            JsonEnvelope::Verbose => json::from_str::<Verbose<T>>(content).map(|verbose| verbose.d),
+        }
+    }
+
+    /// Unwrap an entity like [`JsonEnvelope::entity`], reading the document from `reader`.
+    pub(crate) fn entity_from_reader<T, R>(self, reader: R) -> serde_json::Result<T>
+    where
+        T: DeserializeOwned,
+        R: std::io::Read,
+    {
+        match self {
+            JsonEnvelope::Minimal => serde_json::from_reader(reader),
+            JsonEnvelope::Verbose => {
+                serde_json::from_reader::<_, Verbose<T>>(reader).map(|verbose| verbose.d)
+            }
+        }
+    }
+
+    pub(crate) fn page<T>(self, content: &str) -> serde_json::Result<Page<T>>
+    where
+        T: DeserializeOwned,
+    {
+        match self {
+            JsonEnvelope::Minimal => json::from_str(content),
+            JsonEnvelope::Verbose => {
+                json::from_str::<Verbose<VerboseCollection<T>>>(content).map(|verbose| Page {
+                    value: verbose.d.results,
+                    count: verbose.d.count,
+                    next_link: verbose.d.next_link,
+                    metadata: None,
+                    extra: Default::default(),
+                })
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JsonEnvelope;
+    use crate::Page;
+    use serde_json::Value;
+
+    #[test]
+    fn test_verbose_envelope() {
+        let entity: Value = JsonEnvelope::Verbose
+            .entity(r#"{"d": {"id": 24}}"#)
+            .unwrap();
+        assert_eq!(entity["id"], 24);
+
+        let page: Page<u32> = JsonEnvelope::Verbose
+            .page(r#"{"d": {"results": [1, 2], "__count": "10", "__next": "Dokument?$skip=2"}}"#)
+            .unwrap();
+        assert_eq!(page.value, vec![1, 2]);
+        assert_eq!(page.count.as_deref(), Some("10"));
+        assert_eq!(page.next_link.as_deref(), Some("Dokument?$skip=2"));
+    }
+}
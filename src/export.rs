@@ -0,0 +1,248 @@
+use crate::{Connector, DataSource, Error, ListRequest};
+use futures_util::TryStreamExt;
+use serde::{
+    de::{self, DeserializeOwned},
+    ser, Deserialize, Deserializer, Serialize,
+};
+use serde_json::Value;
+use std::fmt;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// Quote a CSV `field` if it contains a delimiter, quote or line break, doubling any quotes within it.
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+/// Represent a property `value` as a CSV field. Nested objects and arrays are written as JSON.
+fn csv_value(value: &Value) -> String {
+    match value {
+        Value::Null => String::new(),
+        Value::String(value) => csv_field(value),
+        value => csv_field(&value.to_string()),
+    }
+}
+
+/// Properties of a JSON object in the order they appear in, as opposed to the sorted keys of a [`serde_json::Map`].
+struct Properties(Vec<(String, Value)>);
+
+impl Properties {
+    fn get(&self, key: &str) -> Option<&Value> {
+        self.0
+            .iter()
+            .find_map(|(property, value)| (property == key).then_some(value))
+    }
+}
+
+impl<'de> Deserialize<'de> for Properties {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct PropertiesVisitor;
+
+        impl<'de> de::Visitor<'de> for PropertiesVisitor {
+            type Value = Properties;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("an object")
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, mut map: A) -> Result<Properties, A::Error> {
+                let mut properties = Vec::new();
+                while let Some(property) = map.next_entry()? {
+                    properties.push(property);
+                }
+                Ok(Properties(properties))
+            }
+        }
+
+        deserializer.deserialize_map(PropertiesVisitor)
+    }
+}
+
+/// Serialize `entity` into the properties of a JSON object, in the order `T` serializes them,
+/// failing if it is represented as anything else.
+fn into_object<T>(entity: &T) -> Result<Properties, Error>
+where
+    T: Serialize,
+{
+    let content = serde_json::to_string(entity).map_err(|e| Error::Serde(e, String::new()))?;
+    if !content.starts_with('{') {
+        return Err(Error::Serde(
+            ser::Error::custom("only entities serialized as objects can be exported as CSV"),
+            content,
+        ));
+    }
+    serde_json::from_str(&content).map_err(|e| Error::Serde(e, content))
+}
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    /// Export every resource of a [`ListRequest`] into `writer` as newline-delimited JSON, following the
+    /// `next_link` of each page like [`DataSource::stream`], and return the number of resources written.
+    ///
+    /// Each resource is deserialized into `T` and serialized again on a line of its own, so only the properties
+    /// of `T` are exported. Only a single page is held in memory at a time, but every line is written separately,
+    /// so wrap the `writer` in a [`tokio::io::BufWriter`] when writing to a file.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, ListRequest};
+    /// # use serde::{Deserialize, Serialize};
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Deserialize, Serialize)]
+    /// struct Dokument {
+    ///     id: usize,
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let file = tokio::fs::File::create("dokumenter.ndjson").await.unwrap();
+    /// let mut writer = tokio::io::BufWriter::new(file);
+    /// let exported = datasource
+    ///     .export_ndjson::<Dokument, _>(ListRequest::new("Dokument"), &mut writer)
+    ///     .await
+    ///     .unwrap();
+    /// # });
+    /// ```
+    pub async fn export_ndjson<T, W>(
+        &self,
+        request: ListRequest,
+        writer: &mut W,
+    ) -> Result<u64, Error>
+    where
+        T: Serialize + DeserializeOwned,
+        W: AsyncWrite + Unpin,
+    {
+        let mut resources = Box::pin(self.stream::<T>(request));
+        let mut exported = 0;
+        while let Some(resource) = resources.try_next().await? {
+            let mut line =
+                serde_json::to_vec(&resource).map_err(|e| Error::Serde(e, String::new()))?;
+            line.push(b'\n');
+            writer.write_all(&line).await?;
+            exported += 1;
+        }
+        writer.flush().await?;
+
+        Ok(exported)
+    }
+
+    /// Export every resource of a [`ListRequest`] into `writer` as CSV, like [`DataSource::export_ndjson`],
+    /// and return the number of resources written, excluding the header.
+    ///
+    /// The header row lists the properties of the first resource in the order they are serialized from `T`,
+    /// which is the declaration order of the fields of a derived struct, so `T` should be a flat struct. Properties missing from later resources are left empty, and nested objects or arrays are written as JSON.
+    /// Fails with [`Error::Serde`] if `T` is not serialized as an object. Nothing is written for an empty collection.
+    pub async fn export_csv<T, W>(&self, request: ListRequest, writer: &mut W) -> Result<u64, Error>
+    where
+        T: Serialize + DeserializeOwned,
+        W: AsyncWrite + Unpin,
+    {
+        let mut resources = Box::pin(self.stream::<T>(request));
+        let mut columns: Option<Vec<String>> = None;
+        let mut exported = 0;
+        while let Some(resource) = resources.try_next().await? {
+            let object = into_object(&resource)?;
+
+            let columns = match &columns {
+                Some(columns) => columns,
+                None => {
+                    let header: Vec<String> =
+                        object.0.iter().map(|(column, _)| column.clone()).collect();
+                    let line = header
+                        .iter()
+                        .map(|column| csv_field(column))
+                        .collect::<Vec<_>>()
+                        .join(",");
+                    writer.write_all(format!("{line}\r\n").as_bytes()).await?;
+                    columns.insert(header)
+                }
+            };
+
+            let line = columns
+                .iter()
+                .map(|column| object.get(column).map(csv_value).unwrap_or_default())
+                .collect::<Vec<_>>()
+                .join(",");
+            writer.write_all(format!("{line}\r\n").as_bytes()).await?;
+            exported += 1;
+        }
+        writer.flush().await?;
+
+        Ok(exported)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        mock::{self, MockConnector},
+        DataSource, ListRequest,
+    };
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Deserialize, Serialize)]
+    struct Dokument {
+        id: u32,
+        titel: String,
+        dato: Option<String>,
+    }
+
+    fn datasource() -> (DataSource<MockConnector>, MockConnector) {
+        mock::datasource(|request| match request.uri.query().unwrap_or_default() {
+            query if query.contains("skip") => mock::json(
+                200,
+                r#"{"value": [{"id": 3, "titel": "Notat, \"udkast\"", "dato": null}]}"#,
+            ),
+            _ => mock::json(
+                200,
+                r#"{"value": [{"id": 1, "titel": "Grund", "dato": "2020-04-15"}, {"id": 2, "titel": "Svar", "ekstra": true}], "odata.nextLink": "Dokument?$skip=2"}"#,
+            ),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_export_ndjson() {
+        let (datasource, _) = datasource();
+
+        let mut buffer = Vec::new();
+        let exported = datasource
+            .export_ndjson::<Dokument, _>(ListRequest::new("Dokument"), &mut buffer)
+            .await
+            .unwrap();
+        assert_eq!(exported, 3);
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            concat!(
+                r#"{"id":1,"titel":"Grund","dato":"2020-04-15"}"#,
+                "\n",
+                r#"{"id":2,"titel":"Svar","dato":null}"#,
+                "\n",
+                r#"{"id":3,"titel":"Notat, \"udkast\"","dato":null}"#,
+                "\n"
+            )
+        );
+    }
+
+    #[tokio::test]
+    async fn test_export_csv() {
+        let (datasource, _) = datasource();
+
+        let mut buffer = Vec::new();
+        let exported = datasource
+            .export_csv::<Dokument, _>(ListRequest::new("Dokument"), &mut buffer)
+            .await
+            .unwrap();
+        assert_eq!(exported, 3);
+        assert_eq!(
+            String::from_utf8(buffer).unwrap(),
+            "id,titel,dato\r\n1,Grund,2020-04-15\r\n2,Svar,\r\n3,\"Notat, \"\"udkast\"\"\",\r\n"
+        );
+    }
+}
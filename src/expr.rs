@@ -0,0 +1,886 @@
+use std::{
+    fmt::{self, Display},
+    ops::Not,
+};
+
+use crate::{Comparison, Literal};
+
+/// An OData expression, such as a property path, a literal value, a function call, or a combination of these
+/// using comparison and logical operators.
+///
+/// String slices are interpreted as property paths, so existing calls like `order_by("titel", ..)` keep working,
+/// while function calls can be constructed using [`FilterExpr::function`]. Complex expressions are most easily
+/// constructed using the [`odata_filter!`](`crate::odata_filter`) macro.
+/// ```rust
+/// use odata_simple_client::{Comparison, FilterExpr, Literal};
+///
+/// let expression = FilterExpr::function("tolower", [FilterExpr::field("titel")]);
+/// assert_eq!(expression.to_string(), "tolower(titel)");
+///
+/// let expression = FilterExpr::function("substring", [
+///     FilterExpr::field("titel"),
+///     FilterExpr::literal(1),
+/// ]);
+/// assert_eq!(expression.to_string(), "substring(titel,1)");
+///
+/// let expression = FilterExpr::field("id")
+///     .compare(Comparison::GreaterThan, FilterExpr::literal(10))
+///     .and(FilterExpr::function("startswith", [
+///         FilterExpr::field("titel"),
+///         FilterExpr::literal(Literal::string("Grund")),
+///     ]));
+/// assert_eq!(expression.to_string(), "id gt 10 and startswith(titel,'Grund')");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterExpr {
+    kind: Kind,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Kind {
+    Field(String),
+    Literal(Literal),
+    Function(String, Vec<FilterExpr>),
+    Binary(Box<FilterExpr>, Operator, Box<FilterExpr>),
+    Not(Box<FilterExpr>),
+    Group(Box<FilterExpr>),
+    In(Box<FilterExpr>, Vec<Literal>),
+    Has(Box<FilterExpr>, Literal),
+    Raw(String),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Operator {
+    Comparison(Comparison),
+    And,
+    Or,
+}
+
+impl Operator {
+    /// Binding strength of the operator, following the OData operator precedence
+    /// of comparisons over `and` over `or`.
+    fn precedence(self) -> u8 {
+        match self {
+            Operator::Comparison(_) => 3,
+            Operator::And => 2,
+            Operator::Or => 1,
+        }
+    }
+}
+
+impl Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Operator::Comparison(comparison) => f.write_str(comparison.as_odata_str()),
+            Operator::And => f.write_str("and"),
+            Operator::Or => f.write_str("or"),
+        }
+    }
+}
+
+impl FilterExpr {
+    /// A property path, such as `titel` or `DokumentAktør/Aktør/navn`.
+    pub fn field(path: &str) -> Self {
+        FilterExpr {
+            kind: Kind::Field(path.to_string()),
+        }
+    }
+
+    /// A literal value. See [`Literal`] for how values are represented.
+    pub fn literal<V>(value: V) -> Self
+    where
+        V: Into<Literal>,
+    {
+        FilterExpr {
+            kind: Kind::Literal(value.into()),
+        }
+    }
+
+    /// A call to the OData function `name`, such as `tolower` or `year`, with the given `arguments`.
+    pub fn function<A>(name: &str, arguments: A) -> Self
+    where
+        A: IntoIterator<Item = FilterExpr>,
+    {
+        FilterExpr {
+            kind: Kind::Function(name.to_string(), arguments.into_iter().collect()),
+        }
+    }
+
+    /// The year component of a date or time `value`, using the `year` function.
+    /// This and the other date and time component functions are usable in comparisons:
+    /// ```rust
+    /// use odata_simple_client::{Comparison, FilterExpr};
+    ///
+    /// let expression = FilterExpr::year("dato").compare(Comparison::Equal, FilterExpr::literal(2020));
+    /// assert_eq!(expression.to_string(), "year(dato) eq 2020");
+    /// ```
+    pub fn year<E>(value: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("year", [value.into()])
+    }
+
+    /// The month component of a date or time `value`, using the `month` function.
+    pub fn month<E>(value: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("month", [value.into()])
+    }
+
+    /// The day of the month component of a date or time `value`, using the `day` function.
+    pub fn day<E>(value: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("day", [value.into()])
+    }
+
+    /// The hour component of a date or time `value`, using the `hour` function.
+    pub fn hour<E>(value: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("hour", [value.into()])
+    }
+
+    /// The minute component of a date or time `value`, using the `minute` function.
+    pub fn minute<E>(value: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("minute", [value.into()])
+    }
+
+    /// The second component of a date or time `value`, using the `second` function.
+    pub fn second<E>(value: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("second", [value.into()])
+    }
+
+    /// The string `value` converted to lower case, using the `tolower` function.
+    /// This and the other string functions can be nested to normalize text before comparing it:
+    /// ```rust
+    /// use odata_simple_client::{Comparison, FilterExpr, Literal};
+    ///
+    /// let expression = FilterExpr::trim(FilterExpr::tolower("titel"))
+    ///     .compare(Comparison::Equal, Literal::string("grund"));
+    /// assert_eq!(expression.to_string(), "trim(tolower(titel)) eq 'grund'");
+    ///
+    /// let expression = FilterExpr::length("titel").compare(Comparison::GreaterThan, FilterExpr::literal(0));
+    /// assert_eq!(expression.to_string(), "length(titel) gt 0");
+    /// ```
+    pub fn tolower<E>(value: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("tolower", [value.into()])
+    }
+
+    /// The string `value` converted to upper case, using the `toupper` function.
+    pub fn toupper<E>(value: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("toupper", [value.into()])
+    }
+
+    /// The string `value` without leading and trailing whitespace, using the `trim` function.
+    pub fn trim<E>(value: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("trim", [value.into()])
+    }
+
+    /// The number of characters in the string `value`, using the `length` function.
+    pub fn length<E>(value: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("length", [value.into()])
+    }
+
+    /// The strings `first` and `second` joined together, using the `concat` function.
+    pub fn concat<A, B>(first: A, second: B) -> Self
+    where
+        A: Into<FilterExpr>,
+        B: Into<FilterExpr>,
+    {
+        FilterExpr::function("concat", [first.into(), second.into()])
+    }
+
+    /// The string `value` with every occurrence of `find` replaced by `replacement`, using the `replace` function.
+    ///
+    /// Only defined by OData 3.0 and earlier, so it may not be supported by OData 4.0 services.
+    pub fn replace<E, F, R>(value: E, find: F, replacement: R) -> Self
+    where
+        E: Into<FilterExpr>,
+        F: Into<FilterExpr>,
+        R: Into<FilterExpr>,
+    {
+        FilterExpr::function("replace", [value.into(), find.into(), replacement.into()])
+    }
+
+    /// Require the string `value` to begin with `prefix`, using the `startswith` function.
+    ///
+    /// Either argument can be any expression, so wrapping `value` in [`FilterExpr::tolower`] and passing a lower case
+    /// `prefix` matches case-insensitively, such as for a search box:
+    /// ```rust
+    /// use odata_simple_client::{FilterExpr, ListRequest, Literal};
+    ///
+    /// let search = "Grund";
+    /// let expression = FilterExpr::startswith(
+    ///     FilterExpr::tolower("titel"),
+    ///     Literal::string(&search.to_lowercase()),
+    /// );
+    /// assert_eq!(expression.to_string(), "startswith(tolower(titel),'grund')");
+    ///
+    /// let request = ListRequest::new("Dokument").filter_expr(expression);
+    /// ```
+    pub fn startswith<E, P>(value: E, prefix: P) -> Self
+    where
+        E: Into<FilterExpr>,
+        P: Into<FilterExpr>,
+    {
+        FilterExpr::function("startswith", [value.into(), prefix.into()])
+    }
+
+    /// Require the string `value` to end with `suffix`, using the `endswith` function.
+    /// See [`FilterExpr::startswith`] for matching case-insensitively.
+    pub fn endswith<E, S>(value: E, suffix: S) -> Self
+    where
+        E: Into<FilterExpr>,
+        S: Into<FilterExpr>,
+    {
+        FilterExpr::function("endswith", [value.into(), suffix.into()])
+    }
+
+    /// Require the string `value` to contain `substring`, using the `substringof` function of OData 2.0 and 3.0.
+    /// OData 4.0 replaced it with `contains`, taking its arguments in the opposite order.
+    /// ```rust
+    /// use odata_simple_client::{FilterExpr, Literal};
+    ///
+    /// let expression = FilterExpr::substringof(Literal::string("O'Brien"), "navn");
+    /// assert_eq!(expression.to_string(), "substringof('O''Brien',navn)");
+    /// ```
+    pub fn substringof<S, E>(substring: S, value: E) -> Self
+    where
+        S: Into<FilterExpr>,
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("substringof", [substring.into(), value.into()])
+    }
+
+    /// Begin a property path narrowed to the derived type `type_name` using the `cast` function,
+    /// for addressing properties only defined on a subtype in a polymorphic entity set.
+    /// ```rust
+    /// use odata_simple_client::{Comparison, FilterExpr};
+    ///
+    /// let expression = FilterExpr::cast("FT.Lovforslag")
+    ///     .field("nummer")
+    ///     .compare(Comparison::Equal, FilterExpr::literal(12));
+    /// assert_eq!(expression.to_string(), "cast(FT.Lovforslag)/nummer eq 12");
+    /// ```
+    pub fn cast(type_name: &str) -> Cast {
+        Cast {
+            type_name: type_name.to_string(),
+        }
+    }
+
+    /// An expression written out verbatim, such as a filter parsed from a query string.
+    ///
+    /// Its precedence is unknown, so it is wrapped in parentheses when used as an operand.
+    pub(crate) fn raw(expression: &str) -> Self {
+        FilterExpr {
+            kind: Kind::Raw(expression.to_string()),
+        }
+    }
+
+    fn binary(self, operator: Operator, other: FilterExpr) -> Self {
+        FilterExpr {
+            kind: Kind::Binary(Box::new(self), operator, Box::new(other)),
+        }
+    }
+
+    /// Compare this expression to `other`, such as `id gt 10`.
+    pub fn compare<E>(self, comparison: Comparison, other: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        self.binary(Operator::Comparison(comparison), other.into())
+    }
+
+    /// Require this expression to equal any of `values`, using the `in` operator introduced in OData 4.0,
+    /// such as `typeid in (5,7)`.
+    /// ```rust
+    /// use odata_simple_client::{FilterExpr, Literal, ODataVersion};
+    ///
+    /// let expression = FilterExpr::field("id").is_in([
+    ///     Literal::from(24),
+    ///     Literal::string("O'Brien"),
+    ///     Literal::decimal("19.95", ODataVersion::V4),
+    /// ]);
+    /// assert_eq!(expression.to_string(), "id in (24,'O''Brien',19.95)");
+    /// ```
+    pub fn is_in<I>(self, values: I) -> Self
+    where
+        I: IntoIterator<Item = Literal>,
+    {
+        FilterExpr {
+            kind: Kind::In(Box::new(self), values.into_iter().collect()),
+        }
+    }
+
+    /// Require the flag enum value of this expression to have the `flags` set, using the `has` operator
+    /// introduced in OData 4.0, such as `rights has Namespace.Permission'Read'`.
+    /// ```rust
+    /// use odata_simple_client::{FilterExpr, Literal};
+    ///
+    /// let expression = !FilterExpr::field("rights").has(Literal::enum_value("Namespace.Permission", "Read"));
+    /// assert_eq!(expression.to_string(), "not (rights has Namespace.Permission'Read')");
+    /// ```
+    pub fn has(self, flags: Literal) -> Self {
+        FilterExpr {
+            kind: Kind::Has(Box::new(self), flags),
+        }
+    }
+
+    /// Require both this expression and `other` to be true.
+    pub fn and(self, other: FilterExpr) -> Self {
+        self.binary(Operator::And, other)
+    }
+
+    /// Require either this expression or `other` to be true.
+    pub fn or(self, other: FilterExpr) -> Self {
+        self.binary(Operator::Or, other)
+    }
+
+    /// Write the expression as an operand of `parent`, wrapping it in parentheses only if
+    /// its operator binds less tightly, or if both are comparisons, which do not chain.
+    fn fmt_operand(&self, parent: Operator, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            Kind::Binary(_, operator, _)
+                if operator.precedence() < parent.precedence()
+                    || matches!(
+                        (operator, parent),
+                        (Operator::Comparison(_), Operator::Comparison(_))
+                    ) =>
+            {
+                write!(f, "({self})")
+            }
+            Kind::Raw(_) => write!(f, "({self})"),
+            _ => self.fmt(f),
+        }
+    }
+}
+
+impl Display for FilterExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.kind {
+            Kind::Field(path) => f.write_str(path),
+            Kind::Literal(literal) => literal.fmt(f),
+            Kind::Function(name, arguments) => {
+                write!(f, "{name}(")?;
+                for (index, argument) in arguments.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    argument.fmt(f)?;
+                }
+                f.write_str(")")
+            }
+            Kind::Binary(left, operator, right) => {
+                left.fmt_operand(*operator, f)?;
+                write!(f, " {operator} ")?;
+                right.fmt_operand(*operator, f)
+            }
+            Kind::Not(inner) => match inner.kind {
+                Kind::Binary(..) | Kind::In(..) | Kind::Has(..) | Kind::Raw(..) => {
+                    write!(f, "not ({inner})")
+                }
+                _ => write!(f, "not {inner}"),
+            },
+            Kind::Group(inner) => write!(f, "({inner})"),
+            Kind::Raw(expression) => f.write_str(expression),
+            Kind::In(inner, values) => {
+                write!(f, "{inner} in (")?;
+                for (index, value) in values.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(",")?;
+                    }
+                    value.fmt(f)?;
+                }
+                f.write_str(")")
+            }
+            Kind::Has(inner, flags) => write!(f, "{inner} has {flags}"),
+        }
+    }
+}
+
+/// A property path narrowed to a derived type, constructed using [`FilterExpr::cast`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Cast {
+    type_name: String,
+}
+
+impl Cast {
+    /// The property `path` of the derived type, such as `nummer` or `Sag/titel`.
+    pub fn field(self, path: &str) -> FilterExpr {
+        FilterExpr::field(&format!("cast({})/{path}", self.type_name))
+    }
+}
+
+/// A group of conditions combined using the same logical operator, constructed using [`ListRequest::filter_group`](`crate::ListRequest::filter_group`).
+///
+/// The conditions are combined using `and` unless [`FilterGroup::or`] is called, and the group is always wrapped
+/// in parentheses, so nested groups keep their precedence:
+/// ```rust
+/// use odata_simple_client::ListRequest;
+///
+/// let request = ListRequest::new("Dokument").filter_group(|group| {
+///     group
+///         .eq("typeid", 5)
+///         .group(|group| group.or().eq("statusid", 1).eq("statusid", 2))
+/// });
+/// assert_eq!(
+///     request.to_query_string("/api").unwrap(),
+///     "/api/Dokument?$filter=%28typeid%20eq%205%20and%20%28statusid%20eq%201%20or%20statusid%20eq%202%29%29"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterGroup {
+    operator: Operator,
+    clauses: Vec<FilterExpr>,
+}
+
+impl Default for FilterGroup {
+    fn default() -> Self {
+        FilterGroup {
+            operator: Operator::And,
+            clauses: Vec::new(),
+        }
+    }
+}
+
+impl FilterGroup {
+    /// Require every condition of the group to be true. This is the default.
+    pub fn and(mut self) -> Self {
+        self.operator = Operator::And;
+        self
+    }
+
+    /// Require any condition of the group to be true.
+    pub fn or(mut self) -> Self {
+        self.operator = Operator::Or;
+        self
+    }
+
+    /// Add the condition `field eq value` to the group.
+    pub fn eq<V>(self, field: &str, value: V) -> Self
+    where
+        V: Into<Literal>,
+    {
+        self.compare(field, Comparison::Equal, value)
+    }
+
+    /// Add a condition comparing `field` to `value` to the group.
+    pub fn compare<V>(self, field: &str, comparison: Comparison, value: V) -> Self
+    where
+        V: Into<Literal>,
+    {
+        self.expr(FilterExpr::field(field).compare(comparison, FilterExpr::literal(value)))
+    }
+
+    /// Add an arbitrary `expression` to the group.
+    pub fn expr(mut self, expression: FilterExpr) -> Self {
+        self.clauses.push(expression);
+        self
+    }
+
+    /// Add a nested group to the group, constructed by `f`. Empty groups are ignored.
+    pub fn group<F>(self, f: F) -> Self
+    where
+        F: FnOnce(FilterGroup) -> FilterGroup,
+    {
+        match f(FilterGroup::default()).into_expr() {
+            Some(expression) => self.expr(expression),
+            None => self,
+        }
+    }
+
+    /// Combine the conditions into a single parenthesized expression, or `None` if the group is empty.
+    pub fn into_expr(self) -> Option<FilterExpr> {
+        let operator = self.operator;
+        self.clauses
+            .into_iter()
+            .reduce(|left, right| left.binary(operator, right))
+            .map(|expression| FilterExpr {
+                kind: Kind::Group(Box::new(expression)),
+            })
+    }
+}
+
+/// Negate the expression using the `not` operator, such as `not startswith(titel,'Grund')`.
+/// ```rust
+/// use odata_simple_client::{FilterExpr, Literal};
+///
+/// let expression = !FilterExpr::function("startswith", [
+///     FilterExpr::field("titel"),
+///     FilterExpr::literal(Literal::string("Grund")),
+/// ]);
+/// assert_eq!(expression.to_string(), "not startswith(titel,'Grund')");
+/// ```
+impl Not for FilterExpr {
+    type Output = FilterExpr;
+
+    fn not(self) -> Self::Output {
+        FilterExpr {
+            kind: Kind::Not(Box::new(self)),
+        }
+    }
+}
+
+impl From<&str> for FilterExpr {
+    fn from(path: &str) -> Self {
+        FilterExpr::field(path)
+    }
+}
+
+impl From<Literal> for FilterExpr {
+    fn from(literal: Literal) -> Self {
+        FilterExpr::literal(literal)
+    }
+}
+
+/// Conversion of values used in the [`odata_filter!`](`crate::odata_filter`) macro into operands.
+///
+/// Unlike [`From<&str>`](`FilterExpr::from`), strings are converted into string literals.
+#[doc(hidden)]
+pub trait Operand {
+    fn into_operand(self) -> FilterExpr;
+}
+
+impl Operand for FilterExpr {
+    fn into_operand(self) -> FilterExpr {
+        self
+    }
+}
+
+impl Operand for Literal {
+    fn into_operand(self) -> FilterExpr {
+        FilterExpr::literal(self)
+    }
+}
+
+impl Operand for &str {
+    fn into_operand(self) -> FilterExpr {
+        FilterExpr::literal(Literal::string(self))
+    }
+}
+
+impl Operand for &String {
+    fn into_operand(self) -> FilterExpr {
+        FilterExpr::literal(Literal::string(self))
+    }
+}
+
+impl Operand for String {
+    fn into_operand(self) -> FilterExpr {
+        FilterExpr::literal(Literal::string(&self))
+    }
+}
+
+macro_rules! literal_operand {
+    ($($ty:ty),*) => {
+        $(
+            impl Operand for $ty {
+                fn into_operand(self) -> FilterExpr {
+                    FilterExpr::literal(self)
+                }
+            }
+        )*
+    };
+}
+
+literal_operand!(bool, i8, i16, i32, i64, u8, u16, u32, u64, usize);
+
+/// Build a [`FilterExpr`] from an expression written in near-OData syntax, checked at compile time.
+///
+/// Supported are property paths (`DokumentAktør/Aktør/navn`), literals, function calls, comparisons using either
+/// Rust (`==`, `!=`, `>`, `>=`, `<`, `<=`) or OData (`eq`, `ne`, `gt`, `ge`, `lt`, `le`) operators,
+/// the logical operators `not`, `and` and `or` (in order of precedence), and parentheses for grouping.
+/// A `not` negates the entire comparison following it.
+///
+/// String literals are quoted and escaped correctly. Values from the surrounding scope can be used by wrapping
+/// them in braces, such as `{title}`.
+/// ```rust
+/// use odata_simple_client::odata_filter;
+///
+/// let expression = odata_filter!(year(dato) >= 2020 and startswith(titel, "Grund"));
+/// assert_eq!(expression.to_string(), "year(dato) ge 2020 and startswith(titel,'Grund')");
+///
+/// let name = "O'Brien";
+/// let expression = odata_filter!((typeid eq 5 or typeid eq 7) and Aktør/navn == {name});
+/// assert_eq!(expression.to_string(), "(typeid eq 5 or typeid eq 7) and Aktør/navn eq 'O''Brien'");
+/// ```
+#[macro_export]
+macro_rules! odata_filter {
+    // Split on `or`, which has the lowest precedence.
+    (@or () [$($cur:tt)+] or $($rest:tt)+) => {
+        $crate::odata_filter!(@or ($crate::odata_filter!(@and () [] $($cur)+)) [] $($rest)+)
+    };
+    (@or () [$($cur:tt)+]) => { $crate::odata_filter!(@and () [] $($cur)+) };
+    (@or () [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::odata_filter!(@or () [$($cur)* $next] $($rest)*)
+    };
+    (@or ($acc:expr) [$($cur:tt)+] or $($rest:tt)+) => {
+        $crate::odata_filter!(@or ($acc.or($crate::odata_filter!(@and () [] $($cur)+))) [] $($rest)+)
+    };
+    (@or ($acc:expr) [$($cur:tt)+]) => { $acc.or($crate::odata_filter!(@and () [] $($cur)+)) };
+    (@or ($acc:expr) [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::odata_filter!(@or ($acc) [$($cur)* $next] $($rest)*)
+    };
+
+    // Split on `and`.
+    (@and () [$($cur:tt)+] and $($rest:tt)+) => {
+        $crate::odata_filter!(@and ($crate::odata_filter!(@leaf $($cur)+)) [] $($rest)+)
+    };
+    (@and () [$($cur:tt)+]) => { $crate::odata_filter!(@leaf $($cur)+) };
+    (@and () [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::odata_filter!(@and () [$($cur)* $next] $($rest)*)
+    };
+    (@and ($acc:expr) [$($cur:tt)+] and $($rest:tt)+) => {
+        $crate::odata_filter!(@and ($acc.and($crate::odata_filter!(@leaf $($cur)+))) [] $($rest)+)
+    };
+    (@and ($acc:expr) [$($cur:tt)+]) => { $acc.and($crate::odata_filter!(@leaf $($cur)+)) };
+    (@and ($acc:expr) [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::odata_filter!(@and ($acc) [$($cur)* $next] $($rest)*)
+    };
+
+    // A negation, a parenthesized group, or a comparison.
+    (@leaf not $($rest:tt)+) => { ::core::ops::Not::not($crate::odata_filter!(@leaf $($rest)+)) };
+    (@leaf ($($inner:tt)+)) => { $crate::odata_filter!(@or () [] $($inner)+) };
+    (@leaf $($tokens:tt)+) => { $crate::odata_filter!(@cmp [] $($tokens)+) };
+
+    // Split on the comparison operator, if any.
+    (@cmp [$($lhs:tt)+] == $($rhs:tt)+) => { $crate::odata_filter!(@compare Equal [$($lhs)+] $($rhs)+) };
+    (@cmp [$($lhs:tt)+] eq $($rhs:tt)+) => { $crate::odata_filter!(@compare Equal [$($lhs)+] $($rhs)+) };
+    (@cmp [$($lhs:tt)+] != $($rhs:tt)+) => { $crate::odata_filter!(@compare NotEqual [$($lhs)+] $($rhs)+) };
+    (@cmp [$($lhs:tt)+] ne $($rhs:tt)+) => { $crate::odata_filter!(@compare NotEqual [$($lhs)+] $($rhs)+) };
+    (@cmp [$($lhs:tt)+] > $($rhs:tt)+) => { $crate::odata_filter!(@compare GreaterThan [$($lhs)+] $($rhs)+) };
+    (@cmp [$($lhs:tt)+] gt $($rhs:tt)+) => { $crate::odata_filter!(@compare GreaterThan [$($lhs)+] $($rhs)+) };
+    (@cmp [$($lhs:tt)+] >= $($rhs:tt)+) => { $crate::odata_filter!(@compare GreaterOrEqual [$($lhs)+] $($rhs)+) };
+    (@cmp [$($lhs:tt)+] ge $($rhs:tt)+) => { $crate::odata_filter!(@compare GreaterOrEqual [$($lhs)+] $($rhs)+) };
+    (@cmp [$($lhs:tt)+] < $($rhs:tt)+) => { $crate::odata_filter!(@compare LessThan [$($lhs)+] $($rhs)+) };
+    (@cmp [$($lhs:tt)+] lt $($rhs:tt)+) => { $crate::odata_filter!(@compare LessThan [$($lhs)+] $($rhs)+) };
+    (@cmp [$($lhs:tt)+] <= $($rhs:tt)+) => { $crate::odata_filter!(@compare LessOrEqual [$($lhs)+] $($rhs)+) };
+    (@cmp [$($lhs:tt)+] le $($rhs:tt)+) => { $crate::odata_filter!(@compare LessOrEqual [$($lhs)+] $($rhs)+) };
+    (@cmp [$($operand:tt)+]) => { $crate::odata_filter!(@operand $($operand)+) };
+    (@cmp [$($lhs:tt)*] $next:tt $($rest:tt)*) => { $crate::odata_filter!(@cmp [$($lhs)* $next] $($rest)*) };
+
+    (@compare $comparison:ident [$($lhs:tt)+] $($rhs:tt)+) => {
+        $crate::odata_filter!(@operand $($lhs)+).compare(
+            $crate::Comparison::$comparison,
+            $crate::odata_filter!(@operand $($rhs)+),
+        )
+    };
+
+    // Operands.
+    (@operand { $value:expr }) => { $crate::__private::Operand::into_operand($value) };
+    (@operand $value:literal) => { $crate::__private::Operand::into_operand($value) };
+    (@operand $function:ident ($($arguments:tt)*)) => {
+        $crate::FilterExpr::function(
+            stringify!($function),
+            $crate::odata_filter!(@arguments [] [] $($arguments)*),
+        )
+    };
+    (@operand $first:ident $(/ $rest:ident)*) => {
+        $crate::FilterExpr::field(concat!(stringify!($first) $(, "/", stringify!($rest))*))
+    };
+
+    // Split function arguments on commas.
+    (@arguments [$($done:expr),*] []) => { [$($done),*] };
+    (@arguments [$($done:expr),*] [$($cur:tt)+]) => {
+        [$($done,)* $crate::odata_filter!(@operand $($cur)+)]
+    };
+    (@arguments [$($done:expr),*] [$($cur:tt)+] , $($rest:tt)*) => {
+        $crate::odata_filter!(@arguments [$($done,)* $crate::odata_filter!(@operand $($cur)+)] [] $($rest)*)
+    };
+    (@arguments [$($done:expr),*] [$($cur:tt)*] $next:tt $($rest:tt)*) => {
+        $crate::odata_filter!(@arguments [$($done),*] [$($cur)* $next] $($rest)*)
+    };
+
+    ($($tokens:tt)+) => { $crate::odata_filter!(@or () [] $($tokens)+) };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FilterGroup;
+    use crate::{Comparison, FilterExpr, ListRequest, Literal};
+
+    #[test]
+    fn test_macro_precedence() {
+        let expression = odata_filter!(a eq 1 or b eq 2 and c eq 3);
+        assert_eq!(expression.to_string(), "a eq 1 or b eq 2 and c eq 3");
+
+        let expression = odata_filter!((a eq 1 or b eq 2) and c eq 3);
+        assert_eq!(expression.to_string(), "(a eq 1 or b eq 2) and c eq 3");
+
+        let expression = odata_filter!(not a eq 1 and not startswith(b, "x") or not (c eq 3));
+        assert_eq!(
+            expression.to_string(),
+            "not (a eq 1) and not startswith(b,'x') or not (c eq 3)"
+        );
+
+        let expression = odata_filter!(a == 1 and b != 2 and c > 3);
+        assert_eq!(expression.to_string(), "a eq 1 and b ne 2 and c gt 3");
+    }
+
+    #[test]
+    fn test_minimal_parentheses() {
+        let eq = |field: &str, value: i32| {
+            FilterExpr::field(field).compare(Comparison::Equal, Literal::from(value))
+        };
+
+        let expression = eq("a", 1).and(eq("b", 2)).or(eq("c", 3));
+        assert_eq!(expression.to_string(), "a eq 1 and b eq 2 or c eq 3");
+
+        let expression = eq("a", 1).and(eq("b", 2).or(eq("c", 3)));
+        assert_eq!(expression.to_string(), "a eq 1 and (b eq 2 or c eq 3)");
+
+        let expression = eq("a", 1).or(eq("b", 2)).and(eq("c", 3).or(eq("d", 4)));
+        assert_eq!(
+            expression.to_string(),
+            "(a eq 1 or b eq 2) and (c eq 3 or d eq 4)"
+        );
+
+        let expression = eq("a", 1).or(eq("b", 2).or(eq("c", 3)));
+        assert_eq!(expression.to_string(), "a eq 1 or b eq 2 or c eq 3");
+
+        let expression = eq("a", 1).compare(Comparison::Equal, Literal::from(true));
+        assert_eq!(expression.to_string(), "(a eq 1) eq true");
+
+        let expression = FilterExpr::field("a")
+            .and(FilterExpr::field("b").or(FilterExpr::field("c")))
+            .compare(Comparison::NotEqual, Literal::from(false));
+        assert_eq!(expression.to_string(), "(a and (b or c)) ne false");
+    }
+
+    #[test]
+    fn test_string_functions() {
+        let expression = FilterExpr::concat(
+            FilterExpr::toupper("fornavn"),
+            FilterExpr::replace("efternavn", Literal::string("-"), Literal::string(" ")),
+        )
+        .compare(Comparison::Equal, Literal::string("JENS Hansen Jensen"));
+        assert_eq!(
+            expression.to_string(),
+            "concat(toupper(fornavn),replace(efternavn,'-',' ')) eq 'JENS Hansen Jensen'"
+        );
+        assert_eq!(
+            FilterExpr::trim(FilterExpr::tolower("titel")),
+            odata_filter!(trim(tolower(titel)))
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive_matching() {
+        let expression =
+            FilterExpr::startswith(FilterExpr::tolower("titel"), Literal::string("grund"));
+        assert_eq!(expression.to_string(), "startswith(tolower(titel),'grund')");
+        assert_eq!(
+            expression,
+            odata_filter!(startswith(tolower(titel), "grund"))
+        );
+
+        let expression =
+            FilterExpr::endswith(FilterExpr::toupper("efternavn"), Literal::string("SEN"))
+                .and(FilterExpr::startswith("fornavn", Literal::string("L")));
+        assert_eq!(
+            expression.to_string(),
+            "endswith(toupper(efternavn),'SEN') and startswith(fornavn,'L')"
+        );
+    }
+
+    #[test]
+    fn test_substringof() {
+        let request = ListRequest::new("Person")
+            .filter_expr(FilterExpr::substringof(Literal::string("O'Brien"), "navn"));
+        assert_eq!(
+            request.to_query_string("/api").unwrap(),
+            "/api/Person?$filter=substringof%28%27O%27%27Brien%27%2Cnavn%29"
+        );
+    }
+
+    #[test]
+    fn test_filter_group() {
+        let group = FilterGroup::default()
+            .or()
+            .eq("a", 1)
+            .group(|group| group.eq("b", 2).eq("c", 3))
+            .group(|group| group);
+        assert_eq!(
+            group.into_expr().unwrap().to_string(),
+            "(a eq 1 or (b eq 2 and c eq 3))"
+        );
+        assert_eq!(
+            (!FilterGroup::default().eq("a", 1).into_expr().unwrap()).to_string(),
+            "not (a eq 1)"
+        );
+        assert_eq!(FilterGroup::default().into_expr(), None);
+    }
+
+    #[test]
+    fn test_in_operator() {
+        let expression = FilterExpr::field("typeid")
+            .is_in([Literal::from(5), Literal::from(7)])
+            .and(FilterExpr::field("statusid").compare(Comparison::Equal, Literal::from(1)));
+        assert_eq!(expression.to_string(), "typeid in (5,7) and statusid eq 1");
+        assert_eq!(
+            (!FilterExpr::field("typeid").is_in([Literal::from(5)])).to_string(),
+            "not (typeid in (5))"
+        );
+    }
+
+    #[test]
+    fn test_has_operator() {
+        let read = Literal::enum_value("Namespace.Permission", "Read");
+        let expression = FilterExpr::field("typeid")
+            .compare(Comparison::Equal, Literal::from(5))
+            .and(FilterExpr::field("rights").has(read.clone()));
+        assert_eq!(
+            expression.to_string(),
+            "typeid eq 5 and rights has Namespace.Permission'Read'"
+        );
+        assert_eq!(
+            (!FilterExpr::field("rights").has(read)).to_string(),
+            "not (rights has Namespace.Permission'Read')"
+        );
+    }
+
+    #[test]
+    fn test_macro_operands() {
+        let expression =
+            odata_filter!(trim(tolower(titel)) == "grund" and count < -5 and aktiv == true);
+        assert_eq!(
+            expression.to_string(),
+            "trim(tolower(titel)) eq 'grund' and count lt -5 and aktiv eq true"
+        );
+
+        let name = String::from("O'Brien");
+        let expected = FilterExpr::field("Aktør/navn")
+            .compare(Comparison::Equal, Literal::string("O'Brien"))
+            .or(FilterExpr::function(
+                "endswith",
+                ["navn".into(), Literal::string("en").into()],
+            ));
+        assert_eq!(
+            odata_filter!(Aktør/navn eq {&name} or endswith(navn, "en")),
+            expected
+        );
+    }
+}
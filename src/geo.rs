@@ -0,0 +1,105 @@
+//! Spatial values for use in filters, such as the [`Point`]s compared using [`FilterExpr::geo_distance`].
+use crate::{FilterExpr, Literal};
+use std::fmt::{self, Display};
+
+/// A geographic point, for use in spatial filters such as [`FilterExpr::geo_distance`].
+///
+/// The point is written as a `geography` literal in the Well-Known Text representation,
+/// which lists the longitude before the latitude, prefixed by its spatial reference system as required by OData.
+/// Points use WGS 84 (`SRID=4326`) unless another is set using [`Point::with_srid`].
+/// ```rust
+/// use odata_simple_client::{geo::Point, Literal};
+///
+/// let christiansborg = Point::new(12.5792, 55.6761);
+/// assert_eq!(christiansborg.to_string(), "POINT(12.5792 55.6761)");
+/// assert_eq!(
+///     Literal::from(christiansborg).to_string(),
+///     "geography'SRID=4326;POINT(12.5792 55.6761)'"
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    /// Longitude in degrees, east of the prime meridian.
+    pub longitude: f64,
+    /// Latitude in degrees, north of the equator.
+    pub latitude: f64,
+    /// Identifier of the spatial reference system of the coordinates.
+    pub srid: u32,
+}
+
+impl Point {
+    /// Construct a point from its `longitude` and `latitude` in degrees, using WGS 84.
+    pub fn new(longitude: f64, latitude: f64) -> Self {
+        Point {
+            longitude,
+            latitude,
+            srid: 4326,
+        }
+    }
+
+    /// Use the spatial reference system identified by `srid` instead of WGS 84 (4326).
+    pub fn with_srid(mut self, srid: u32) -> Self {
+        self.srid = srid;
+        self
+    }
+}
+
+impl Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "POINT({} {})", self.longitude, self.latitude)
+    }
+}
+
+impl From<Point> for Literal {
+    fn from(point: Point) -> Self {
+        Literal::raw(&format!("geography'SRID={};{point}'", point.srid))
+    }
+}
+
+impl FilterExpr {
+    /// The distance between the geographic `value` and `point`, using the `geo.distance` function
+    /// introduced in OData 4.0. The distance is measured in meters by most services.
+    /// ```rust
+    /// use odata_simple_client::{geo::Point, Comparison, FilterExpr, Literal};
+    ///
+    /// let expression = FilterExpr::geo_distance("Location", Point::new(12.5792, 55.6761))
+    ///     .compare(Comparison::LessThan, Literal::from(1000));
+    /// assert_eq!(
+    ///     expression.to_string(),
+    ///     "geo.distance(Location,geography'SRID=4326;POINT(12.5792 55.6761)') lt 1000"
+    /// );
+    /// ```
+    pub fn geo_distance<E>(value: E, point: Point) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        FilterExpr::function("geo.distance", [value.into(), FilterExpr::literal(point)])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Point;
+    use crate::{Comparison, FilterExpr, ListRequest, Literal};
+
+    #[test]
+    fn test_geo_distance() {
+        assert_eq!(
+            Literal::from(Point::new(-122.1, 47.6)).to_string(),
+            "geography'SRID=4326;POINT(-122.1 47.6)'"
+        );
+        assert_eq!(
+            Literal::from(Point::new(-122.1, 47.6).with_srid(4269)).to_string(),
+            "geography'SRID=4269;POINT(-122.1 47.6)'"
+        );
+
+        let request = ListRequest::new("Airports").filter_expr(
+            FilterExpr::geo_distance("Location", Point::new(-122.1, 47.6))
+                .compare(Comparison::LessThan, Literal::from(1000)),
+        );
+        assert_eq!(
+            request.to_query_string("/api").unwrap(),
+            "/api/Airports?$filter=geo.distance%28Location%2Cgeography%27SRID%3D4326%3BPOINT%28-122.1%2047.6%29%27%29%20lt%201000"
+        );
+    }
+}
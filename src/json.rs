@@ -0,0 +1,54 @@
+use serde::de::DeserializeOwned;
+
+/// Backend used for parsing the JSON documents returned by the API.
+trait Parser {
+    fn parse<T: DeserializeOwned>(content: &str) -> serde_json::Result<T>;
+}
+
+#[cfg(not(feature = "simd-json"))]
+struct SerdeJson;
+
+#[cfg(not(feature = "simd-json"))]
+impl Parser for SerdeJson {
+    fn parse<T: DeserializeOwned>(content: &str) -> serde_json::Result<T> {
+        serde_json::from_str(content)
+    }
+}
+
+#[cfg(feature = "simd-json")]
+struct SimdJson;
+
+#[cfg(feature = "simd-json")]
+impl Parser for SimdJson {
+    fn parse<T: DeserializeOwned>(content: &str) -> serde_json::Result<T> {
+        // The document is parsed in place, so the content is kept intact for error reporting.
+        let mut buffer = content.as_bytes().to_vec();
+        simd_json::from_slice(&mut buffer).map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(not(feature = "simd-json"))]
+type Backend = SerdeJson;
+#[cfg(feature = "simd-json")]
+type Backend = SimdJson;
+
+/// Parse `content` using the JSON backend selected by the enabled features.
+///
+/// Errors are always reported as a [`serde_json::Error`], so the public [`Error`](`crate::Error`) is unaffected.
+pub(crate) fn from_str<T: DeserializeOwned>(content: &str) -> serde_json::Result<T> {
+    Backend::parse(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::Value;
+
+    #[test]
+    fn test_parse() {
+        let value: Value = super::from_str(r#"{"value": [1, 2], "titel": "Grund"}"#).unwrap();
+        assert_eq!(value["value"][1], 2);
+        assert_eq!(value["titel"], "Grund");
+
+        assert!(super::from_str::<Value>(r#"{"value": "#).is_err());
+    }
+}
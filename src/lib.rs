@@ -44,7 +44,8 @@
     overflowing_literals,
     path_statements,
     patterns_in_fns_without_body,
-    private_in_public,
+    private_interfaces,
+    private_bounds,
     unconditional_recursion,
     unused,
     unused_allocation,
@@ -65,27 +66,190 @@
 #[cfg(feature = "rate-limiting")]
 mod ratelimiting;
 #[cfg(feature = "rate-limiting")]
-pub use ratelimiting::RateLimitedDataSource;
+pub use ratelimiting::{Jitter, Quota, RateLimitedDataSource};
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
+mod annotated;
+pub use annotated::Annotated;
+
+pub mod de;
+
+mod conditional;
+pub use conditional::ResponseMeta;
+
+mod create;
+pub use create::CreateRequest;
+
+mod deferred;
+pub use deferred::Deferred;
+
+mod delta;
+pub use delta::DeltaStore;
+
+mod envelope;
+pub use envelope::JsonEnvelope;
+
+mod export;
+
+mod expr;
+pub use expr::{Cast, FilterExpr, FilterGroup};
+
+#[doc(hidden)]
+pub mod __private {
+    pub use crate::expr::Operand;
+}
+
+pub mod geo;
+
+mod json;
+
+mod literal;
+pub use literal::{encode_literal, Literal};
+
+mod open;
+pub use open::OpenEntity;
+
+mod operation;
+pub use operation::{AsyncResponse, Monitor};
+
+mod preference;
+pub use preference::AppliedPreferences;
+
+mod raw;
+pub use raw::RawRequest;
+
+mod reference;
+
+mod spec;
+pub use spec::QuerySpec;
+
+mod stream;
+pub use stream::PagingStyle;
+
+#[cfg(feature = "channel")]
+mod channel;
+
+#[cfg(feature = "encoding")]
+mod charset;
+#[cfg(feature = "encoding")]
+use charset::decode_body;
+
+#[cfg(feature = "openssl")]
+mod tls;
+#[cfg(feature = "openssl")]
+pub use tls::StaticResolver;
+
+#[cfg(feature = "xml")]
+mod xml;
 
 mod path;
-use path::PathBuilder;
-pub use path::{Comparison, Direction, Format, InlineCount};
+use path::{encode_path, PathBuilder};
+
+#[cfg(test)]
+mod mock;
+pub use path::{
+    Comparison, Direction, ExpandOption, Format, InlineCount, NamingStrategy, ParameterOrder,
+    ResourceType,
+};
 
+use futures_util::StreamExt;
 use hyper::{
-    body::Buf,
+    body::{Buf, HttpBody},
     client::{connect::Connect, Client},
-    http::uri::{Authority, InvalidUri, Scheme},
-    Body, Response, Uri,
+    http::{request, uri::InvalidUri},
+    Body, Method, Request, Response, StatusCode,
 };
-use log::debug;
+
+// Re-exported so downstream crates can configure a DataSource without depending on a matching version of hyper.
+pub use hyper::{
+    header::{HeaderMap, HeaderName, HeaderValue},
+    http::uri::{Authority, Scheme},
+    Uri,
+};
+use log::{debug, warn};
 use serde::{de::DeserializeOwned, Deserialize};
-use std::{convert::TryFrom, io::Read};
+use std::{borrow::Cow, collections::HashMap, convert::TryFrom, fmt, sync::Arc, time::Duration};
 use thiserror::Error;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
 
 /// Umbrella trait covering all the traits required of a [`Client`] for a [`DataSource`] to work.
 pub trait Connector: Connect + Clone + Send + Sync + 'static {}
 impl<T: Connect + Clone + Send + Sync + 'static> Connector for T {}
 
+/// Version of the OData protocol spoken by the API. Use [`DataSource::with_odata_version`] to set it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ODataVersion {
+    /// OData 3.0, negotiated using the `DataServiceVersion` and `MaxDataServiceVersion` headers.
+    V3,
+    /// OData 4.0, negotiated using the `OData-Version` and `OData-MaxVersion` headers.
+    V4,
+}
+
+/// How [`DataSource::fetch`] and [`DataSource::fetch_paged`] ask the API to respond with JSON.
+/// Use [`DataSource::with_format_negotiation`] to set it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FormatNegotiation {
+    /// Use both the `$format=json` query parameter and an `Accept: application/json` header.
+    #[default]
+    Both,
+    /// Only use the `$format=json` query parameter.
+    QueryParameter,
+    /// Only use the `Accept: application/json` header, for servers rejecting unknown query parameters.
+    AcceptHeader,
+}
+
+/// Whether [`DataSource::fetch`] and [`DataSource::fetch_paged`] follow redirects returned by the API.
+/// Use [`DataSource::with_redirect_policy`] to set it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RedirectPolicy {
+    /// Redirects are returned as-is.
+    #[default]
+    None,
+    /// Follow up to the given number of consecutive redirects, after which the last redirect is returned as-is.
+    Limited(usize),
+}
+
+impl ODataVersion {
+    fn headers(&self) -> [(&'static str, &'static str); 2] {
+        match self {
+            ODataVersion::V3 => [
+                ("DataServiceVersion", "3.0"),
+                ("MaxDataServiceVersion", "3.0"),
+            ],
+            ODataVersion::V4 => [("OData-Version", "4.0"), ("OData-MaxVersion", "4.0")],
+        }
+    }
+}
+
+/// Header attached to every request with a freshly generated value, see [`DataSource::with_correlation_header`].
+#[derive(Clone)]
+struct CorrelationHeader {
+    name: String,
+    generator: Arc<dyn Fn() -> String + Send + Sync>,
+}
+
+impl fmt::Debug for CorrelationHeader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CorrelationHeader")
+            .field("name", &self.name)
+            .finish_non_exhaustive()
+    }
+}
+
+type Preprocess = dyn Fn(&[u8]) -> Cow<'_, [u8]> + Send + Sync;
+
+/// Transformation applied to response bodies before parsing, see [`DataSource::with_body_preprocessor`].
+#[derive(Clone)]
+struct BodyPreprocessor(Arc<Preprocess>);
+
+impl fmt::Debug for BodyPreprocessor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BodyPreprocessor").finish_non_exhaustive()
+    }
+}
+
 /// Represents a target OData API.
 #[derive(Clone, Debug)]
 pub struct DataSource<C> {
@@ -93,10 +257,41 @@ pub struct DataSource<C> {
     authority: Authority,
     base_path: String,
     scheme: Scheme,
+    default_inline_count: Option<InlineCount>,
+    parameter_order: ParameterOrder,
+    odata_version: Option<ODataVersion>,
+    api_version: Option<String>,
+    host_header: Option<String>,
+    strict_next_links: bool,
+    envelope: JsonEnvelope,
+    accept_charset: Option<String>,
+    accept_types: Option<String>,
+    format_negotiation: FormatNegotiation,
+    max_response_size: Option<u64>,
+    max_top: Option<u32>,
+    strict_max_top: bool,
+    redirect_policy: RedirectPolicy,
+    query_via_post: Option<usize>,
+    query_length_limit: Option<usize>,
+    keep_alive: Option<bool>,
+    entity_sets: Vec<String>,
+    default_select: Vec<String>,
+    extend_default_select: bool,
+    correlation_header: Option<CorrelationHeader>,
+    bearer_token: Option<String>,
+    headers: HeaderMap,
+    retriable_statuses: Vec<StatusCode>,
+    paging_headers: Option<(HeaderName, HeaderName)>,
+    body_preprocessor: Option<BodyPreprocessor>,
+    method_override: bool,
 }
 
 /// Generalized Error type encompassing all the possible errors that can be generated by this crate.
+///
+/// Some variants only exist when their feature is enabled, and more may be added in the future,
+/// so matching on an Error requires a wildcard arm.
 #[derive(Error, Debug)]
+#[non_exhaustive]
 pub enum Error {
     /// The provided URI was not valid.
     #[error("invalid URI")]
@@ -106,40 +301,329 @@ pub enum Error {
     Http(#[from] hyper::http::Error),
     /// A Hyper error occurred while executing the request, or constructing the Client.
     #[error("hyper error")]
-    Hyper(#[from] hyper::Error),
+    Hyper(#[source] hyper::Error),
     /// An error occurred while serializing or deserializing data during a request or response.
+    /// Contains the underlying error, along with the content which could not be deserialized.
     #[error("serde error")]
-    Serde(serde_json::Error, String),
+    Serde(#[source] serde_json::Error, String),
     /// An IO error occurred.
+    ///
+    /// Without the `encoding` feature, a response body which isn't valid UTF-8 is reported as an
+    /// [`std::io::ErrorKind::InvalidData`] error wrapping the [`std::string::FromUtf8Error`], which holds the raw bytes.
     #[error("io error")]
     Io(#[from] std::io::Error),
+    /// The TLS backend could not be configured.
+    #[cfg(feature = "openssl")]
+    #[error("tls error")]
+    Tls(#[from] openssl::error::ErrorStack),
+    /// A request could not be constructed from its textual representation.
+    #[error("invalid request: {0}")]
+    InvalidRequest(String),
+    /// A next link pointed to a different host, while [`DataSource::with_strict_next_links`] was enabled.
+    #[error("next link {0} points to a different host")]
+    CrossHostNextLink(String),
+    /// A header required to interpret the response was missing or invalid.
+    #[error("missing or invalid {0} header")]
+    Header(&'static str),
+    /// The API responded with an unexpected HTTP status code.
+    #[error("unexpected http status {code}")]
+    Status {
+        /// Status code returned by the API.
+        code: StatusCode,
+        /// Body of the response, often containing an error message from the API.
+        body: String,
+    },
+    /// The response exceeded the limit set using [`DataSource::with_max_response_size`]. Contains the length declared
+    /// by its `Content-Length`, or the number of bytes received when the limit was crossed, if no length was declared.
+    #[error("response of {0} bytes exceeds the configured maximum size")]
+    ResponseTooLarge(u64),
+    /// A request asked for more resources than allowed by [`DataSource::with_max_top`],
+    /// while [`DataSource::with_strict_max_top`] was enabled.
+    #[error("$top of {top} exceeds the configured maximum of {cap}")]
+    TopExceedsCap {
+        /// The `$top` requested.
+        top: u32,
+        /// The maximum configured on the DataSource.
+        cap: u32,
+    },
+    /// The path and query of a request exceeded the limit set using [`DataSource::with_query_length_limit`],
+    /// and could not be sent using [`DataSource::with_query_via_post`] instead.
+    #[error("query of {length} characters exceeds the configured limit of {limit}, consider enabling DataSource::with_query_via_post")]
+    QueryTooLong {
+        /// Length of the path and query of the request.
+        length: usize,
+        /// The limit configured on the DataSource.
+        limit: usize,
+    },
+    /// No request permit was available from the rate limiter.
+    #[cfg(feature = "rate-limiting")]
+    #[error("rate limit exceeded")]
+    RateLimited,
+    /// An environment variable read by [`DataSource::from_env`] was missing or invalid.
+    /// Contains the name of the variable.
+    #[error("missing or invalid environment variable {0}")]
+    Environment(String),
+    /// The body of the response was shorter than its declared `Content-Length`,
+    /// usually because the connection was dropped while it was being received.
+    #[error("received {received} of {expected} bytes of the response body")]
+    IncompleteBody {
+        /// The `Content-Length` declared by the response.
+        expected: u64,
+        /// The number of bytes actually received.
+        received: u64,
+    },
+    /// An asynchronous operation was still running after its status monitor was polled the maximum number of times
+    /// by [`DataSource::poll_until_complete`]. Contains the location of the monitor, from which polling can be resumed.
+    #[error("operation at {0} is still running")]
+    OperationPending(String),
+    /// [`DataSource::detect_version`] found neither a version header nor a service document of a known shape.
+    #[error("could not detect the OData version of the service")]
+    UnknownVersion,
+}
+
+impl Error {
+    /// Whether the request might succeed if it is sent again, because the error was caused by a transient
+    /// condition, such as a dropped connection, an overloaded server responding with `429 Too Many Requests`
+    /// or `503 Service Unavailable`, a gateway responding with `502 Bad Gateway` or `504 Gateway Timeout`,
+    /// or an exhausted rate limit. Other server errors, such as `500 Internal Server Error`, are not retriable.
+    pub fn is_retriable(&self) -> bool {
+        match self {
+            Error::IncompleteBody { .. } => true,
+            Error::Hyper(e) => {
+                e.is_incomplete_message() || e.is_connect() || e.is_closed() || e.is_timeout()
+            }
+            Error::Status { code, .. } => is_transient_status(*code),
+            #[cfg(feature = "rate-limiting")]
+            Error::RateLimited => true,
+            _ => false,
+        }
+    }
+}
+
+/// Whether a response with the status `code` reports a transient condition, which might be gone if the request
+/// is sent again.
+fn is_transient_status(code: StatusCode) -> bool {
+    matches!(
+        code,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// The `Content-Length` declared by `response`, if any.
+fn content_length(response: &Response<Body>) -> Option<u64> {
+    response
+        .headers()
+        .get(hyper::header::CONTENT_LENGTH)
+        .and_then(|length| length.to_str().ok())
+        .and_then(|length| length.parse::<u64>().ok())
+}
+
+/// Whether the `Content-Type` of `response` declares that control information such as `@odata.nextLink`
+/// was omitted, using `odata.metadata=none` in OData 4.0 or `odata=nometadata` in OData 3.0.
+fn omits_control_information(response: &Response<Body>) -> bool {
+    response
+        .headers()
+        .get(hyper::header::CONTENT_TYPE)
+        .and_then(|content_type| content_type.to_str().ok())
+        .map(|content_type| {
+            content_type.split(';').skip(1).any(|parameter| {
+                let parameter = parameter.trim().to_ascii_lowercase();
+                parameter == "odata.metadata=none" || parameter == "odata=nometadata"
+            })
+        })
+        .unwrap_or(false)
+}
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Failure while receiving the body of a response, raised within the body stream of hyper,
+/// and converted into the corresponding [`enum@Error`] when surfaced as a [`hyper::Error`].
+#[derive(Debug, Error)]
+enum BodyError {
+    #[error("response body exceeds the configured maximum size after {0} bytes")]
+    TooLarge(u64),
+    #[error("connection closed after {received} of {expected} bytes of the response body")]
+    Incomplete { expected: u64, received: u64 },
+}
+
+/// Whether receiving a body failed because the connection was closed before the body was complete.
+fn is_truncated(e: &hyper::Error) -> bool {
+    let eof = std::error::Error::source(e)
+        .and_then(|source| source.downcast_ref::<std::io::Error>())
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::UnexpectedEof);
+    e.is_incomplete_message() || eof
+}
+
+impl From<hyper::Error> for Error {
+    fn from(e: hyper::Error) -> Self {
+        let source = std::error::Error::source(&e).and_then(|source| source.downcast_ref());
+        match source {
+            Some(BodyError::TooLarge(received)) => Error::ResponseTooLarge(*received),
+            Some(BodyError::Incomplete { expected, received }) => Error::IncompleteBody {
+                expected: *expected,
+                received: *received,
+            },
+            None => Error::Hyper(e),
+        }
+    }
 }
 
 /// Wraps lists of Resources returned by the API. Used for deserializing ListRequest responses.
+///
+/// More properties may be added in the future, so a Page can only be constructed by deserializing it.
 #[derive(Debug, Deserialize)]
+#[non_exhaustive]
 pub struct Page<T> {
     /// List of returned values in the page.
     pub value: Vec<T>,
-    #[serde(rename = "odata.count")]
     /// Inline count of remanining objects to be fetched, excluding the ones in this page.
+    ///
+    /// OData 4.0 returns the count as a number, which is converted into its textual representation.
+    #[serde(
+        rename = "odata.count",
+        alias = "@odata.count",
+        default,
+        deserialize_with = "de::option_count"
+    )]
     pub count: Option<String>,
     /// URL Request to send, to fetch the next page in this sequence.
-    #[serde(rename = "odata.nextLink")]
+    #[serde(rename = "odata.nextLink", alias = "@odata.nextLink")]
     pub next_link: Option<String>,
     /// Url to the schema describing the data returned
-    #[serde(rename = "odata.metadata")]
+    #[serde(rename = "odata.metadata", alias = "@odata.context")]
     pub metadata: Option<String>,
+    /// Any other top-level properties of the page, such as `@odata.deltaLink` or vendor-specific annotations.
+    #[serde(flatten)]
+    pub extra: HashMap<String, serde_json::Value>,
+}
+
+/// Pagination details of a [`Page`], see [`Page::info`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PageInfo {
+    /// Whether there are more pages to be fetched after this one.
+    pub has_next: bool,
+    /// Inline count reported by the API, if it was requested and could be parsed as a number.
+    pub total: Option<u64>,
+    /// URL Request to send, to fetch the next page in this sequence.
+    pub next_link: Option<String>,
+}
+
+impl<T> Page<T> {
+    /// Returns `true` if the page contains no values.
+    pub fn is_empty(&self) -> bool {
+        self.value.is_empty()
+    }
+
+    /// Number of values in the page.
+    pub fn len(&self) -> usize {
+        self.value.len()
+    }
+
+    /// Consume the page, returning only its values.
+    pub fn into_values(self) -> Vec<T> {
+        self.value
+    }
+
+    /// Transform each value of the page using `f`, preserving the count, next link and metadata.
+    /// ```rust
+    /// use odata_simple_client::Page;
+    ///
+    /// let page: Page<u32> = serde_json::from_str(r#"{"value": [1, 2], "odata.count": "5"}"#).unwrap();
+    /// let page: Page<String> = page.map(|id| format!("Dokument({id})"));
+    ///
+    /// assert_eq!(page.value, ["Dokument(1)", "Dokument(2)"]);
+    /// assert_eq!(page.count.as_deref(), Some("5"));
+    /// ```
+    pub fn map<U, F>(self, f: F) -> Page<U>
+    where
+        F: FnMut(T) -> U,
+    {
+        Page {
+            value: self.value.into_iter().map(f).collect(),
+            count: self.count,
+            next_link: self.next_link,
+            metadata: self.metadata,
+            extra: self.extra,
+        }
+    }
+
+    /// Collect the pagination details of this page.
+    /// ```rust
+    /// use odata_simple_client::Page;
+    ///
+    /// let page: Page<u32> = serde_json::from_str(r#"{
+    ///     "value": [1, 2],
+    ///     "odata.count": "10",
+    ///     "odata.nextLink": "Dokument?$skip=2"
+    /// }"#).unwrap();
+    ///
+    /// assert_eq!(page.len(), 2);
+    /// let info = page.info();
+    /// assert!(info.has_next);
+    /// assert_eq!(info.total, Some(10));
+    /// ```
+    pub fn info(&self) -> PageInfo {
+        PageInfo {
+            has_next: self.next_link.is_some(),
+            total: self
+                .count
+                .as_deref()
+                .and_then(|count| count.trim().parse().ok()),
+            next_link: self.next_link.clone(),
+        }
+    }
+}
+
+/// Decode the received `body` of a response with the given `headers` into a string, validating that it is UTF-8.
+#[cfg(not(feature = "encoding"))]
+fn decode_body(_headers: &HeaderMap, body: Vec<u8>) -> Result<String, Error> {
+    let mut content = String::from_utf8(body)
+        .map_err(|e| Error::Io(std::io::Error::new(std::io::ErrorKind::InvalidData, e)))?;
+
+    // Some servers and proxies prefix the body with a byte order mark, which isn't valid JSON.
+    if content.starts_with('\u{feff}') {
+        content.replace_range(..'\u{feff}'.len_utf8(), "");
+    }
+    Ok(content)
 }
 
-async fn deserialize_as<T: DeserializeOwned>(response: Response<Body>) -> Result<T, Error> {
+/// Deserialize the response from a reader over the received chunks, without first copying them into a single buffer,
+/// unwrapping the entity from the `envelope`.
+///
+/// Since the body is never collected into a string, the content of the [`Error::Serde`] returned on failure is left empty.
+async fn deserialize_from_chunks<T: DeserializeOwned>(
+    response: Response<Body>,
+    envelope: JsonEnvelope,
+) -> Result<T, Error> {
     let body = hyper::body::aggregate(response).await?;
 
-    let mut content = String::new();
-    // We don't care about the read number of bytes,
-    // we just read until EOF.
-    let _ = body.reader().read_to_string(&mut content)?;
+    envelope
+        .entity_from_reader(body.reader())
+        .map_err(|e| Error::Serde(e, String::new()))
+}
+
+/// Fail with [`Error::Status`] unless `response` was successful (2xx), so that error responses
+/// are surfaced as such, instead of failing to deserialize.
+async fn check_status(response: Response<Body>) -> Result<Response<Body>, Error> {
+    if response.status().is_success() {
+        Ok(response)
+    } else {
+        Err(status_error(response).await)
+    }
+}
 
-    serde_json::from_str(&content).map_err(|e| Error::Serde(e, content))
+async fn status_error(response: Response<Body>) -> Error {
+    let code = response.status();
+    match hyper::body::to_bytes(response).await {
+        Ok(body) => Error::Status {
+            code,
+            body: String::from_utf8_lossy(&body).into_owned(),
+        },
+        Err(e) => e.into(),
+    }
 }
 
 impl<C> DataSource<C>
@@ -160,6 +644,23 @@ where
     ///     Some(String::from("/api"))
     /// ).unwrap();
     /// ```
+    ///
+    /// Connection establishment is handled entirely by the connector of the [`Client`], so settings such as
+    /// the connect timeout must be configured on the connector before constructing the DataSource.
+    /// This allows failing fast on unreachable hosts, without limiting how long a response may take to arrive.
+    /// The configured [`HttpConnector`](`hyper::client::HttpConnector`) can then be wrapped in a TLS connector,
+    /// such as `hyper_openssl::HttpsConnector::with_connector`:
+    /// ```rust
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use odata_simple_client::DataSource;
+    /// # use std::time::Duration;
+    /// let mut http = HttpConnector::new();
+    /// http.set_connect_timeout(Some(Duration::from_secs(2)));
+    ///
+    /// let client = Client::builder().build(http);
+    ///
+    /// let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// ```
     pub fn new<A>(
         client: Client<C>,
         domain: A,
@@ -174,233 +675,3721 @@ where
             authority: Authority::try_from(domain)?,
             base_path: base_path.unwrap_or_default(),
             scheme: Scheme::HTTPS,
+            default_inline_count: None,
+            parameter_order: ParameterOrder::Sorted,
+            odata_version: None,
+            api_version: None,
+            host_header: None,
+            strict_next_links: false,
+            envelope: JsonEnvelope::Minimal,
+            accept_charset: None,
+            accept_types: None,
+            format_negotiation: FormatNegotiation::Both,
+            max_response_size: None,
+            max_top: None,
+            strict_max_top: false,
+            redirect_policy: RedirectPolicy::None,
+            query_via_post: None,
+            query_length_limit: None,
+            keep_alive: None,
+            entity_sets: Vec::new(),
+            default_select: Vec::new(),
+            extend_default_select: false,
+            correlation_header: None,
+            bearer_token: None,
+            headers: HeaderMap::new(),
+            retriable_statuses: Vec::new(),
+            paging_headers: None,
+            body_preprocessor: None,
+            method_override: false,
         })
     }
 
-    async fn execute<R>(&self, request: R) -> Result<Response<Body>, Error>
-    where
-        R: Into<PathBuilder>,
-    {
-        let builder: PathBuilder = request.into().base_path(self.base_path.clone());
+    /// Construct a DataSource from the environment variables named by `prefix`, such as `ODATA_HOST`
+    /// for the prefix `ODATA`, for configuring containerized deployments:
+    ///
+    /// * `{prefix}_HOST`: The domain of the API, which is required.
+    /// * `{prefix}_BASE_PATH`: The base path of the API, if any.
+    /// * `{prefix}_SCHEME`: Either `https` (the default) or `http`. See [`DataSource::with_scheme`].
+    /// * `{prefix}_TOKEN`: A token to authenticate with, if any. See [`DataSource::with_bearer_token`].
+    ///
+    /// Returns [`Error::Environment`] naming the variable which is missing or invalid.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::DataSource;
+    /// let client: Client<HttpsConnector<HttpConnector>> =
+    ///     Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    ///
+    /// let datasource = DataSource::from_env(client, "ODATA").unwrap();
+    /// ```
+    pub fn from_env(client: Client<C>, prefix: &str) -> Result<DataSource<C>, Error> {
+        let var = |name: &str| {
+            let name = format!("{prefix}_{name}");
+            match std::env::var(&name) {
+                Ok(value) => Ok(Some(value)),
+                Err(std::env::VarError::NotPresent) => Ok(None),
+                Err(std::env::VarError::NotUnicode(_)) => Err(Error::Environment(name)),
+            }
+        };
 
-        let uri = Uri::builder()
-            .scheme(self.scheme.as_ref())
-            .authority(self.authority.as_ref())
-            .path_and_query(builder.build()?)
-            .build()?;
+        let host = var("HOST")?.ok_or_else(|| Error::Environment(format!("{prefix}_HOST")))?;
+        let mut datasource = DataSource::new(client, host.as_str(), var("BASE_PATH")?)
+            .map_err(|_| Error::Environment(format!("{prefix}_HOST")))?;
 
-        debug!("fetching {}", uri);
-        Ok(self.client.get(uri).await?)
+        if let Some(scheme) = var("SCHEME")? {
+            let scheme = match scheme.to_ascii_lowercase().as_str() {
+                "https" => Scheme::HTTPS,
+                "http" => Scheme::HTTP,
+                _ => return Err(Error::Environment(format!("{prefix}_SCHEME"))),
+            };
+            datasource = datasource.with_scheme(scheme);
+        }
+        if let Some(token) = var("TOKEN")? {
+            datasource = datasource.with_bearer_token(&token);
+        }
+
+        Ok(datasource)
     }
 
-    /// Fetch a single resource using a [`GetRequest`]
+    /// Connect to the API using `scheme`, which defaults to [`Scheme::HTTPS`].
+    ///
+    /// Plain [`Scheme::HTTP`] is useful for services reachable only within a private network,
+    /// or when TLS is terminated by a local proxy. The connector of the [`Client`] must support the scheme.
+    pub fn with_scheme(mut self, scheme: Scheme) -> Self {
+        self.scheme = scheme;
+        self
+    }
+
+    /// Connect to the API on `port`, instead of the port given to [`DataSource::new`], or the default port of the scheme.
+    /// ```rust
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use odata_simple_client::{DataSource, Scheme};
+    /// let client: Client<HttpConnector> = Client::new();
+    /// let datasource = DataSource::new(client, "127.0.0.1", None)
+    ///     .unwrap()
+    ///     .with_scheme(Scheme::HTTP)
+    ///     .with_port(8080);
+    /// ```
+    pub fn with_port(mut self, port: u16) -> Self {
+        self.authority = format!("{}:{port}", self.authority.host())
+            .parse()
+            .expect("host of a valid authority with a port is a valid authority");
+        self
+    }
+
+    /// Authenticate every request using `token`, sent as `Authorization: Bearer {token}`.
+    ///
+    /// Like the `Host` header set using [`DataSource::with_host_header`], the token is only sent to the scheme,
+    /// host and port this DataSource targets, so it isn't leaked to other hosts, or sent in cleartext after
+    /// a redirect from `https` to `http`.
+    pub fn with_bearer_token(mut self, token: &str) -> Self {
+        self.bearer_token = Some(token.to_string());
+        self
+    }
+
+    /// Apply an [`InlineCount`] to every [`ListRequest`] executed by this DataSource,
+    /// unless the request explicitly specifies one using [`ListRequest::inline_count`].
     /// ```rust
     /// # use hyper::{Client, client::HttpConnector};
     /// # use hyper_openssl::{HttpsConnector};
-    /// # use odata_simple_client::{DataSource, GetRequest};
-    /// # use serde::Deserialize;
-    /// #
+    /// # use odata_simple_client::{DataSource, InlineCount};
     /// # let client: Client<HttpsConnector<HttpConnector>> =
     /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
     /// #
-    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
-    /// #
-    /// #[derive(Deserialize)]
-    /// struct Dokument {
-    ///     titel: String,
-    /// }
-    ///
-    /// # tokio_test::block_on(async {
-    /// let dokument: Dokument = datasource.fetch(
-    ///         GetRequest::new("Dokument", 24)
-    ///     ).await.unwrap();
-    ///
-    /// assert_eq!(dokument.titel, "Grund- og nærhedsnotat vedr. sanktioner på toldområdet");
-    /// # });
+    /// let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api")))
+    ///     .unwrap()
+    ///     .with_default_inline_count(InlineCount::AllPages);
     /// ```
-    pub async fn fetch<T>(&self, request: GetRequest) -> Result<T, Error>
-    where
-        T: DeserializeOwned,
-    {
-        let response = self
-            .execute(Into::<PathBuilder>::into(request).format(Format::Json))
-            .await?;
-        deserialize_as::<T>(response).await
+    pub fn with_default_inline_count(mut self, value: InlineCount) -> Self {
+        self.default_inline_count = Some(value);
+        self
     }
 
-    /// Fetch a [`Page`]d list of resources using a [`ListRequest`]
+    /// Select only the properties `fields` in every [`GetRequest`] and [`ListRequest`] executed by this DataSource,
+    /// unless the request explicitly selects its own properties. Requests writing data, such as
+    /// [`DataSource::create`] and [`DataSource::add_reference`], are sent without it.
+    ///
+    /// Useful for trimming the payloads of services with very large entities, of which only a few properties are needed.
     /// ```rust
     /// # use hyper::{Client, client::HttpConnector};
     /// # use hyper_openssl::{HttpsConnector};
-    /// # use odata_simple_client::{DataSource, ListRequest, Page, InlineCount};
-    /// # use serde::Deserialize;
-    /// #
+    /// # use odata_simple_client::DataSource;
     /// # let client: Client<HttpsConnector<HttpConnector>> =
     /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
     /// #
-    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
-    /// #
-    /// #[derive(Deserialize)]
-    /// struct Dokument {
-    ///     titel: String,
-    /// }
-    ///
-    /// # tokio_test::block_on(async {
-    /// let page: Page<Dokument> = datasource
-    ///     .fetch_paged(ListRequest::new("Dokument")
-    ///         .inline_count(InlineCount::AllPages)
-    ///     ).await.unwrap();
-    /// assert!(page.count.unwrap().parse::<u32>().unwrap() > 0)
-    /// # });
+    /// let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api")))
+    ///     .unwrap()
+    ///     .with_default_select(vec![String::from("id"), String::from("titel")]);
     /// ```
-    pub async fn fetch_paged<T>(&self, request: ListRequest) -> Result<Page<T>, Error>
-    where
-        T: DeserializeOwned,
-    {
-        let response = self
-            .execute(Into::<PathBuilder>::into(request).format(Format::Json))
-            .await?;
-        deserialize_as::<Page<T>>(response).await
+    pub fn with_default_select(mut self, fields: Vec<String>) -> Self {
+        self.default_select = fields;
+        self
     }
-}
 
-/// Request a single resource by ID
-#[derive(Debug, Clone)]
-pub struct GetRequest {
-    builder: PathBuilder,
-}
+    /// Add the properties selected by a request to those set using [`DataSource::with_default_select`],
+    /// rather than replacing them.
+    pub fn with_extend_default_select(mut self, extend: bool) -> Self {
+        self.extend_default_select = extend;
+        self
+    }
 
-impl GetRequest {
-    /// Constructs a GET request for `<DataSource Path>/resource_type(id)`
+    /// Change the order in which query parameters are emitted. Defaults to [`ParameterOrder::Sorted`].
     ///
-    /// Must be [`DataSource::fetch`]ed using a [`DataSource`] to retrieve data.
-    pub fn new(resource_type: &str, id: usize) -> Self {
-        GetRequest {
-            builder: PathBuilder::new(resource_type.to_string()).id(id),
-        }
+    /// Sorted parameters produce the same URI for equivalent requests, which is useful for caching,
+    /// but some non-conformant servers expect the parameters in a specific order. Use
+    /// [`ParameterOrder::Insertion`] to emit them in the order they were set on the request.
+    pub fn with_parameter_order(mut self, order: ParameterOrder) -> Self {
+        self.parameter_order = order;
+        self
     }
 
-    /// Change format of the returned data.
+    /// Declare which version of the OData protocol the API speaks.
     ///
-    /// Can be either [`Format::Json`] or [`Format::Xml`]
-    pub fn format(mut self, format: Format) -> Self {
-        self.builder = self.builder.format(format);
+    /// The version is sent along with every request, using the version negotiation headers of that version,
+    /// to ensure the server responds in the expected format. By default no version headers are sent,
+    /// leaving the choice up to the server.
+    pub fn with_odata_version(mut self, version: ODataVersion) -> Self {
+        self.odata_version = Some(version);
         self
     }
 
-    /// Expand specific relations of the returned object, if possible.
+    /// Target a specific version of the API, which is appended to the base path as a separate path segment.
     ///
-    /// For the [Folketinget API](https://oda.ft.dk) for example, you can expand the `DokumentAktør` field of a `Dokument`, to simultaneously retrieve information about the document authors, instead of having to do two separate lookups for the `DokumentAktør` relation and then the actual `Aktør`.
-    pub fn expand<'f, F>(mut self, field: F) -> Self
-    where
-        F: IntoIterator<Item = &'f str>,
-    {
-        self.builder = self.builder.expand(field);
+    /// For a DataSource with the base path `/api`, calling `with_api_version("v2")` sends all requests
+    /// to `/api/v2/`. Calling it again replaces the version, which makes it easy to switch between versions.
+    pub fn with_api_version(mut self, segment: &str) -> Self {
+        self.api_version = Some(segment.trim_matches('/').to_string());
         self
     }
-}
 
-impl From<GetRequest> for PathBuilder {
-    fn from(request: GetRequest) -> Self {
-        request.builder
+    /// Construct a copy of this DataSource targeting a different `base_path` on the same host,
+    /// sharing the [`Client`] and keeping every other setting, for hosts exposing several OData services.
+    pub fn clone_with_base_path(&self, base_path: String) -> DataSource<C> {
+        DataSource {
+            base_path,
+            ..self.clone()
+        }
     }
-}
 
-/// Request a list of resources.
-#[derive(Debug, Clone)]
-pub struct ListRequest {
-    builder: PathBuilder,
-}
-
-impl ListRequest {
-    /// Create a new ListRequest, fetching all resources of type `resource_type`.
+    /// Attach a header `name` to every request sent by this DataSource, such as `X-Request-ID`,
+    /// whose value is produced by calling `generator` for each request.
     ///
-    /// Use a [`DataSource`] to execute the `ListRequest`
-    pub fn new(resource_type: &str) -> Self {
-        ListRequest {
-            builder: PathBuilder::new(resource_type.to_string()),
-        }
+    /// Useful for correlating the requests with those of upstream services in distributed tracing and logging.
+    /// A fixed value can be sent by returning the same value every time.
+    /// ```rust
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::DataSource;
+    /// use std::sync::atomic::{AtomicUsize, Ordering};
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    ///
+    /// let counter = AtomicUsize::new(0);
+    /// let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api")))
+    ///     .unwrap()
+    ///     .with_correlation_header("X-Request-ID", move || {
+    ///         format!("export-{}", counter.fetch_add(1, Ordering::Relaxed))
+    ///     });
+    /// ```
+    pub fn with_correlation_header<F>(mut self, name: &str, generator: F) -> Self
+    where
+        F: Fn() -> String + Send + Sync + 'static,
+    {
+        self.correlation_header = Some(CorrelationHeader {
+            name: name.to_string(),
+            generator: Arc::new(generator),
+        });
+        self
     }
 
-    /// Change format of the returned data.
+    /// Send `headers` with every request, such as an API key or `Accept-Language`, in addition to any headers set previously.
     ///
-    /// Can be either [`Format::Json`] or [`Format::Xml`]
-    pub fn format(mut self, format: Format) -> Self {
-        self.builder = self.builder.format(format);
+    /// All headers are merged in a single place before a request is sent, with the following precedence:
+    ///
+    /// 1. Headers set on the request using [`GetRequest::header`] or [`ListRequest::header`].
+    /// 2. Headers of the request itself, such as the `Accept` header negotiating its format, or a `Prefer` header.
+    /// 3. Headers of specific settings, such as [`DataSource::with_bearer_token`] or [`DataSource::with_correlation_header`].
+    /// 4. Headers set using this method, or [`DataSource::with_header`].
+    ///
+    /// A header set at a higher level replaces all values of the same header at the lower levels.
+    ///
+    /// Since headers may carry credentials, the headers set using this method, [`DataSource::with_header`] or on
+    /// the request itself are only sent to the scheme, host and port this DataSource targets. Requests to other
+    /// origins, such as when following a redirect or an absolute next link, are sent without them.
+    /// ```rust
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, HeaderMap, HeaderValue};
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// let mut headers = HeaderMap::new();
+    /// headers.insert("Accept-Language", HeaderValue::from_static("da"));
+    /// headers.insert("User-Agent", HeaderValue::from_static("dokument-export/1.0"));
+    ///
+    /// let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api")))
+    ///     .unwrap()
+    ///     .with_headers(headers);
+    /// ```
+    pub fn with_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
         self
     }
 
-    /// Order the returned resources by `field`, in specified `direction`. [`Direction::Ascending`] by default.
-    pub fn order_by(mut self, field: &str, direction: Direction) -> Self {
-        self.builder = self.builder.order_by(field, direction);
+    /// Send the header `name` with every request, such as an API key required by a gateway, replacing any value
+    /// previously set for it. See [`DataSource::with_headers`] for how headers are merged.
+    /// ```rust
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, HeaderName, HeaderValue};
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api")))
+    ///     .unwrap()
+    ///     .with_header(HeaderName::from_static("x-api-key"), HeaderValue::from_static("secret"));
+    /// ```
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        let _ = self.headers.insert(name, value);
         self
     }
 
-    /// Only retrieve the top `count` items.
-    pub fn top(mut self, count: u32) -> Self {
-        self.builder = self.builder.top(count);
+    /// Send `host` as the `Host` header of every request, instead of the authority the DataSource connects to.
+    ///
+    /// Useful when connecting to a gateway or an IP address serving multiple virtual hosts.
+    pub fn with_host_header(mut self, host: &str) -> Self {
+        self.host_header = Some(host.to_string());
         self
     }
 
-    /// Skip the first `count` items.
-    pub fn skip(mut self, count: u32) -> Self {
-        self.builder = self.builder.skip(count);
+    /// Refuse to follow next links pointing to a different origin (scheme, host and port) than the one this DataSource targets.
+    ///
+    /// By default, absolute next links are followed wherever they point, which is required by some federated setups.
+    /// In strict mode, following such a link when paging using [`DataSource::fetch_pages`] fails
+    /// with [`Error::CrossHostNextLink`] instead.
+    pub fn with_strict_next_links(mut self, strict: bool) -> Self {
+        self.strict_next_links = strict;
         self
     }
 
-    /// Include an inline count field in the odata page metadata.
-    /// Useful for gauging how many results/pages are left. By default this is not specified, which implies [`InlineCount::None`]
-    pub fn inline_count(mut self, value: InlineCount) -> Self {
-        self.builder = self.builder.inline_count(value);
+    /// Read the inline count and next link of pages from the `count` and `next_link` response headers,
+    /// for non-standard servers returning them as headers rather than in the body of the response.
+    ///
+    /// Values in the body take precedence, so the headers are only used when the body omits them.
+    pub fn with_paging_headers(mut self, count: HeaderName, next_link: HeaderName) -> Self {
+        self.paging_headers = Some((count, next_link));
         self
     }
 
-    /// Filter the returned results using an OData conditional expression.
+    /// Declare the [`JsonEnvelope`] the API wraps its responses in, which is unwrapped when deserializing
+    /// responses to [`DataSource::fetch`] and [`DataSource::fetch_paged`]. Defaults to [`JsonEnvelope::Minimal`].
     ///
-    /// See [the OData 2.0 documentation (section 4.5)](https://www.odata.org/documentation/odata-version-2-0/uri-conventions/) for more information.
+    /// This only affects how responses are parsed. The API must already be responding in the declared format.
+    pub fn with_json_envelope(mut self, envelope: JsonEnvelope) -> Self {
+        self.envelope = envelope;
+        self
+    }
+
+    /// Transform the raw bytes of every response body using `preprocessor` before it is parsed, such as to strip
+    /// the `)]}'` prefix some servers add to guard against JSON hijacking, or to unwrap a JSONP callback.
+    ///
+    /// The bytes are given exactly as they were received, so the result is then decoded like any other body:
+    /// validated as UTF-8, or transcoded from its charset with the `encoding` feature, and stripped of any
+    /// byte order mark. Return the bytes unchanged as [`Cow::Borrowed`] to avoid copying them.
+    /// Bodies deserialized by [`DataSource::fetch_from_chunks`] are not preprocessed.
     /// ```rust
     /// # use hyper::{Client, client::HttpConnector};
     /// # use hyper_openssl::{HttpsConnector};
-    /// # use odata_simple_client::{DataSource, ListRequest, Page, Comparison};
-    /// # use serde::Deserialize;
-    /// #
+    /// # use odata_simple_client::DataSource;
+    /// # use std::borrow::Cow;
     /// # let client: Client<HttpsConnector<HttpConnector>> =
     /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
-    /// #
-    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
-    /// #
-    /// #[derive(Deserialize, Debug)]
-    /// struct Dokument {
-    ///     titel: String,
-    /// }
+    /// let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api")))
+    ///     .unwrap()
+    ///     .with_body_preprocessor(|body| match body.strip_prefix(b")]}'\n") {
+    ///         Some(json) => Cow::Borrowed(json),
+    ///         None => Cow::Borrowed(body),
+    ///     });
+    /// ```
+    pub fn with_body_preprocessor<F>(mut self, preprocessor: F) -> Self
+    where
+        F: Fn(&[u8]) -> Cow<'_, [u8]> + Send + Sync + 'static,
+    {
+        self.body_preprocessor = Some(BodyPreprocessor(Arc::new(preprocessor)));
+        self
+    }
+
+    /// Read the entire body of `response`, apply the [`DataSource::with_body_preprocessor`] to its raw bytes,
+    /// and decode the result using [`decode_body`].
+    async fn read_content(&self, response: Response<Body>) -> Result<String, Error> {
+        let (parts, body) = response.into_parts();
+        // Collecting the body into a single buffer, which is then validated in place, avoids copying it into
+        // a growing string chunk by chunk, and is measurably faster for pages of the sizes returned by oda.ft.dk.
+        let body = hyper::body::to_bytes(body).await?;
+
+        // A borrowed slice of the same length is the body itself, which needs no copying.
+        let processed = match &self.body_preprocessor {
+            Some(BodyPreprocessor(preprocessor)) => match preprocessor(&body) {
+                Cow::Borrowed(processed) if processed.len() == body.len() => None,
+                processed => Some(processed.into_owned()),
+            },
+            None => None,
+        };
+        decode_body(&parts.headers, processed.unwrap_or_else(|| Vec::from(body)))
+    }
+
+    async fn deserialize_as<T>(&self, response: Response<Body>) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let content = self.read_content(response).await?;
+
+        json::from_str(&content).map_err(|e| Error::Serde(e, content))
+    }
+
+    async fn deserialize_entity<T>(&self, response: Response<Body>) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let content = self.read_content(check_status(response).await?).await?;
+
+        self.envelope
+            .entity(&content)
+            .map_err(|e| Error::Serde(e, content))
+    }
+
+    async fn deserialize_page<T>(&self, response: Response<Body>) -> Result<Page<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let response = check_status(response).await?;
+        let omits_control_information = omits_control_information(&response);
+        let header = |name: &HeaderName| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string)
+        };
+        let (count, next_link) = match &self.paging_headers {
+            Some((count, next_link)) => (header(count), header(next_link)),
+            None => (None, None),
+        };
+        let content = self.read_content(response).await?;
+
+        match self.envelope.page::<T>(&content) {
+            Ok(mut page) => {
+                page.count = page.count.or(count);
+                page.next_link = page.next_link.or(next_link);
+                if omits_control_information && page.next_link.is_none() && !page.is_empty() {
+                    warn!("response omits control information (odata.metadata=none), so any further pages can't be followed");
+                }
+                Ok(page)
+            }
+            Err(e) => Err(Error::Serde(e, content)),
+        }
+    }
+
+    /// Send an `Accept-Charset` header with every request, asking the API to respond using one of the given `charset`s,
+    /// such as `utf-8, iso-8859-1;q=0.5`.
     ///
-    /// # tokio_test::block_on(async {
-    /// let page: Page<Dokument> = datasource
-    ///     .fetch_paged(ListRequest::new("Dokument")
-    ///         .filter("id", Comparison::Equal, "24")
-    ///     ).await.unwrap();
-    /// assert_eq!(page.value[0].titel, "Grund- og nærhedsnotat vedr. sanktioner på toldområdet")
-    /// # });
+    /// Responses in a charset other than UTF-8 are only decoded correctly if the `encoding` feature is enabled,
+    /// which transcodes responses according to the charset of their `Content-Type` header.
+    pub fn with_accept_charset(mut self, charset: &str) -> Self {
+        self.accept_charset = Some(charset.to_string());
+        self
+    }
+
+    /// Send a weighted `Accept` header listing the media `types` the API may respond with, in order of preference,
+    /// such as `application/json;q=1.0, application/xml;q=0.5`, instead of only `application/json`.
+    ///
+    /// Weights are clamped to the range `0.0..=1.0`, and rounded to three decimals. The header replaces the `Accept`
+    /// header of requests which would otherwise send one, depending on the [`FormatNegotiation`].
+    /// ```rust
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::DataSource;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api")))
+    ///     .unwrap()
+    ///     .with_accept_types(&[("application/json", 1.0), ("application/xml", 0.5)]);
     /// ```
-    pub fn filter(mut self, field: &str, comparison: Comparison, value: &str) -> Self {
-        self.builder = self.builder.filter(field, comparison, value);
+    pub fn with_accept_types(mut self, types: &[(&str, f32)]) -> Self {
+        let types: Vec<_> = types
+            .iter()
+            .map(|(media_type, weight)| {
+                let weight = format!("{:.3}", weight.clamp(0.0, 1.0));
+                let weight = weight.trim_end_matches('0');
+                let weight = match weight.strip_suffix('.') {
+                    Some(whole) => format!("{whole}.0"),
+                    None => weight.to_string(),
+                };
+                format!("{media_type};q={weight}")
+            })
+            .collect();
+
+        self.accept_types = (!types.is_empty()).then(|| types.join(", "));
         self
     }
 
-    /// Expand specific relations of the returned object, if possible.
+    /// Change how JSON responses are requested from the API. Defaults to [`FormatNegotiation::Both`],
+    /// which is compatible with the most servers.
+    pub fn with_format_negotiation(mut self, negotiation: FormatNegotiation) -> Self {
+        self.format_negotiation = negotiation;
+        self
+    }
+
+    /// Limit the number of resources requested by every [`ListRequest`] executed by this DataSource to `cap`,
+    /// adding `$top` to requests which don't specify one, and lowering it on requests asking for more.
     ///
-    /// For the [Folketinget API](https://oda.ft.dk) for example, you can expand the `DokumentAktør` field of a `Dokument`, to simultaneously retrieve information about the document authors, instead of having to do two separate lookups for the `DokumentAktør` relation and then the actual `Aktør`.
-    pub fn expand<'f, F>(mut self, field: F) -> Self
+    /// This guards against accidentally fetching an entire collection. Note that the server may still apply
+    /// its own, lower page size. Use [`DataSource::with_strict_max_top`] to reject requests exceeding the cap instead.
+    pub fn with_max_top(mut self, cap: u32) -> Self {
+        self.max_top = Some(cap);
+        self
+    }
+
+    /// Fail requests explicitly asking for more resources than the cap set using [`DataSource::with_max_top`]
+    /// with [`Error::TopExceedsCap`], rather than silently lowering their `$top`.
+    pub fn with_strict_max_top(mut self, strict: bool) -> Self {
+        self.strict_max_top = strict;
+        self
+    }
+
+    /// Reject responses larger than `bytes` with [`Error::ResponseTooLarge`]. Responses declaring a larger
+    /// `Content-Length` are rejected before their bodies are downloaded, while the bodies of other responses,
+    /// such as chunked responses, are counted as they are received, and fail once they exceed the limit.
+    ///
+    /// This applies to every request sent by the DataSource, including [`DataSource::raw_get`].
+    /// Responses are not decompressed by the DataSource, so the limit applies to the data actually received.
+    pub fn with_max_response_size(mut self, bytes: u64) -> Self {
+        self.max_response_size = Some(bytes);
+        self
+    }
+
+    /// Fail if the declared length of the `response` exceeds the configured maximum response size.
+    fn check_response_size(&self, response: &Response<Body>) -> Result<(), Error> {
+        match (self.max_response_size, content_length(response)) {
+            (Some(max), Some(length)) if length > max => Err(Error::ResponseTooLarge(length)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Follow redirects returned by the API, such as when the base path of the service has moved,
+    /// or the service redirects from HTTP to HTTPS. Defaults to [`RedirectPolicy::None`].
+    ///
+    /// Credentials and configured headers are only sent along if the target of the redirect has the same origin
+    /// (scheme, host and port) as this DataSource. That covers the token set using [`DataSource::with_bearer_token`], the
+    /// headers set using [`DataSource::with_header`] and [`DataSource::with_headers`], the headers of the request itself,
+    /// and the `Host` header set using [`DataSource::with_host_header`]. A redirect to another host, or from HTTPS to
+    /// HTTP, only receives the `OData-Version`, `Accept-Charset`, correlation and `Connection` headers.
+    pub fn with_redirect_policy(mut self, policy: RedirectPolicy) -> Self {
+        self.redirect_policy = policy;
+        self
+    }
+
+    /// Send requests whose path and query would exceed `max_length` characters as a `POST` to the `/$query`
+    /// path of the resource instead, with the query options in the body of the request, as defined by OData 4.01.
+    ///
+    /// This allows very large filters to be used with servers limiting the length of URLs.
+    pub fn with_query_via_post(mut self, max_length: usize) -> Self {
+        self.query_via_post = Some(max_length);
+        self
+    }
+
+    /// Tunnel requests using verbs other than `GET`, `HEAD` and `POST` through a `POST` request, sending the actual
+    /// verb in an `X-HTTP-Method-Override` header, for gateways blocking verbs such as `DELETE` at the edge.
+    ///
+    /// This currently applies to [`DataSource::remove_reference`], which deletes the reference. Reading and creating
+    /// resources already use `GET` and `POST`, and are sent unchanged.
+    pub fn with_method_override(mut self, enabled: bool) -> Self {
+        self.method_override = enabled;
+        self
+    }
+
+    /// Limit the path and query of requests to `max_length` characters, guarding against servers rejecting
+    /// long URLs, such as those produced by large filters, with a `414 URI Too Long` status.
+    ///
+    /// Requests exceeding the limit are sent as a `POST` to `/$query` if [`DataSource::with_query_via_post`] is enabled,
+    /// even if they are shorter than the length given there. Otherwise they fail with [`Error::QueryTooLong`]
+    /// before being sent.
+    pub fn with_query_length_limit(mut self, max_length: usize) -> Self {
+        self.query_length_limit = Some(max_length);
+        self
+    }
+
+    /// Send an explicit `Connection: keep-alive` header with every request if `keep_alive` is true,
+    /// or `Connection: close` if it is false. By default no `Connection` header is sent.
+    ///
+    /// Connections are pooled by the [`Client`] either way, but hyper does not return a connection to the pool
+    /// after a request asking for it to be closed, so `Connection: close` results in a new connection per request.
+    /// This can help with intermediaries or servers mishandling long-lived connections.
+    pub fn with_keep_alive(mut self, keep_alive: bool) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+
+    /// Also consider responses with any of the status `codes` retriable, in addition to those considered retriable by
+    /// [`Error::is_retriable`], for services using other statuses such as `409 Conflict` for transient conditions.
+    ///
+    /// A DataSource never retries requests by itself, so this only affects [`DataSource::is_retriable`], for callers
+    /// retrying requests on their own, and the retries of a `RateLimitedDataSource` configured using its `with_retry`
+    /// method, which requires the `rate-limiting` feature.
+    pub fn with_retry_on_status(mut self, codes: &[StatusCode]) -> Self {
+        self.retriable_statuses.extend_from_slice(codes);
+        self
+    }
+
+    /// Whether the request which failed with `error` might succeed if it is sent again,
+    /// like [`Error::is_retriable`], but including the statuses added using [`DataSource::with_retry_on_status`].
+    pub fn is_retriable(&self, error: &Error) -> bool {
+        match error {
+            Error::Status { code, .. } => self.is_retriable_status(*code),
+            error => error.is_retriable(),
+        }
+    }
+
+    /// Whether a response with the status `code` might succeed if the request is sent again.
+    fn is_retriable_status(&self, code: StatusCode) -> bool {
+        is_transient_status(code) || self.retriable_statuses.contains(&code)
+    }
+
+    /// Declare the canonical names of the entity sets exposed by the API, such as those listed in its
+    /// service document or `$metadata`.
+    ///
+    /// The resource type of every request is then replaced by the entity set whose name matches it
+    /// case-insensitively, so `ListRequest::new("dokument")` requests `/Dokument`. Resource types not matching any
+    /// of the entity sets are used as-is, which is also the case for every request if none are declared.
+    pub fn with_entity_sets<I, S>(mut self, entity_sets: I) -> Self
     where
-        F: IntoIterator<Item = &'f str>,
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
     {
-        self.builder = self.builder.expand(field);
+        self.entity_sets = entity_sets.into_iter().map(Into::into).collect();
         self
     }
-}
 
-impl From<ListRequest> for PathBuilder {
-    fn from(request: ListRequest) -> Self {
-        request.builder
+    /// Path of the service root, composed of the base path and the API version segment, if any.
+    fn service_root(&self) -> String {
+        match &self.api_version {
+            Some(version) => format!("{}/{}", self.base_path, version),
+            None => self.base_path.clone(),
+        }
+    }
+
+    async fn send(&self, request: request::Builder) -> Result<Response<Body>, Error> {
+        self.send_with_body(request, Body::empty()).await
+    }
+
+    /// Whether `uri` has the same origin as this DataSource: the same scheme, host and port, where a missing port
+    /// is the default port of the scheme. Links from `https` down to `http` are never considered the same origin.
+    fn is_same_origin(&self, uri: &Uri) -> bool {
+        let port = |scheme: &Scheme, authority: &Authority| {
+            authority.port_u16().or(if *scheme == Scheme::HTTPS {
+                Some(443)
+            } else if *scheme == Scheme::HTTP {
+                Some(80)
+            } else {
+                None
+            })
+        };
+
+        match (uri.scheme(), uri.authority()) {
+            (Some(scheme), Some(authority)) => {
+                *scheme == self.scheme
+                    && authority.host().eq_ignore_ascii_case(self.authority.host())
+                    && port(scheme, authority) == port(&self.scheme, &self.authority)
+            }
+            _ => false,
+        }
+    }
+
+    /// Headers configured on this DataSource for a request to `uri`, in order of increasing precedence.
+    fn default_headers(&self, uri: Option<&Uri>) -> Result<HeaderMap, Error> {
+        let same_origin = uri.is_some_and(|uri| self.is_same_origin(uri));
+        // Headers set by the user may carry credentials such as API keys, so they are never sent elsewhere either.
+        let mut headers = if same_origin {
+            self.headers.clone()
+        } else {
+            HeaderMap::new()
+        };
+        let mut set = |name: &str, value: &str| -> Result<(), Error> {
+            let name = HeaderName::try_from(name).map_err(hyper::http::Error::from)?;
+            let value = HeaderValue::try_from(value).map_err(hyper::http::Error::from)?;
+            let _ = headers.insert(name, value);
+            Ok(())
+        };
+
+        if let Some(version) = self.odata_version {
+            for (name, value) in version.headers() {
+                set(name, value)?;
+            }
+        }
+        // Only relevant to the origin this DataSource targets, and never sent elsewhere, such as when following redirects.
+        if same_origin {
+            if let Some(host) = &self.host_header {
+                set("Host", host)?;
+            }
+            if let Some(token) = &self.bearer_token {
+                set("Authorization", &format!("Bearer {token}"))?;
+            }
+        }
+        if let Some(charset) = &self.accept_charset {
+            set("Accept-Charset", charset)?;
+        }
+        if let Some(correlation) = &self.correlation_header {
+            set(&correlation.name, &(correlation.generator)())?;
+        }
+        if let Some(keep_alive) = self.keep_alive {
+            let connection = if keep_alive { "keep-alive" } else { "close" };
+            set("Connection", connection)?;
+        }
+
+        Ok(headers)
+    }
+
+    /// Send `request`, merging in the headers configured on this DataSource, unless the request sets them itself.
+    async fn send_with_body(
+        &self,
+        mut request: request::Builder,
+        body: Body,
+    ) -> Result<Response<Body>, Error> {
+        let defaults = self.default_headers(request.uri_ref())?;
+        if let Some(headers) = request.headers_mut() {
+            for name in defaults.keys() {
+                if !headers.contains_key(name) {
+                    for value in defaults.get_all(name) {
+                        let _ = headers.append(name.clone(), value.clone());
+                    }
+                }
+            }
+        }
+        let request = request.body(body)?;
+
+        let response = self.client.request(request).await?;
+        self.check_response_size(&response)?;
+        Ok(self.guard_body(response))
+    }
+
+    /// Count the bytes of the body of `response` as they are received, failing with [`Error::ResponseTooLarge`]
+    /// once they exceed the configured maximum response size, for responses which don't declare their length,
+    /// or are larger than declared, and with [`Error::IncompleteBody`] if the connection is closed before
+    /// the declared `Content-Length` was received.
+    fn guard_body(&self, response: Response<Body>) -> Response<Body> {
+        let max = self.max_response_size;
+        let expected = content_length(&response);
+        if max.is_none() && expected.is_none() {
+            return response;
+        }
+
+        let mut received = 0u64;
+        response.map(|body| {
+            Body::wrap_stream(body.map(move |chunk| -> Result<_, BoxError> {
+                let chunk = match (chunk, expected) {
+                    (Err(e), Some(expected)) if is_truncated(&e) => {
+                        return Err(BodyError::Incomplete { expected, received }.into());
+                    }
+                    (chunk, _) => chunk?,
+                };
+                received += chunk.len() as u64;
+                match max {
+                    Some(max) if received > max => Err(BodyError::TooLarge(received).into()),
+                    _ => Ok(chunk),
+                }
+            }))
+        })
+    }
+
+    /// Apply the defaults configured on this DataSource to `builder`, such as the base path and default `$select`.
+    ///
+    /// The defaults shaping the returned resources, `$select`, `$inlinecount` and `$top`, are only applied
+    /// to requests reading data using `method`, `GET` or `HEAD`.
+    fn prepare(&self, builder: PathBuilder, method: &Method) -> Result<PathBuilder, Error> {
+        let mut builder = builder
+            .default_base_path(self.service_root())
+            .parameter_order(self.parameter_order);
+        if *method == Method::GET || *method == Method::HEAD {
+            if let Some(inline_count) = self.default_inline_count {
+                builder = builder.default_inline_count(inline_count);
+            }
+            if !self.default_select.is_empty() && !builder.is_count() {
+                builder = builder.default_select(&self.default_select, self.extend_default_select);
+            }
+            if let Some(cap) = self.max_top {
+                builder = builder.max_top(cap, self.strict_max_top)?;
+            }
+        }
+        if !self.entity_sets.is_empty() {
+            builder = builder.canonical_resource_type(&self.entity_sets);
+        }
+        Ok(builder)
+    }
+
+    /// Complete `path_and_query` into a [`Uri`] using the scheme and authority of this DataSource.
+    fn uri(&self, path_and_query: &str) -> Result<Uri, Error> {
+        Ok(Uri::builder()
+            .scheme(self.scheme.as_ref())
+            .authority(self.authority.as_ref())
+            .path_and_query(path_and_query)
+            .build()?)
+    }
+
+    /// The [`Uri`] a `GET` request for `builder` is sent to, without sending it.
+    fn request_uri(&self, builder: PathBuilder) -> Result<Uri, Error> {
+        let mut builder = self.prepare(builder, &Method::GET)?;
+        if builder.requests_json() && self.format_negotiation == FormatNegotiation::AcceptHeader {
+            builder = builder.without_format();
+        }
+        self.uri(builder.build()?.as_str())
+    }
+
+    async fn execute<R>(&self, request: R) -> Result<Response<Body>, Error>
+    where
+        R: Into<PathBuilder>,
+    {
+        self.execute_method(request, Method::GET).await
+    }
+
+    /// Execute the request using `method`. Long `GET` requests are sent as `POST` requests to `$query`,
+    /// if enabled using [`DataSource::with_query_via_post`].
+    async fn execute_method<R>(&self, request: R, method: Method) -> Result<Response<Body>, Error>
+    where
+        R: Into<PathBuilder>,
+    {
+        self.execute_with_body(request, method, None).await
+    }
+
+    /// Execute the request using `method`, like [`DataSource::execute_method`], sending `json` as the body
+    /// of the request with a `Content-Type: application/json` header.
+    async fn execute_with_body<R>(
+        &self,
+        request: R,
+        method: Method,
+        json: Option<Vec<u8>>,
+    ) -> Result<Response<Body>, Error>
+    where
+        R: Into<PathBuilder>,
+    {
+        let mut builder = self.prepare(request.into(), &method)?;
+
+        debug!(
+            resource = builder.resource_type(),
+            filter = builder.decoded_parameter("filter").as_deref(),
+            expand = builder.decoded_parameter("expand").as_deref(),
+            select = builder.decoded_parameter("select").as_deref(),
+            orderby = builder.decoded_parameter("orderby").as_deref(),
+            top = builder.decoded_parameter("top").as_deref(),
+            skip = builder.decoded_parameter("skip").as_deref();
+            "executing request"
+        );
+
+        // Counts are returned as plain text, regardless of the requested format.
+        let accept_json = builder.requests_json()
+            && !builder.is_count()
+            && self.format_negotiation != FormatNegotiation::QueryParameter;
+        if builder.requests_json() && self.format_negotiation == FormatNegotiation::AcceptHeader {
+            builder = builder.without_format();
+        }
+
+        let path_and_query = builder.build()?;
+        let length = path_and_query.as_str().len();
+        let via_post = self.query_via_post.map(|max_length| {
+            self.query_length_limit
+                .map_or(max_length, |limit| limit.min(max_length))
+        });
+        let (method, path_and_query, query) = match via_post {
+            Some(max_length) if method == Method::GET && length > max_length => (
+                Method::POST,
+                format!("{}/$query", path_and_query.path()),
+                Some(path_and_query.query().unwrap_or_default().to_string()),
+            ),
+            _ => match self.query_length_limit {
+                Some(limit) if length > limit => return Err(Error::QueryTooLong { length, limit }),
+                _ => (method, path_and_query.to_string(), None),
+            },
+        };
+
+        let uri = self.uri(&path_and_query)?;
+
+        let request = |uri: &Uri, method: &Method, with_body: bool| {
+            let mut request = match *method {
+                Method::GET | Method::HEAD | Method::POST => {
+                    Request::builder().method(method).uri(uri)
+                }
+                _ if self.method_override => Request::builder()
+                    .method(Method::POST)
+                    .uri(uri)
+                    .header("X-HTTP-Method-Override", method.as_str()),
+                _ => Request::builder().method(method).uri(uri),
+            };
+            if with_body && query.is_some() {
+                request = request.header(hyper::header::CONTENT_TYPE, "text/plain");
+            } else if with_body && json.is_some() {
+                request = request.header(hyper::header::CONTENT_TYPE, "application/json");
+            }
+            if accept_json {
+                let accept = self.accept_types.as_deref().unwrap_or("application/json");
+                request = request.header(hyper::header::ACCEPT, accept);
+            }
+            if !builder.preferences().is_empty() {
+                request = request.header("Prefer", builder.preferences().join(", "));
+            }
+            // Headers of the request replace any set by the request itself, such as the `Accept` header,
+            // but like the headers of the DataSource, they are only sent to its own origin.
+            if let Some(headers) = request.headers_mut().filter(|_| self.is_same_origin(uri)) {
+                for (name, _) in builder.headers() {
+                    let _ = headers.remove(name);
+                }
+                for (name, value) in builder.headers() {
+                    let _ = headers.append(name.clone(), value.clone());
+                }
+            }
+            request
+        };
+
+        let body = |with_body: bool| match (&query, &json) {
+            (Some(query), _) if with_body => Body::from(query.clone()),
+            (None, Some(json)) if with_body => Body::from(json.clone()),
+            _ => Body::empty(),
+        };
+
+        debug!("fetching {}", uri);
+        let mut response = self
+            .send_with_body(request(&uri, &method, true), body(true))
+            .await?;
+
+        let mut method = method;
+        let mut with_body = true;
+
+        let max_redirects = match self.redirect_policy {
+            RedirectPolicy::None => 0,
+            RedirectPolicy::Limited(max_redirects) => max_redirects,
+        };
+        for _ in 0..max_redirects {
+            let location = match response.headers().get(hyper::header::LOCATION) {
+                Some(location) if response.status().is_redirection() => {
+                    location.to_str().map_err(|_| Error::Header("Location"))?
+                }
+                _ => break,
+            };
+
+            // A `303 See Other` points to the result of the request, which is fetched using `GET`, as browsers also do
+            // when a `POST` is redirected using `301` or `302`. Only `307` and `308` ask for the request to be repeated,
+            // so resending a created entity elsewhere can't create it twice.
+            match response.status() {
+                StatusCode::SEE_OTHER if method != Method::HEAD => {
+                    method = Method::GET;
+                    with_body = false;
+                }
+                StatusCode::MOVED_PERMANENTLY | StatusCode::FOUND if method == Method::POST => {
+                    method = Method::GET;
+                    with_body = false;
+                }
+                _ => {}
+            }
+
+            let uri = self.resolve_link(location)?;
+            debug!("following redirect to {}", uri);
+            response = self
+                .send_with_body(request(&uri, &method, with_body), body(with_body))
+                .await?;
+        }
+
+        Ok(response)
+    }
+
+    /// Send a `GET` request for the complete `uri`, bypassing the request builders entirely,
+    /// but including all the headers configured on this DataSource.
+    ///
+    /// The response is returned as-is, whatever its status code.
+    pub async fn raw_get(&self, uri: Uri) -> Result<Response<Body>, Error> {
+        debug!("fetching {}", uri);
+        self.send(Request::get(uri)).await
+    }
+
+    /// Send a `GET` request for a link returned by the API, such as a read link or a deferred link, asking for
+    /// JSON according to the [`FormatNegotiation`] of this DataSource, like requests built using [`GetRequest`] do.
+    ///
+    /// Unlike next links, such links don't carry over the `$format` of the request returning them, so OData 3.0
+    /// services respond using Atom unless asked otherwise. Links are used as returned, except for adding
+    /// `$format=json` if the negotiation uses the query parameter, and the link doesn't specify a `$format` itself.
+    async fn get_link(&self, uri: Uri) -> Result<Response<Body>, Error> {
+        let mut uri = uri;
+        if self.format_negotiation != FormatNegotiation::AcceptHeader {
+            let query = uri.query().unwrap_or_default();
+            let has_format = query
+                .split('&')
+                .any(|pair| pair.starts_with("$format=") || pair.starts_with("%24format="));
+            if !has_format {
+                let path_and_query = match query {
+                    "" => format!("{}?$format=json", uri.path()),
+                    query => format!("{}?{query}&$format=json", uri.path()),
+                };
+                let mut parts = uri.into_parts();
+                parts.path_and_query = Some(path_and_query.parse()?);
+                uri = Uri::from_parts(parts).map_err(hyper::http::Error::from)?;
+            }
+        }
+
+        let mut request = Request::get(uri);
+        if self.format_negotiation != FormatNegotiation::QueryParameter {
+            let accept = self.accept_types.as_deref().unwrap_or("application/json");
+            request = request.header(hyper::header::ACCEPT, accept);
+        }
+
+        debug!(
+            "fetching {}",
+            request
+                .uri_ref()
+                .map(ToString::to_string)
+                .unwrap_or_default()
+        );
+        self.send(request).await
+    }
+
+    /// Check that the API is reachable, by issuing a `HEAD` request for the service document at the base path.
+    ///
+    /// Any successful (2xx) or redirection (3xx) response is considered healthy, while other status codes
+    /// are returned as [`Error::Status`].
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::DataSource;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// # tokio_test::block_on(async {
+    /// datasource.ping().await.unwrap();
+    /// # });
+    /// ```
+    pub async fn ping(&self) -> Result<(), Error> {
+        let uri = Uri::builder()
+            .scheme(self.scheme.as_ref())
+            .authority(self.authority.as_ref())
+            .path_and_query(format!("{}/", encode_path(&self.service_root())))
+            .build()?;
+
+        debug!("pinging {}", uri);
+        let response = self.send(Request::head(uri)).await?;
+        let status = response.status();
+        if status.is_success() || status.is_redirection() {
+            Ok(())
+        } else {
+            Err(status_error(response).await)
+        }
+    }
+
+    /// Check whether the entity set `name` exists, for example before building requests from user input.
+    ///
+    /// If the entity sets of the service have been declared using [`DataSource::with_entity_sets`], `name` is
+    /// looked up among them, case-insensitively. Otherwise the API is probed with a cheap `$top=0` request,
+    /// and a `404 Not Found` response is interpreted as the entity set not existing.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::DataSource;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// # tokio_test::block_on(async {
+    /// assert!(datasource.entity_set_exists("Dokument").await.unwrap());
+    /// assert!(!datasource.entity_set_exists("Dokumnet").await.unwrap());
+    /// # });
+    /// ```
+    pub async fn entity_set_exists(&self, name: &str) -> Result<bool, Error> {
+        if !self.entity_sets.is_empty() {
+            let name = name.to_lowercase();
+            return Ok(self
+                .entity_sets
+                .iter()
+                .any(|entity_set| entity_set.to_lowercase() == name));
+        }
+
+        let request = ListRequest::new(name).top(0);
+        let response = self
+            .execute(Into::<PathBuilder>::into(request).format(Format::Json))
+            .await?;
+
+        match response.status() {
+            StatusCode::NOT_FOUND => Ok(false),
+            status if status.is_success() => Ok(true),
+            _ => Err(status_error(response).await),
+        }
+    }
+
+    /// Detect the version of the OData protocol spoken by the API, for use with [`DataSource::with_odata_version`].
+    ///
+    /// The service document at the base path is requested, and the version is determined from the `OData-Version`
+    /// or `DataServiceVersion` header of the response, or failing that, from the shape of the returned document.
+    /// Services speaking OData 2.0 or earlier are reported as [`ODataVersion::V3`], whose headers they understand,
+    /// and a service matching none of these yields [`Error::UnknownVersion`].
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::DataSource;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// # tokio_test::block_on(async {
+    /// let version = datasource.detect_version().await.unwrap();
+    /// let datasource = datasource.with_odata_version(version);
+    /// # });
+    /// ```
+    pub async fn detect_version(&self) -> Result<ODataVersion, Error> {
+        let uri = Uri::builder()
+            .scheme(self.scheme.as_ref())
+            .authority(self.authority.as_ref())
+            .path_and_query(format!("{}/", encode_path(&self.service_root())))
+            .build()?;
+
+        debug!("detecting version of {}", uri);
+        let request = Request::get(uri).header(hyper::header::ACCEPT, "application/json");
+        let response = self.send(request).await?;
+        if !response.status().is_success() {
+            return Err(status_error(response).await);
+        }
+
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+        };
+        match (header("OData-Version"), header("DataServiceVersion")) {
+            (Some(version), _) if version.starts_with('4') => return Ok(ODataVersion::V4),
+            (_, Some(_)) => return Ok(ODataVersion::V3),
+            _ => {}
+        }
+
+        let document: serde_json::Map<String, serde_json::Value> =
+            self.deserialize_as(response).await?;
+        if document.contains_key("@odata.context") {
+            Ok(ODataVersion::V4)
+        } else if document.contains_key("odata.metadata") || document.contains_key("d") {
+            Ok(ODataVersion::V3)
+        } else {
+            Err(Error::UnknownVersion)
+        }
+    }
+
+    /// Fetch a single resource using a [`GetRequest`]
+    /// ```rust
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, GetRequest};
+    /// # use serde::Deserialize;
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let dokument: Dokument = datasource.fetch(
+    ///         GetRequest::new("Dokument", 24)
+    ///     ).await.unwrap();
+    ///
+    /// assert_eq!(dokument.titel, "Grund- og nærhedsnotat vedr. sanktioner på toldområdet");
+    /// # });
+    /// ```
+    ///
+    /// A [`CountRequest`] can be fetched as well, returning the number of matching resources.
+    ///
+    /// Parsing of large properties which are rarely needed can be deferred by deserializing them into a
+    /// `Box<serde_json::value::RawValue>`, which requires the `raw_value` feature of `serde_json`. The property is then
+    /// only validated, and kept as the raw JSON text, until it is parsed on demand. This is not supported when the
+    /// `simd-json` feature is enabled.
+    ///
+    /// With the `xml` feature enabled, requests using [`Format::Xml`] are deserialized from XML instead.
+    /// Otherwise, and for all other requests, JSON is requested regardless of the format of the request.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, GetRequest};
+    /// # use serde::Deserialize;
+    /// use serde_json::value::RawValue;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    ///     #[serde(rename = "Fil")]
+    ///     filer: Box<RawValue>,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let dokument: Dokument = datasource
+    ///     .fetch(GetRequest::new("Dokument", 24).expand(["Fil"]))
+    ///     .await
+    ///     .unwrap();
+    /// println!("{}: {} bytes of files", dokument.titel, dokument.filer.get().len());
+    /// # });
+    /// ```
+    pub async fn fetch<T>(&self, request: impl Into<GetRequest>) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let builder = Into::<PathBuilder>::into(request.into());
+        #[cfg(feature = "xml")]
+        if builder.requests_xml() {
+            let response = self.execute(builder).await?;
+            let content = self.read_content(check_status(response).await?).await?;
+            return xml::from_str(&content).map_err(|e| Error::Serde(e, content));
+        }
+
+        // Counts are returned as plain text, and are never wrapped in an envelope.
+        let is_count = builder.is_count();
+
+        let response = self.execute(builder.format(Format::Json)).await?;
+        if is_count {
+            self.deserialize_as::<T>(check_status(response).await?)
+                .await
+        } else {
+            self.deserialize_entity::<T>(response).await
+        }
+    }
+
+    /// Fetch a single resource using a [`GetRequest`], deserializing the response as `T` if successful (2xx),
+    /// or as the error model `E` of the service if the request failed (4xx or 5xx).
+    ///
+    /// Transport failures and other status codes are returned as the outer [`enum@Error`].
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, GetRequest};
+    /// # use serde::Deserialize;
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// #[derive(Deserialize)]
+    /// struct ServiceError {
+    ///     message: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// match datasource.fetch_result::<Dokument, ServiceError>(GetRequest::new("Dokument", 24)).await.unwrap() {
+    ///     Ok(dokument) => println!("{}", dokument.titel),
+    ///     Err(error) => println!("request failed: {}", error.message),
+    /// }
+    /// # });
+    /// ```
+    pub async fn fetch_result<T, E>(&self, request: GetRequest) -> Result<Result<T, E>, Error>
+    where
+        T: DeserializeOwned,
+        E: DeserializeOwned,
+    {
+        let response = self
+            .execute(Into::<PathBuilder>::into(request).format(Format::Json))
+            .await?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(Ok(self.deserialize_entity::<T>(response).await?))
+        } else if status.is_client_error() || status.is_server_error() {
+            Ok(Err(self.deserialize_as::<E>(response).await?))
+        } else {
+            Err(status_error(response).await)
+        }
+    }
+
+    /// Fetch the resource of a [`GetRequest`], writing the body of the response into `writer` chunk by chunk
+    /// as it is received, without deserializing it, and return the number of bytes written.
+    ///
+    /// Unlike the other fetch methods, the `$format` of the request is left as-is, and the body is never held
+    /// in memory in its entirety, which makes this suitable for exporting very large responses to a file.
+    /// Responses with a status other than 2xx are returned as [`Error::Status`], without writing anything.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, Format, GetRequest};
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// # tokio_test::block_on(async {
+    /// let mut file = tokio::fs::File::create("dokument.json").await.unwrap();
+    /// let written = datasource
+    ///     .fetch_into(GetRequest::new("Dokument", 24).format(Format::Json), &mut file)
+    ///     .await
+    ///     .unwrap();
+    /// # });
+    /// ```
+    pub async fn fetch_into<W>(&self, request: GetRequest, writer: &mut W) -> Result<u64, Error>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        let response = self.execute(request).await?;
+        if !response.status().is_success() {
+            return Err(status_error(response).await);
+        }
+
+        let mut body = response.into_body();
+        let mut written = 0;
+        while let Some(chunk) = body.data().await {
+            let chunk = chunk?;
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+
+        Ok(written)
+    }
+
+    /// Fetch a single resource using a [`GetRequest`], deserializing the response from the received chunks
+    /// of the body, instead of first copying them into a single string like [`DataSource::fetch`] does.
+    ///
+    /// The entire body is still received before parsing starts, but only the chunks are held in memory, rather than
+    /// a second, contiguous copy of very large entities. The [`JsonEnvelope`] of this DataSource is unwrapped like
+    /// [`DataSource::fetch`] does, but the body isn't preprocessed. This comes at the cost of less helpful errors:
+    /// the body is not available in the [`Error::Serde`] returned if the response could not be deserialized.
+    pub async fn fetch_from_chunks<T>(&self, request: GetRequest) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self
+            .execute(Into::<PathBuilder>::into(request).format(Format::Json))
+            .await?;
+        deserialize_from_chunks::<T>(check_status(response).await?, self.envelope).await
+    }
+
+    /// Fetch a [`Page`]d list of resources using a [`ListRequest`]
+    ///
+    /// Servers responding without metadata, such as when asked for `application/json;odata.metadata=none`, also omit
+    /// the `next_link` of the page, so a page truncated by server-driven paging is indistinguishable from the last one.
+    /// A warning is logged when a non-empty page declares that it omits metadata. Request at least minimal metadata
+    /// to follow pages reliably, or page explicitly using [`ListRequest::with_pagination`] at the cost of an extra
+    /// round-trip past the final page.
+    /// ```rust
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, ListRequest, Page, InlineCount};
+    /// # use serde::Deserialize;
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let page: Page<Dokument> = datasource
+    ///     .fetch_paged(ListRequest::new("Dokument")
+    ///         .inline_count(InlineCount::AllPages)
+    ///     ).await.unwrap();
+    /// assert!(page.count.unwrap().parse::<u32>().unwrap() > 0)
+    /// # });
+    /// ```
+    pub async fn fetch_paged<T>(&self, request: ListRequest) -> Result<Page<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self
+            .execute(Into::<PathBuilder>::into(request).format(Format::Json))
+            .await?;
+        self.deserialize_page::<T>(response).await
+    }
+
+    /// Fetch a [`Page`]d list of resources like [`DataSource::fetch_paged`], returning the raw body of the response
+    /// alongside the error if it could not be deserialized, or if the API responded with an unsuccessful status,
+    /// for inspecting schema mismatches and error messages.
+    ///
+    /// The body is empty for any other error, such as the request failing before a response was received.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, ListRequest};
+    /// # use serde::Deserialize;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// match datasource.try_fetch_paged::<Dokument>(ListRequest::new("Dokument")).await {
+    ///     Ok(page) => println!("fetched {} documents", page.len()),
+    ///     Err((error, body)) => eprintln!("{error}: {body}"),
+    /// }
+    /// # });
+    /// ```
+    pub async fn try_fetch_paged<T>(&self, request: ListRequest) -> Result<Page<T>, (Error, String)>
+    where
+        T: DeserializeOwned,
+    {
+        self.fetch_paged(request).await.map_err(|e| {
+            let body = match &e {
+                Error::Serde(_, body) | Error::Status { body, .. } => body.clone(),
+                _ => String::new(),
+            };
+            (e, body)
+        })
+    }
+
+    /// Count the resources matching a [`ListRequest`] using the `$count` path segment, applying any filters of the request,
+    /// without downloading any of the resources themselves.
+    ///
+    /// The API responds with the count as plain text, which is parsed directly, rather than as JSON.
+    /// Options only relevant to listing resources, such as the format and inline count, are omitted from the request.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{Comparison, DataSource, ListRequest};
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// # tokio_test::block_on(async {
+    /// let count = datasource
+    ///     .count(ListRequest::new("Dokument").filter("typeid", Comparison::Equal, 3))
+    ///     .await
+    ///     .unwrap();
+    /// println!("{count} results");
+    /// # });
+    /// ```
+    pub async fn count(&self, request: ListRequest) -> Result<u64, Error> {
+        let response = self.execute(GetRequest::from(request.count_only())).await?;
+        let content = self.read_content(check_status(response).await?).await?;
+
+        match content.trim().parse() {
+            Ok(count) => Ok(count),
+            Err(_) => Err(Error::Serde(
+                serde::de::Error::custom("expected the count as a plain-text integer"),
+                content,
+            )),
+        }
+    }
+
+    /// Fetch the resources of a [`ListRequest`] paginated using [`ListRequest::with_pagination`], along with the total
+    /// number of matching resources reported by the inline count, if the API reports one.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, ListRequest};
+    /// # use serde::Deserialize;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let (dokumenter, total) = datasource
+    ///     .fetch_page_with_total::<Dokument>(ListRequest::new("Dokument").with_pagination(0, 20))
+    ///     .await
+    ///     .unwrap();
+    /// println!("showing {} of {:?} documents", dokumenter.len(), total);
+    /// # });
+    /// ```
+    pub async fn fetch_page_with_total<T>(
+        &self,
+        request: ListRequest,
+    ) -> Result<(Vec<T>, Option<u64>), Error>
+    where
+        T: DeserializeOwned,
+    {
+        let page = self.fetch_paged::<T>(request).await?;
+        let total = page.info().total;
+        Ok((page.into_values(), total))
+    }
+
+    /// Fetch a single resource like [`DataSource::fetch`], along with the time spent on executing the request
+    /// and deserializing the response, for profiling slow requests.
+    pub async fn fetch_timed<T>(
+        &self,
+        request: impl Into<GetRequest>,
+    ) -> Result<(T, Duration), Error>
+    where
+        T: DeserializeOwned,
+    {
+        let start = tokio::time::Instant::now();
+        let value = self.fetch(request).await?;
+        Ok((value, start.elapsed()))
+    }
+
+    /// Fetch a [`Page`]d list of resources like [`DataSource::fetch_paged`], along with the time spent on
+    /// executing the request and deserializing the response. See [`DataSource::fetch_timed`].
+    pub async fn fetch_paged_timed<T>(
+        &self,
+        request: ListRequest,
+    ) -> Result<(Page<T>, Duration), Error>
+    where
+        T: DeserializeOwned,
+    {
+        let start = tokio::time::Instant::now();
+        let page = self.fetch_paged(request).await?;
+        Ok((page, start.elapsed()))
+    }
+
+    /// Resolve a link returned by the API, such as a `next_link`, into a complete [`Uri`].
+    ///
+    /// The link is used as-is if absolute, otherwise it is resolved against the scheme,
+    /// authority and base path of this DataSource. Links are already percent-encoded by the server,
+    /// so they are never encoded again.
+    fn resolve_link(&self, link: &str) -> Result<Uri, Error> {
+        if link.contains("://") {
+            return Ok(link.parse()?);
+        }
+
+        let path_and_query = if link.starts_with('/') {
+            link.to_string()
+        } else {
+            format!("{}/{}", encode_path(&self.service_root()), link)
+        };
+
+        Ok(Uri::builder()
+            .scheme(self.scheme.as_ref())
+            .authority(self.authority.as_ref())
+            .path_and_query(path_and_query)
+            .build()?)
+    }
+
+    async fn fetch_next<T>(&self, next_link: &str) -> Result<Page<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let uri = self.resolve_link(next_link)?;
+        if self.strict_next_links && !self.is_same_origin(&uri) {
+            return Err(Error::CrossHostNextLink(uri.to_string()));
+        }
+
+        debug!("following next link {}", uri);
+        let response = self.raw_get(uri).await?;
+        self.deserialize_page::<T>(response).await
+    }
+
+    /// Fetch up to `max_pages` [`Page`]s of resources using a [`ListRequest`], following the `next_link` of each page,
+    /// and return all the collected resources.
+    ///
+    /// Useful for bounding the time and memory spent on fetching a sample from a very large collection.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, ListRequest};
+    /// # use serde::Deserialize;
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let dokumenter: Vec<Dokument> = datasource
+    ///     .fetch_pages(ListRequest::new("Dokument"), 2)
+    ///     .await
+    ///     .unwrap();
+    /// # });
+    /// ```
+    pub async fn fetch_pages<T>(
+        &self,
+        request: ListRequest,
+        max_pages: usize,
+    ) -> Result<Vec<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        if max_pages == 0 {
+            return Ok(Vec::new());
+        }
+
+        let page = self.fetch_paged(request).await?;
+        stream::collect_pages(page, max_pages, |next_link| async move {
+            self.fetch_next(&next_link).await
+        })
+        .await
+    }
+}
+
+/// Render the path and query of a request relative to `base_path`, omitting the trailing `?` of requests without parameters.
+fn render(builder: PathBuilder, base_path: &str) -> Result<String, Error> {
+    let path_and_query = builder
+        .default_base_path(base_path.trim_end_matches('/').to_string())
+        .build()?;
+    let rendered = path_and_query.as_str();
+
+    Ok(rendered.strip_suffix('?').unwrap_or(rendered).to_string())
+}
+
+/// Request a single resource by ID
+#[derive(Debug, Clone)]
+pub struct GetRequest {
+    builder: PathBuilder,
+}
+
+impl GetRequest {
+    /// Constructs a GET request for `<DataSource Path>/resource_type(id)`
+    ///
+    /// Must be [`DataSource::fetch`]ed using a [`DataSource`] to retrieve data.
+    pub fn new<R>(resource_type: R, id: usize) -> Self
+    where
+        R: Into<ResourceType>,
+    {
+        GetRequest {
+            builder: PathBuilder::new(resource_type.into().into()).id(id),
+        }
+    }
+
+    /// Change format of the returned data.
+    ///
+    /// Can be either [`Format::Json`] or [`Format::Xml`]
+    pub fn format(mut self, format: Format) -> Self {
+        self.builder = self.builder.format(format);
+        self
+    }
+
+    /// Add a query option the request builder doesn't model, like [`ListRequest::raw_param`].
+    pub fn raw_param(mut self, key: &'static str, value: &str) -> Self {
+        self.builder = self.builder.raw_param(key, value);
+        self
+    }
+
+    /// Expand specific relations of the returned object, if possible.
+    ///
+    /// For the [Folketinget API](https://oda.ft.dk) for example, you can expand the `DokumentAktør` field of a `Dokument`, to simultaneously retrieve information about the document authors, instead of having to do two separate lookups for the `DokumentAktør` relation and then the actual `Aktør`.
+    pub fn expand<'f, F>(mut self, field: F) -> Self
+    where
+        F: IntoIterator<Item = &'f str>,
+    {
+        self.builder = self.builder.expand(field);
+        self
+    }
+
+    /// Expand a navigation property using OData v4 nested query options, such as a nested `$select`
+    /// restricting which of its properties are retrieved.
+    ///
+    /// Properties of the navigation selected using a path at the top level, such as `Fil/titel`, are moved into
+    /// the nested `$select`, since v4 servers only apply the top-level `$select` to the resource itself.
+    /// The navigation property itself is still selected at the top level, so the projection of the resource is unchanged.
+    /// See [`ExpandOption`] for details.
+    pub fn expand_with(mut self, option: ExpandOption) -> Self {
+        self.builder = self.builder.expand_with(option);
+        self
+    }
+
+    /// Expand every navigation property of the resource using `$expand=*`, introduced in OData 4.0.
+    ///
+    /// This replaces any properties previously passed to `expand`, and any properties passed afterwards are ignored,
+    /// since they are already included.
+    pub fn expand_all(mut self) -> Self {
+        self.builder = self.builder.expand_all();
+        self
+    }
+
+    /// Only retrieve specific properties of the returned object using `$select`, to reduce the size of the response.
+    ///
+    /// Repeated calls append to the list of selected properties. Replaces the default set using
+    /// [`DataSource::with_default_select`], unless [`DataSource::with_extend_default_select`] is enabled.
+    pub fn select<'f, F>(mut self, field: F) -> Self
+    where
+        F: IntoIterator<Item = &'f str>,
+    {
+        self.builder = self.builder.select(field);
+        self
+    }
+
+    /// Send this request to `base_path` instead of the base path of the [`DataSource`] executing it,
+    /// which is useful when a single host serves multiple OData services.
+    ///
+    /// The path replaces the entire service root, including any version set using [`DataSource::with_api_version`].
+    pub fn base_path(mut self, base_path: &str) -> Self {
+        self.builder = self
+            .builder
+            .base_path(base_path.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Ask the server to omit null-valued properties from the response, using the OData v4 `Prefer: omit-values=nulls` header.
+    ///
+    /// Missing properties deserialize fine into `Option` fields, so this only reduces the size of the response.
+    pub fn omit_nulls(mut self) -> Self {
+        self.builder = self.builder.prefer("omit-values=nulls");
+        self
+    }
+
+    /// Send the header `name` along with this request, replacing any value set for it by the [`DataSource`],
+    /// or by the request itself. Repeated calls for the same header send every value.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.builder = self.builder.header(name, value);
+        self
+    }
+
+    /// Ask for the connection to be closed after this request using a `Connection: close` header, so it is not
+    /// returned to the connection pool of the [`Client`] and the next request opens a new one.
+    ///
+    /// Useful for diagnosing servers mishandling keep-alive without giving up pooling for every request,
+    /// as [`DataSource::with_keep_alive`] does.
+    pub fn fresh_connection(self) -> Self {
+        self.header(hyper::header::CONNECTION, HeaderValue::from_static("close"))
+    }
+
+    /// Ask the server to respond synchronously if it can complete the request within `wait`,
+    /// using the `Prefer: wait=N` header, with the duration truncated to whole seconds.
+    ///
+    /// Combined with [`DataSource::fetch_async`], the server only falls back to processing the request
+    /// asynchronously if it takes longer than that, in which case [`AsyncResponse::Accepted`] is returned.
+    pub fn wait(mut self, wait: Duration) -> Self {
+        self.builder = self.builder.prefer(&format!("wait={}", wait.as_secs()));
+        self
+    }
+
+    /// Append a path segment after the resource, such as a navigation property or the namespace-qualified name
+    /// of a bound function, as in `Products(1)/Model.Rate`. Repeated calls append further segments.
+    pub fn segment(mut self, segment: &str) -> Self {
+        self.builder = self.builder.segment(segment);
+        self
+    }
+
+    /// Render the path and query of the request relative to `base_path`, such as `/api/Dokument(24)?$expand=Fil`,
+    /// without requiring a [`DataSource`]. Useful for logging, or asserting the outcome of building a request.
+    ///
+    /// Parameters added by a [`DataSource`] when executing the request, such as `$format=json`, aren't included.
+    /// ```rust
+    /// use odata_simple_client::GetRequest;
+    ///
+    /// let request = GetRequest::new("Dokument", 24).expand(["Fil"]);
+    /// assert_eq!(request.to_query_string("/api").unwrap(), "/api/Dokument(24)?$expand=Fil");
+    /// ```
+    pub fn to_query_string(&self, base_path: &str) -> Result<String, Error> {
+        render(self.clone().into(), base_path)
+    }
+
+    /// The complete [`Uri`] [`DataSource::fetch`] would request for this request from `datasource`,
+    /// including the defaults configured on it and the `$format` parameter, without sending the request.
+    ///
+    /// Requests which would be sent as a `POST` to `$query`, see [`DataSource::with_query_via_post`],
+    /// are returned as the `GET` request they replace.
+    pub fn uri<C>(&self, datasource: &DataSource<C>) -> Result<Uri, Error>
+    where
+        C: Connector,
+    {
+        let builder: PathBuilder = self.clone().into();
+        #[cfg(feature = "xml")]
+        if builder.requests_xml() {
+            return datasource.request_uri(builder);
+        }
+        datasource.request_uri(builder.format(Format::Json))
+    }
+
+    /// Check that the request can be turned into a valid URI when sent to a DataSource with the given `base_path`,
+    /// without sending it. Errors are the same as would be returned when fetching the request.
+    pub fn validate(&self, base_path: &str) -> Result<(), Error> {
+        let _ = self
+            .builder
+            .clone()
+            .default_base_path(base_path.to_string())
+            .build()?;
+        Ok(())
+    }
+}
+
+impl From<GetRequest> for PathBuilder {
+    fn from(request: GetRequest) -> Self {
+        request.builder
+    }
+}
+
+/// Request a list of resources.
+#[derive(Debug, Clone)]
+pub struct ListRequest {
+    builder: PathBuilder,
+}
+
+impl ListRequest {
+    /// Create a new ListRequest, fetching all resources of type `resource_type`.
+    ///
+    /// Use a [`DataSource`] to execute the `ListRequest`
+    pub fn new<R>(resource_type: R) -> Self
+    where
+        R: Into<ResourceType>,
+    {
+        ListRequest {
+            builder: PathBuilder::new(resource_type.into().into()),
+        }
+    }
+
+    /// Change format of the returned data.
+    ///
+    /// Can be either [`Format::Json`] or [`Format::Xml`]
+    pub fn format(mut self, format: Format) -> Self {
+        self.builder = self.builder.format(format);
+        self
+    }
+
+    /// Order the returned resources by `expression`, in specified `direction`. [`Direction::Ascending`] by default.
+    ///
+    /// Repeated calls add further sort keys, used to order resources which are equal according to the previous ones,
+    /// such as `$orderby=typeid asc,dato desc`.
+    ///
+    /// The `expression` is usually just a field name, but can be any [`FilterExpr`], such as a function call:
+    /// ```rust
+    /// use odata_simple_client::{Direction, FilterExpr, ListRequest};
+    ///
+    /// let request = ListRequest::new("Dokument")
+    ///     .order_by(FilterExpr::function("tolower", ["titel".into()]), Direction::Descending);
+    /// ```
+    pub fn order_by<E>(mut self, expression: E, direction: Direction) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        self.builder = self
+            .builder
+            .order_by(&expression.into().to_string(), direction);
+        self
+    }
+
+    /// Order the returned resources by `expression` in ascending order, like [`ListRequest::order_by`].
+    pub fn order_by_asc<E>(self, expression: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        self.order_by(expression, Direction::Ascending)
+    }
+
+    /// Order the returned resources by `expression` in descending order, like [`ListRequest::order_by`].
+    pub fn order_by_desc<E>(self, expression: E) -> Self
+    where
+        E: Into<FilterExpr>,
+    {
+        self.order_by(expression, Direction::Descending)
+    }
+
+    /// Order the returned resources by `field` in the default, ascending order, like [`ListRequest::order_by`].
+    pub fn order_by_field(self, field: &str) -> Self {
+        self.order_by_asc(field)
+    }
+
+    /// Only retrieve the top `count` items.
+    pub fn top(mut self, count: u32) -> Self {
+        self.builder = self.builder.top(count);
+        self
+    }
+
+    /// Skip the first `count` items.
+    pub fn skip(mut self, count: u32) -> Self {
+        self.builder = self.builder.skip(count);
+        self
+    }
+
+    /// Request the zero-based `page` of `page_size` resources, along with the inline count of all matching resources,
+    /// by setting `$top`, `$skip` and [`InlineCount::AllPages`] together.
+    /// Use [`DataSource::fetch_page_with_total`] to fetch the page along with the total.
+    /// ```rust
+    /// use odata_simple_client::ListRequest;
+    ///
+    /// let request = ListRequest::new("Dokument").with_pagination(2, 20);
+    /// assert_eq!(
+    ///     request.to_query_string("/api").unwrap(),
+    ///     "/api/Dokument?$inlinecount=allpages&$skip=40&$top=20"
+    /// );
+    /// ```
+    pub fn with_pagination(self, page: u32, page_size: u32) -> Self {
+        self.top(page_size)
+            .skip(page.saturating_mul(page_size))
+            .inline_count(InlineCount::AllPages)
+    }
+
+    /// Include an inline count field in the odata page metadata.
+    /// Useful for gauging how many results/pages are left. By default this is not specified, which implies [`InlineCount::None`]
+    pub fn inline_count(mut self, value: InlineCount) -> Self {
+        self.builder = self.builder.inline_count(value);
+        self
+    }
+
+    /// Filter the returned results using an OData conditional expression.
+    ///
+    /// The `value` can be any [`Literal`]. String slices are used verbatim, so strings must be quoted by the caller,
+    /// or constructed using [`Literal::string`].
+    ///
+    /// Repeated calls combine the conditions using `and`, such as `id gt 10 and id lt 20`. Use [`ListRequest::filter_or`]
+    /// to combine them using `or` instead. A call with an empty `field` is ignored rather than producing a malformed
+    /// clause, so filters built dynamically can be applied unconditionally.
+    ///
+    /// See [the OData 2.0 documentation (section 4.5)](https://www.odata.org/documentation/odata-version-2-0/uri-conventions/) for more information.
+    /// ```rust
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, ListRequest, Page, Comparison};
+    /// # use serde::Deserialize;
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #
+    /// #[derive(Deserialize, Debug)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let page: Page<Dokument> = datasource
+    ///     .fetch_paged(ListRequest::new("Dokument")
+    ///         .filter("id", Comparison::Equal, "24")
+    ///     ).await.unwrap();
+    /// assert_eq!(page.value[0].titel, "Grund- og nærhedsnotat vedr. sanktioner på toldområdet")
+    /// # });
+    /// ```
+    pub fn filter<V>(mut self, field: &str, comparison: Comparison, value: V) -> Self
+    where
+        V: Into<Literal>,
+    {
+        self.builder = self.builder.filter(field, comparison, &value.into());
+        self
+    }
+
+    /// Combine a condition with the current filter using `or`, keeping results matching either.
+    ///
+    /// Conditions are combined in the order they are added, and each side is only parenthesized where operator precedence
+    /// requires it, so `filter(a).filter(b).filter_or(c)` produces `a and b or c`, while `filter(a).filter_or(b).filter(c)`
+    /// produces `(a or b) and c`. Use [`ListRequest::filter_group`] to group conditions differently.
+    /// ```rust
+    /// use odata_simple_client::{Comparison, ListRequest};
+    ///
+    /// let request = ListRequest::new("Dokument")
+    ///     .filter("typeid", Comparison::Equal, 5)
+    ///     .filter("statusid", Comparison::Equal, 1)
+    ///     .filter_or("id", Comparison::Equal, 24);
+    /// assert_eq!(
+    ///     request.to_query_string("/api").unwrap(),
+    ///     "/api/Dokument?$filter=typeid%20eq%205%20and%20statusid%20eq%201%20or%20id%20eq%2024"
+    /// );
+    /// ```
+    pub fn filter_or<V>(self, field: &str, comparison: Comparison, value: V) -> Self
+    where
+        V: Into<Literal>,
+    {
+        if field.trim().is_empty() {
+            return self;
+        }
+
+        self.filter_expr_or(FilterExpr::field(field).compare(comparison, value.into()))
+    }
+
+    /// Filter the returned results, keeping only those where every field equals its value, such as
+    /// `typeid eq 5 and titel eq 'Grund'`. Like with [`ListRequest::filter`], each value is represented according
+    /// to its type, so strings must be quoted using [`Literal::string`].
+    ///
+    /// Use [`ListRequest::filter_expr`] for more complex conditions. Pairs with an empty field are skipped,
+    /// and nothing is done if no pairs remain.
+    /// ```rust
+    /// use odata_simple_client::{ListRequest, Literal};
+    ///
+    /// let request = ListRequest::new("Dokument")
+    ///     .filter_all_eq(&[("typeid", Literal::from(5)), ("titel", Literal::string("Grund"))]);
+    /// assert_eq!(
+    ///     request.to_query_string("/api").unwrap(),
+    ///     "/api/Dokument?$filter=typeid%20eq%205%20and%20titel%20eq%20%27Grund%27"
+    /// );
+    /// ```
+    pub fn filter_all_eq<V>(self, pairs: &[(&str, V)]) -> Self
+    where
+        V: Into<Literal> + Clone,
+    {
+        let expression = pairs
+            .iter()
+            .filter(|(field, _)| !field.trim().is_empty())
+            .map(|(field, value)| {
+                FilterExpr::field(field).compare(Comparison::Equal, value.clone().into())
+            })
+            .reduce(FilterExpr::and);
+
+        match expression {
+            Some(expression) => self.filter_expr(expression),
+            None => self,
+        }
+    }
+
+    /// Filter the returned results, keeping only those where `field` equals any of `values`, such as
+    /// `typeid eq 5 or typeid eq 7`. Like with [`ListRequest::filter`], each value is represented according
+    /// to its type, so strings must be quoted using [`Literal::string`].
+    ///
+    /// Unlike the `in` operator, this is understood by OData 3.0 services as well. The comparisons are grouped
+    /// in parentheses when combined with other conditions using [`FilterExpr::and`]. Does nothing if `field` or `values` is empty.
+    /// ```rust
+    /// use odata_simple_client::{ListRequest, Literal};
+    ///
+    /// let request = ListRequest::new("Dokument").filter_any_of("typeid", &[5, 7]);
+    /// assert_eq!(
+    ///     request.to_query_string("/api").unwrap(),
+    ///     "/api/Dokument?$filter=typeid%20eq%205%20or%20typeid%20eq%207"
+    /// );
+    ///
+    /// let request = ListRequest::new("Aktør")
+    ///     .filter_any_of("fornavn", &[Literal::string("Lars"), Literal::string("Mette")]);
+    /// assert_eq!(
+    ///     request.to_query_string("/api").unwrap(),
+    ///     "/api/Akt%C3%B8r?$filter=fornavn%20eq%20%27Lars%27%20or%20fornavn%20eq%20%27Mette%27"
+    /// );
+    /// ```
+    pub fn filter_any_of<V>(self, field: &str, values: &[V]) -> Self
+    where
+        V: Into<Literal> + Clone,
+    {
+        if field.trim().is_empty() {
+            return self;
+        }
+
+        let expression = values
+            .iter()
+            .map(|value| FilterExpr::field(field).compare(Comparison::Equal, value.clone().into()))
+            .reduce(FilterExpr::or);
+
+        match expression {
+            Some(expression) => self.filter_expr(expression),
+            None => self,
+        }
+    }
+
+    /// Filter the returned results, keeping only those where `field` equals any of the typed `values`,
+    /// using the `in` operator introduced in OData 4.0, such as `id in (24,26)`.
+    ///
+    /// Each value is represented according to its type, so mixed lists of strings, numbers and guids are quoted
+    /// correctly. Use [`ListRequest::filter_any_of`] for OData 3.0 services. Does nothing if `field` or `values` is empty.
+    /// ```rust
+    /// use odata_simple_client::{ListRequest, Literal};
+    ///
+    /// let request = ListRequest::new("Dokument")
+    ///     .filter_in_typed("titel", &[Literal::string("Grund"), Literal::string("Notat")]);
+    /// assert_eq!(
+    ///     request.to_query_string("/api").unwrap(),
+    ///     "/api/Dokument?$filter=titel%20in%20%28%27Grund%27%2C%27Notat%27%29"
+    /// );
+    /// ```
+    pub fn filter_in_typed(self, field: &str, values: &[Literal]) -> Self {
+        if field.trim().is_empty() || values.is_empty() {
+            return self;
+        }
+
+        self.filter_expr(FilterExpr::field(field).is_in(values.iter().cloned()))
+    }
+
+    /// Filter the returned results using a [`FilterGroup`] of conditions constructed by `f`,
+    /// which is wrapped in parentheses. Does nothing if the group is empty.
+    /// ```rust
+    /// use odata_simple_client::ListRequest;
+    ///
+    /// let request = ListRequest::new("Dokument")
+    ///     .filter_group(|group| group.or().eq("typeid", 5).eq("typeid", 7));
+    /// ```
+    pub fn filter_group<F>(self, f: F) -> Self
+    where
+        F: FnOnce(FilterGroup) -> FilterGroup,
+    {
+        match f(FilterGroup::default()).into_expr() {
+            Some(expression) => self.filter_expr(expression),
+            None => self,
+        }
+    }
+
+    /// Filter the returned results by comparing two fields of each resource, such as `startdato lt slutdato`.
+    /// Both sides are emitted as field references, so the right-hand side isn't quoted. Does nothing if either field is empty.
+    pub fn filter_fields(self, left: &str, comparison: Comparison, right: &str) -> Self {
+        if left.trim().is_empty() || right.trim().is_empty() {
+            return self;
+        }
+
+        self.filter_expr(FilterExpr::field(left).compare(comparison, FilterExpr::field(right)))
+    }
+
+    /// Filter the returned results on a boolean `field`, emitting `field eq true` or `field eq false`.
+    pub fn filter_bool(mut self, field: &str, value: bool) -> Self {
+        self.builder = self
+            .builder
+            .filter(field, Comparison::Equal, &Literal::from(value));
+        self
+    }
+
+    /// Filter the returned results on the number of entities in the `collection` navigation property,
+    /// emitting `collection/$count <comparison> value`, such as `Fil/$count gt 5`.
+    ///
+    /// Counting navigation properties within a filter was introduced in OData 4.0.
+    pub fn filter_count(mut self, collection: &str, comparison: Comparison, value: u64) -> Self {
+        self.builder = self.builder.filter(
+            &format!("{collection}/$count"),
+            comparison,
+            &Literal::from(value),
+        );
+        self
+    }
+
+    /// Filter the returned results using a [`FilterExpr`], such as one built using the [`odata_filter!`] macro.
+    ///
+    /// The expression is combined with any previous filter using `and`.
+    /// ```rust
+    /// use odata_simple_client::{odata_filter, ListRequest};
+    ///
+    /// let request = ListRequest::new("Dokument")
+    ///     .filter_expr(odata_filter!(typeid == 5 and startswith(titel, "Grund")));
+    /// ```
+    pub fn filter_expr(mut self, expression: FilterExpr) -> Self {
+        self.builder = self.builder.filter_expr(&expression);
+        self
+    }
+
+    /// Filter the returned results using an already formed OData filter `expression`, such as
+    /// `year(dato) eq 2020`, combined with any other filters using `and`.
+    ///
+    /// The expression is URL-encoded, but otherwise sent as-is, so string literals within it must be quoted
+    /// with any single quotes doubled. Prefer [`ListRequest::filter_expr`] when values come from user input.
+    /// ```rust
+    /// use odata_simple_client::ListRequest;
+    ///
+    /// let request = ListRequest::new("Dokument").filter_raw("substringof('nnotat', titel)");
+    /// assert_eq!(
+    ///     request.to_query_string("/api").unwrap(),
+    ///     "/api/Dokument?$filter=substringof%28%27nnotat%27%2C%20titel%29"
+    /// );
+    /// ```
+    pub fn filter_raw(mut self, expression: &str) -> Self {
+        self.builder = self.builder.filter_raw(expression);
+        self
+    }
+
+    /// Combine a [`FilterExpr`] with the current filter using `or`, like [`ListRequest::filter_or`].
+    pub fn filter_expr_or(mut self, expression: FilterExpr) -> Self {
+        self.builder = self.builder.filter_expr_or(&expression);
+        self
+    }
+
+    /// Filter the returned results on flag enums, keeping only results where `field` has the `member` flag
+    /// of the enumeration type `type_name` set, such as `AccessRights has Namespace.Permission'Read'`.
+    ///
+    /// The `has` operator was introduced in OData 4.0.
+    pub fn filter_has(mut self, field: &str, type_name: &str, member: &str) -> Self {
+        self.builder = self
+            .builder
+            .filter_has(field, &Literal::enum_value(type_name, member));
+        self
+    }
+
+    /// Add a query option the request builder doesn't model, such as `$skiptoken` or `$apply`, with its `value`
+    /// URL-encoded. The leading `$` of the `key` is optional, and setting the same key again replaces its value.
+    ///
+    /// Options set by dedicated methods, such as `$filter` or `$top`, can't be set this way, and are ignored
+    /// with a warning, so they can't be clobbered by mistake.
+    /// ```rust
+    /// use odata_simple_client::ListRequest;
+    ///
+    /// let request = ListRequest::new("Dokument").raw_param("$skiptoken", "24");
+    /// assert_eq!(request.to_query_string("/api").unwrap(), "/api/Dokument?$skiptoken=24");
+    /// ```
+    pub fn raw_param(mut self, key: &'static str, value: &str) -> Self {
+        self.builder = self.builder.raw_param(key, value);
+        self
+    }
+
+    /// Expand specific relations of the returned object, if possible.
+    ///
+    /// For the [Folketinget API](https://oda.ft.dk) for example, you can expand the `DokumentAktør` field of a `Dokument`, to simultaneously retrieve information about the document authors, instead of having to do two separate lookups for the `DokumentAktør` relation and then the actual `Aktør`.
+    pub fn expand<'f, F>(mut self, field: F) -> Self
+    where
+        F: IntoIterator<Item = &'f str>,
+    {
+        self.builder = self.builder.expand(field);
+        self
+    }
+
+    /// Expand a navigation property using OData v4 nested query options, such as a nested `$select`
+    /// restricting which of its properties are retrieved.
+    ///
+    /// Properties of the navigation selected using a path at the top level, such as `Fil/titel`, are moved into
+    /// the nested `$select`, since v4 servers only apply the top-level `$select` to the resource itself.
+    /// The navigation property itself is still selected at the top level, so the projection of the resource is unchanged.
+    /// See [`ExpandOption`] for details.
+    pub fn expand_with(mut self, option: ExpandOption) -> Self {
+        self.builder = self.builder.expand_with(option);
+        self
+    }
+
+    /// Expand every navigation property of the resource using `$expand=*`, introduced in OData 4.0.
+    ///
+    /// This replaces any properties previously passed to `expand`, and any properties passed afterwards are ignored,
+    /// since they are already included.
+    pub fn expand_all(mut self) -> Self {
+        self.builder = self.builder.expand_all();
+        self
+    }
+
+    /// Only retrieve specific properties of the returned object using `$select`, to reduce the size of the response.
+    ///
+    /// Repeated calls append to the list of selected properties. Replaces the default set using
+    /// [`DataSource::with_default_select`], unless [`DataSource::with_extend_default_select`] is enabled.
+    pub fn select<'f, F>(mut self, field: F) -> Self
+    where
+        F: IntoIterator<Item = &'f str>,
+    {
+        self.builder = self.builder.select(field);
+        self
+    }
+
+    /// Define a computed property using an OData v4 `$compute` expression, e.g. `Price mul Qty` as `Total`.
+    ///
+    /// The `alias` can be referenced in subsequent calls such as [`ListRequest::order_by`]. Repeated calls append to the list of computed properties.
+    pub fn compute(mut self, expression: &str, alias: &str) -> Self {
+        self.builder = self.builder.compute(expression, alias);
+        self
+    }
+
+    /// Send this request to `base_path` instead of the base path of the [`DataSource`] executing it,
+    /// which is useful when a single host serves multiple OData services.
+    ///
+    /// The path replaces the entire service root, including any version set using [`DataSource::with_api_version`].
+    pub fn base_path(mut self, base_path: &str) -> Self {
+        self.builder = self
+            .builder
+            .base_path(base_path.trim_end_matches('/').to_string());
+        self
+    }
+
+    /// Ask the server to omit null-valued properties from the response, using the OData v4 `Prefer: omit-values=nulls` header.
+    ///
+    /// Missing properties deserialize fine into `Option` fields, so this only reduces the size of the response.
+    pub fn omit_nulls(mut self) -> Self {
+        self.builder = self.builder.prefer("omit-values=nulls");
+        self
+    }
+
+    /// Send the header `name` along with this request, replacing any value set for it by the [`DataSource`],
+    /// or by the request itself. Repeated calls for the same header send every value.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.builder = self.builder.header(name, value);
+        self
+    }
+
+    /// Ask for the connection to be closed after this request using a `Connection: close` header, like
+    /// [`GetRequest::fresh_connection`].
+    ///
+    /// Only the first page is requested this way, pages followed using their `next_link` are fetched with
+    /// the headers configured on the [`DataSource`].
+    pub fn fresh_connection(self) -> Self {
+        self.header(hyper::header::CONNECTION, HeaderValue::from_static("close"))
+    }
+
+    /// Ask the server to return at most `size` resources per page, using the `Prefer: odata.maxpagesize=N` header.
+    ///
+    /// Unlike [`ListRequest::top`], the remaining resources are still available by following the `next_link`
+    /// of each page. Servers may ignore the preference, which can be detected using [`DataSource::fetch_paged_with_preferences`].
+    pub fn max_page_size(mut self, size: u32) -> Self {
+        self.builder = self.builder.prefer(&format!("odata.maxpagesize={size}"));
+        self
+    }
+
+    /// Ask the server to respond synchronously if it can complete the request within `wait`,
+    /// using the `Prefer: wait=N` header, with the duration truncated to whole seconds.
+    ///
+    /// Combined with [`DataSource::fetch_async`], the server only falls back to processing the request
+    /// asynchronously if it takes longer than that, in which case [`AsyncResponse::Accepted`] is returned.
+    pub fn wait(mut self, wait: Duration) -> Self {
+        self.builder = self.builder.prefer(&format!("wait={}", wait.as_secs()));
+        self
+    }
+
+    /// Append a path segment after the resource, such as a navigation property or the namespace-qualified name
+    /// of a bound function, as in `Products(1)/Model.Rate`. Repeated calls append further segments.
+    pub fn segment(mut self, segment: &str) -> Self {
+        self.builder = self.builder.segment(segment);
+        self
+    }
+
+    /// Only list the resources of the derived type `qualified_type` within a polymorphic entity set,
+    /// by appending the namespace-qualified type name after the resource type, as in `Vehicles/Model.Car`.
+    ///
+    /// Unlike filtering on a [`FilterExpr::cast`], the entity set is narrowed to the derived type itself,
+    /// so the properties of the derived type can be used directly in the rest of the request.
+    /// ```rust
+    /// use odata_simple_client::ListRequest;
+    ///
+    /// let request = ListRequest::new("Vehicles").of_type("Model.Car");
+    /// assert_eq!(request.to_query_string("/api").unwrap(), "/api/Vehicles/Model.Car");
+    /// ```
+    pub fn of_type(self, qualified_type: &str) -> Self {
+        self.segment(qualified_type)
+    }
+
+    /// Render the path and query of the request relative to `base_path`, without requiring a [`DataSource`].
+    /// See [`GetRequest::to_query_string`].
+    /// ```rust
+    /// use odata_simple_client::{Direction, ListRequest};
+    ///
+    /// let request = ListRequest::new("Dokument").order_by("id", Direction::Descending).top(5);
+    /// assert_eq!(
+    ///     request.to_query_string("/api").unwrap(),
+    ///     "/api/Dokument?$orderby=id%20desc&$top=5"
+    /// );
+    /// ```
+    pub fn to_query_string(&self, base_path: &str) -> Result<String, Error> {
+        render(self.clone().into(), base_path)
+    }
+
+    /// The complete [`Uri`] [`DataSource::fetch_paged`] would request for this request from `datasource`,
+    /// like [`GetRequest::uri`].
+    /// ```rust
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{Comparison, DataSource, ListRequest};
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// let request = ListRequest::new("Dokument").filter("typeid", Comparison::Equal, 5);
+    ///
+    /// assert_eq!(
+    ///     request.uri(&datasource).unwrap(),
+    ///     "https://oda.ft.dk/api/Dokument?$filter=typeid%20eq%205&$format=json"
+    /// );
+    /// ```
+    pub fn uri<C>(&self, datasource: &DataSource<C>) -> Result<Uri, Error>
+    where
+        C: Connector,
+    {
+        datasource.request_uri(PathBuilder::from(self.clone()).format(Format::Json))
+    }
+
+    /// Check that the request can be turned into a valid URI when sent to a DataSource with the given `base_path`,
+    /// without sending it. Errors are the same as would be returned when fetching the request.
+    pub fn validate(&self, base_path: &str) -> Result<(), Error> {
+        let _ = self
+            .builder
+            .clone()
+            .default_base_path(base_path.to_string())
+            .build()?;
+        Ok(())
+    }
+
+    /// Request only the number of resources matching this request, keeping all the filters applied so far.
+    ///
+    /// The returned [`CountRequest`] can be [`DataSource::fetch`]ed like a [`GetRequest`], yielding a number.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, ListRequest, Comparison};
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// # tokio_test::block_on(async {
+    /// let count: u64 = datasource
+    ///     .fetch(ListRequest::new("Dokument")
+    ///         .filter("typeid", Comparison::Equal, 5)
+    ///         .count_only()
+    ///     ).await.unwrap();
+    /// # });
+    /// ```
+    pub fn count_only(self) -> CountRequest {
+        CountRequest {
+            builder: self.builder.count(),
+        }
+    }
+}
+
+/// Request the number of resources matching a [`ListRequest`]. Constructed using [`ListRequest::count_only`].
+#[derive(Debug, Clone)]
+pub struct CountRequest {
+    builder: PathBuilder,
+}
+
+impl From<CountRequest> for GetRequest {
+    fn from(request: CountRequest) -> Self {
+        GetRequest {
+            builder: request.builder,
+        }
+    }
+}
+
+impl From<ListRequest> for PathBuilder {
+    fn from(request: ListRequest) -> Self {
+        request.builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::omits_control_information;
+    use crate::{
+        mock, Comparison, DataSource, Error, FormatNegotiation, GetRequest, HeaderMap, HeaderName,
+        HeaderValue, JsonEnvelope, ListRequest, Literal, ODataVersion, Page, RedirectPolicy,
+        Scheme,
+    };
+    use hyper::{Body, Method, Response, StatusCode};
+    use serde_json::Value;
+    use std::{
+        borrow::Cow,
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    /// Serves three pages of `Dokument`, linking them together using absolute, root-relative and relative next links.
+    fn paged(request: &mock::RecordedRequest) -> Response<Body> {
+        match request.uri.query().unwrap_or_default() {
+            query if query.contains("$skip=2") => mock::json(200, r#"{"value": [3]}"#),
+            query if query.contains("$skip=1") => mock::json(
+                200,
+                r#"{"value": [2], "odata.nextLink": "Dokument?$skip=2"}"#,
+            ),
+            _ => mock::json(
+                200,
+                r#"{"value": [1], "odata.nextLink": "https://oda.ft.dk/api/Dokument?$skip=1"}"#,
+            ),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pages() {
+        let (datasource, connector) = mock::datasource(paged);
+
+        let all: Vec<u32> = datasource
+            .fetch_pages(ListRequest::new("Dokument"), 10)
+            .await
+            .unwrap();
+        assert_eq!(all, vec![1, 2, 3]);
+
+        let uris: Vec<_> = connector
+            .requests()
+            .iter()
+            .map(|request| request.uri.to_string())
+            .collect();
+        assert_eq!(
+            uris,
+            [
+                "/api/Dokument?$format=json",
+                "/api/Dokument?$skip=1",
+                "/api/Dokument?$skip=2"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_encoded_next_links() {
+        let (datasource, connector) = mock::datasource(|request| {
+            match request.uri.query().unwrap_or_default() {
+                "$format=json" => mock::json(
+                    200,
+                    r#"{"value": [1], "odata.nextLink": "Dokument?$filter=titel%20eq%20%27a%20b%27&$skip=1"}"#,
+                ),
+                "$filter=titel%20eq%20%27a%20b%27&$skip=1" => mock::json(
+                    200,
+                    r#"{"value": [2], "odata.nextLink": "https://oda.ft.dk/api/Dokument?$orderby=id%20asc&$skip=2"}"#,
+                ),
+                _ => mock::json(200, r#"{"value": [3]}"#),
+            }
+        });
+
+        let all: Vec<u32> = datasource
+            .fetch_pages(ListRequest::new("Dokument"), 10)
+            .await
+            .unwrap();
+        assert_eq!(all, vec![1, 2, 3]);
+
+        let requests = connector.requests();
+        assert_eq!(
+            requests[1].uri,
+            "/api/Dokument?$filter=titel%20eq%20%27a%20b%27&$skip=1"
+        );
+        assert_eq!(requests[2].uri, "/api/Dokument?$orderby=id%20asc&$skip=2");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_pages_bounded() {
+        let (datasource, connector) = mock::datasource(paged);
+
+        let first: Vec<u32> = datasource
+            .fetch_pages(ListRequest::new("Dokument"), 1)
+            .await
+            .unwrap();
+        assert_eq!(first, vec![1]);
+        assert_eq!(connector.requests().len(), 1);
+
+        let none: Vec<u32> = datasource
+            .fetch_pages(ListRequest::new("Dokument"), 0)
+            .await
+            .unwrap();
+        assert!(none.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_api_version() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, "{}"));
+
+        let datasource = datasource.with_api_version("v2");
+        let _: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+
+        let datasource = datasource.with_api_version("/v3/");
+        datasource.ping().await.unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/api/v2/Dokument(24)?$format=json");
+        assert_eq!(requests[1].uri, "/api/v3/");
+    }
+
+    #[tokio::test]
+    async fn test_base_path_override() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"value": []}"#));
+
+        let _: Page<Value> = datasource
+            .fetch_paged(ListRequest::new("Dokument").base_path("/other/"))
+            .await
+            .unwrap();
+        let _: Page<Value> = datasource
+            .fetch_paged(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/other/Dokument?$format=json");
+        assert_eq!(requests[1].uri, "/api/Dokument?$format=json");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_from_chunks() {
+        let (datasource, _) = mock::datasource(|_| mock::json(200, r#"{"titel": "Grund"}"#));
+
+        let dokument: Value = datasource
+            .fetch_from_chunks(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(dokument["titel"], "Grund");
+
+        let (datasource, _) = mock::datasource(|_| mock::json(200, "{"));
+        let result = datasource
+            .fetch_from_chunks::<Value>(GetRequest::new("Dokument", 24))
+            .await;
+        assert!(matches!(result, Err(Error::Serde(_, _))));
+
+        let (datasource, _) = mock::datasource(|_| mock::json(200, r#"{"d": {"titel": "Grund"}}"#));
+        let dokument: Value = datasource
+            .with_json_envelope(JsonEnvelope::Verbose)
+            .fetch_from_chunks(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(dokument["titel"], "Grund");
+
+        let (datasource, _) = mock::datasource(|_| mock::json(404, r#"{"titel": "Grund"}"#));
+        let result = datasource
+            .fetch_from_chunks::<Value>(GetRequest::new("Dokument", 24))
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::Status {
+                code: StatusCode::NOT_FOUND,
+                ..
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_omit_nulls() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, "{}"));
+
+        let _: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24).omit_nulls())
+            .await
+            .unwrap();
+        let _: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].headers["Prefer"], "omit-values=nulls");
+        assert!(!requests[1].headers.contains_key("Prefer"));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_result() {
+        let (datasource, _) = mock::datasource(|request| {
+            if request.uri.path().ends_with("(24)") {
+                mock::json(200, "24")
+            } else {
+                mock::json(404, r#"{"message": "not found"}"#)
+            }
+        });
+
+        let found: Result<u32, Value> = datasource
+            .fetch_result(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(found.unwrap(), 24);
+
+        let missing: Result<u32, Value> = datasource
+            .fetch_result(GetRequest::new("Dokument", 25))
+            .await
+            .unwrap();
+        assert_eq!(missing.unwrap_err()["message"], "not found");
+    }
+
+    #[tokio::test]
+    async fn test_entity_set_exists() {
+        let (datasource, connector) = mock::datasource(|request| match request.uri.path() {
+            "/api/Dokument" => mock::json(200, r#"{"value": []}"#),
+            _ => mock::json(404, r#"{"odata.error": {}}"#),
+        });
+
+        assert!(datasource.entity_set_exists("Dokument").await.unwrap());
+        assert!(!datasource.entity_set_exists("Dokumnet").await.unwrap());
+        assert_eq!(
+            connector.requests()[0].uri,
+            "/api/Dokument?$format=json&$top=0"
+        );
+
+        let datasource = datasource.with_entity_sets(["Aktør", "Dokument"]);
+        assert!(datasource.entity_set_exists("aktør").await.unwrap());
+        assert!(!datasource.entity_set_exists("Sag").await.unwrap());
+        assert_eq!(connector.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_correlation_header() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, "24"));
+        let counter = AtomicUsize::new(0);
+        let datasource = datasource.with_correlation_header("X-Request-ID", move || {
+            counter.fetch_add(1, Ordering::Relaxed).to_string()
+        });
+
+        for _ in 0..2 {
+            let _: u32 = datasource
+                .fetch(GetRequest::new("Dokument", 24))
+                .await
+                .unwrap();
+        }
+
+        let ids: Vec<_> = connector
+            .requests()
+            .iter()
+            .map(|request| request.headers["X-Request-ID"].clone())
+            .collect();
+        assert_eq!(ids, ["0", "1"]);
+    }
+
+    #[test]
+    fn test_omits_control_information() {
+        let response = |content_type: &str| {
+            Response::builder()
+                .header("Content-Type", content_type)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        assert!(omits_control_information(&response(
+            "application/json;odata.metadata=none;charset=utf-8"
+        )));
+        assert!(omits_control_information(&response(
+            "application/json; odata=nometadata"
+        )));
+        assert!(!omits_control_information(&response(
+            "application/json;odata.metadata=minimal"
+        )));
+        assert!(!omits_control_information(&Response::new(Body::empty())));
+    }
+
+    #[tokio::test]
+    async fn test_incomplete_body() {
+        let (datasource, _) = mock::datasource(|_| {
+            // The connection is only dropped once the first chunk had time to be flushed to the client.
+            let first = futures_util::stream::iter([Ok(String::from(r#"{"titel": "#))]);
+            let dropped = futures_util::stream::once(async {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Err(std::io::Error::from(std::io::ErrorKind::ConnectionReset))
+            });
+            let chunks = futures_util::StreamExt::chain(first, dropped);
+            Response::builder()
+                .header("Content-Length", "50")
+                .body(Body::wrap_stream(chunks))
+                .unwrap()
+        });
+
+        let error = datasource
+            .fetch::<Value>(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(
+                error,
+                Error::IncompleteBody {
+                    expected: 50,
+                    received: 10
+                }
+            ),
+            "{error:?}"
+        );
+        assert!(error.is_retriable());
+
+        let status = |code: u16| Error::Status {
+            code: StatusCode::from_u16(code).unwrap(),
+            body: String::new(),
+        };
+        assert!(status(503).is_retriable());
+        assert!(status(429).is_retriable());
+        assert!(status(504).is_retriable());
+        assert!(!status(500).is_retriable());
+        assert!(!status(404).is_retriable());
+    }
+
+    #[test]
+    fn test_retry_on_status() {
+        let (datasource, _) = mock::datasource(|_| mock::json(200, "{}"));
+        let status = |code: u16| Error::Status {
+            code: StatusCode::from_u16(code).unwrap(),
+            body: String::new(),
+        };
+        assert!(!datasource.is_retriable(&status(409)));
+
+        let datasource = datasource.with_retry_on_status(&[StatusCode::CONFLICT]);
+        assert!(datasource.is_retriable(&status(409)));
+        assert!(datasource.is_retriable(&status(503)));
+        assert!(!datasource.is_retriable(&status(404)));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_page_with_total() {
+        let (datasource, connector) =
+            mock::datasource(|_| mock::json(200, r#"{"value": [21, 22], "odata.count": "42"}"#));
+
+        let (values, total) = datasource
+            .fetch_page_with_total::<u32>(ListRequest::new("Dokument").with_pagination(1, 20))
+            .await
+            .unwrap();
+        assert_eq!(values, [21, 22]);
+        assert_eq!(total, Some(42));
+        assert_eq!(
+            connector.requests()[0].uri,
+            "/api/Dokument?$format=json&$inlinecount=allpages&$skip=20&$top=20"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_from_env() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, ""));
+        let client = datasource.client.clone();
+
+        match DataSource::from_env(client.clone(), "ODATA_TEST_FROM_ENV") {
+            Err(Error::Environment(name)) => assert_eq!(name, "ODATA_TEST_FROM_ENV_HOST"),
+            other => panic!("expected missing host, got {other:?}"),
+        }
+
+        std::env::set_var("ODATA_TEST_FROM_ENV_HOST", "oda.ft.dk");
+        std::env::set_var("ODATA_TEST_FROM_ENV_BASE_PATH", "/api");
+        std::env::set_var("ODATA_TEST_FROM_ENV_TOKEN", "secret");
+        let datasource = DataSource::from_env(client, "ODATA_TEST_FROM_ENV").unwrap();
+        datasource.ping().await.unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/api/");
+        assert_eq!(requests[0].headers["Authorization"], "Bearer secret");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_into() {
+        let (datasource, connector) = mock::datasource(|request| match request.uri.path() {
+            "/api/Dokument(24)" => mock::json(200, r#"{"id": 24}"#),
+            _ => mock::json(404, r#"{"odata.error": {}}"#),
+        });
+
+        let mut buffer = Vec::new();
+        let written = datasource
+            .fetch_into(GetRequest::new("Dokument", 24), &mut buffer)
+            .await
+            .unwrap();
+        assert_eq!(written, 10);
+        assert_eq!(buffer, br#"{"id": 24}"#);
+        assert_eq!(connector.requests()[0].uri, "/api/Dokument(24)?");
+
+        let mut buffer = Vec::new();
+        assert!(matches!(
+            datasource
+                .fetch_into(GetRequest::new("Dokument", 26), &mut buffer)
+                .await,
+            Err(Error::Status { .. })
+        ));
+        assert!(buffer.is_empty());
+    }
+
+    #[cfg(not(feature = "simd-json"))]
+    #[tokio::test]
+    async fn test_raw_value() {
+        use serde::Deserialize;
+        use serde_json::value::RawValue;
+
+        #[derive(Deserialize)]
+        struct Dokument {
+            id: usize,
+            #[serde(rename = "Fil")]
+            filer: Box<RawValue>,
+        }
+
+        let (datasource, _) =
+            mock::datasource(|_| mock::json(200, r#"{"id": 24, "Fil": [{"id": 1}, {"id": 2}]}"#));
+
+        let dokument: Dokument = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(dokument.id, 24);
+        assert_eq!(dokument.filer.get(), r#"[{"id": 1}, {"id": 2}]"#);
+
+        let dokument: Dokument = datasource
+            .fetch_from_chunks(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(dokument.filer.get(), r#"[{"id": 1}, {"id": 2}]"#);
+    }
+
+    #[tokio::test]
+    async fn test_scheme_and_port() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"value": []}"#));
+        let datasource = datasource.with_scheme(Scheme::HTTP).with_port(8080);
+
+        assert_eq!(
+            datasource.resolve_link("Dokument?$skip=2").unwrap(),
+            "http://oda.ft.dk:8080/api/Dokument?$skip=2"
+        );
+
+        let _: Page<Value> = datasource
+            .fetch_paged(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+        assert_eq!(connector.requests()[0].headers["Host"], "oda.ft.dk:8080");
+    }
+
+    #[tokio::test]
+    async fn test_default_headers() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"value": []}"#));
+
+        let mut headers = HeaderMap::new();
+        let _ = headers.insert("Accept-Language", HeaderValue::from_static("da"));
+        let _ = headers.insert("Accept", HeaderValue::from_static("application/xml"));
+        let _ = headers.insert("Authorization", HeaderValue::from_static("Basic b2RhdGE="));
+        let datasource = datasource.with_headers(headers).with_bearer_token("secret");
+
+        let _: Page<Value> = datasource
+            .fetch_paged(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+
+        let request = &connector.requests()[0];
+        assert_eq!(request.headers["Accept-Language"], "da");
+        assert_eq!(request.headers["Authorization"], "Bearer secret");
+        assert_eq!(request.headers["Accept"], "application/json");
+    }
+
+    #[tokio::test]
+    async fn test_request_headers() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"value": []}"#));
+        let datasource = datasource
+            .with_header(
+                HeaderName::from_static("x-api-key"),
+                HeaderValue::from_static("secret"),
+            )
+            .with_header(
+                HeaderName::from_static("accept-language"),
+                HeaderValue::from_static("da"),
+            );
+
+        let _: Page<Value> = datasource
+            .fetch_paged(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+        let _: Page<Value> = datasource
+            .fetch_paged(
+                ListRequest::new("Dokument")
+                    .header(
+                        HeaderName::from_static("accept-language"),
+                        HeaderValue::from_static("en"),
+                    )
+                    .header(
+                        hyper::header::ACCEPT,
+                        HeaderValue::from_static("application/xml"),
+                    )
+                    .header(
+                        hyper::header::ACCEPT,
+                        HeaderValue::from_static("application/json"),
+                    ),
+            )
+            .await
+            .unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].headers["X-Api-Key"], "secret");
+        assert_eq!(requests[0].headers["Accept-Language"], "da");
+        assert_eq!(requests[1].headers["X-Api-Key"], "secret");
+        assert_eq!(requests[1].headers["Accept-Language"], "en");
+        let accept: Vec<_> = requests[1].headers.get_all("Accept").iter().collect();
+        assert_eq!(accept, ["application/xml", "application/json"]);
+    }
+
+    #[tokio::test]
+    async fn test_host_header() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, ""));
+
+        datasource.ping().await.unwrap();
+        let datasource = datasource.with_host_header("internal.example");
+        datasource.ping().await.unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].headers["Host"], "oda.ft.dk");
+        assert_eq!(requests[1].headers["Host"], "internal.example");
+    }
+
+    #[tokio::test]
+    async fn test_serde_error_source() {
+        let (datasource, _) = mock::datasource(|_| mock::json(200, "not json"));
+
+        let error = datasource
+            .fetch::<Value>(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap_err();
+
+        let source = std::error::Error::source(&error).unwrap();
+        assert!(source.is::<serde_json::Error>());
+        assert!(matches!(error, Error::Serde(_, body) if body == "not json"));
+    }
+
+    #[tokio::test]
+    async fn test_count_only() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, "42"));
+
+        let count: u64 = datasource
+            .fetch(ListRequest::new("Dokument").top(5).count_only())
+            .await
+            .unwrap();
+        assert_eq!(count, 42);
+        assert_eq!(connector.requests()[0].uri, "/api/Dokument/$count?$top=5");
+    }
+
+    #[tokio::test]
+    async fn test_request_uri() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"value": []}"#));
+        let request = ListRequest::new("Dokument")
+            .filter("typeid", Comparison::Equal, 5)
+            .top(10);
+
+        let uri = request.uri(&datasource).unwrap();
+        assert_eq!(
+            uri,
+            "https://oda.ft.dk/api/Dokument?$filter=typeid%20eq%205&$format=json&$top=10"
+        );
+        let _: Page<Value> = datasource.fetch_paged(request.clone()).await.unwrap();
+        assert_eq!(
+            connector.requests()[0].uri,
+            uri.path_and_query().unwrap().as_str()
+        );
+
+        let datasource = datasource
+            .with_default_select(vec![String::from("id")])
+            .with_format_negotiation(FormatNegotiation::AcceptHeader);
+        let uri = GetRequest::new("Dokument", 24).uri(&datasource).unwrap();
+        assert_eq!(uri, "https://oda.ft.dk/api/Dokument(24)?$select=id");
+    }
+
+    #[tokio::test]
+    async fn test_count() {
+        let (datasource, connector) =
+            mock::datasource(|request| match request.uri.query().unwrap_or_default() {
+                "" => mock::json(200, "ERROR"),
+                _ => mock::json(200, "42\r\n"),
+            });
+
+        let count = datasource
+            .count(ListRequest::new("Dokument").filter("typeid", Comparison::Equal, "3"))
+            .await
+            .unwrap();
+        assert_eq!(count, 42);
+        assert_eq!(
+            connector.requests()[0].uri,
+            "/api/Dokument/$count?$filter=typeid%20eq%203"
+        );
+
+        assert!(matches!(
+            datasource.count(ListRequest::new("Dokument")).await,
+            Err(Error::Serde(_, content)) if content == "ERROR"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_cross_host_next_link() {
+        let external = |request: &mock::RecordedRequest| match request.uri.query() {
+            Some("$format=json") => mock::json(
+                200,
+                r#"{"value": [1], "odata.nextLink": "https://mirror.example/api/Dokument?$skip=1"}"#,
+            ),
+            _ => mock::json(200, r#"{"value": [2]}"#),
+        };
+
+        let (datasource, connector) = mock::datasource(external);
+        let values: Vec<u32> = datasource
+            .fetch_pages(ListRequest::new("Dokument"), 2)
+            .await
+            .unwrap();
+        assert_eq!(values, vec![1, 2]);
+        assert_eq!(connector.requests()[1].headers["Host"], "mirror.example");
+
+        let (datasource, connector) = mock::datasource(external);
+        let datasource = datasource.with_strict_next_links(true);
+        match datasource
+            .fetch_pages::<u32>(ListRequest::new("Dokument"), 2)
+            .await
+        {
+            Err(Error::CrossHostNextLink(link)) => {
+                assert_eq!(link, "https://mirror.example/api/Dokument?$skip=1")
+            }
+            other => panic!("expected cross host error, got {other:?}"),
+        }
+        assert_eq!(connector.requests().len(), 1);
+
+        let (datasource, _) = mock::datasource(|request| match request.uri.query() {
+            Some("$format=json") => mock::json(
+                200,
+                r#"{"value": [1], "odata.nextLink": "https://oda.ft.dk:443/api/Dokument?$skip=1"}"#,
+            ),
+            _ => mock::json(200, r#"{"value": [2]}"#),
+        });
+        let datasource = datasource.with_strict_next_links(true);
+        let values: Vec<u32> = datasource
+            .fetch_pages(ListRequest::new("Dokument"), 2)
+            .await
+            .unwrap();
+        assert_eq!(values, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_same_origin() {
+        let (datasource, connector) = mock::datasource(|request| match request.uri.path() {
+            "/api/Dokument(24)" => Response::builder()
+                .status(301)
+                .header("Location", "http://oda.ft.dk/api/v2/Dokument(24)")
+                .body(Body::empty())
+                .unwrap(),
+            _ => mock::json(200, r#"{"id": 24}"#),
+        });
+        let datasource = datasource
+            .with_redirect_policy(RedirectPolicy::Limited(5))
+            .with_bearer_token("secret");
+
+        let _: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        let requests = connector.requests();
+        assert_eq!(requests[0].headers["Authorization"], "Bearer secret");
+        assert!(!requests[1].headers.contains_key("Authorization"));
+
+        for (uri, same) in [
+            ("https://oda.ft.dk/api", true),
+            ("https://ODA.ft.dk:443/api", true),
+            ("http://oda.ft.dk/api", false),
+            ("https://oda.ft.dk:8443/api", false),
+            ("https://mirror.example/api", false),
+            ("/api", false),
+        ] {
+            assert_eq!(
+                datasource.is_same_origin(&uri.parse().unwrap()),
+                same,
+                "{uri}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_headers_cross_origin() {
+        let (datasource, connector) = mock::datasource(|request| match request.uri.path() {
+            _ if request.headers["Host"] == "mirror.example" => {
+                mock::json(200, r#"{"id": 24, "value": [2]}"#)
+            }
+            "/api/Dokument(24)" => Response::builder()
+                .status(307)
+                .header("Location", "https://mirror.example/api/Dokument(24)")
+                .body(Body::empty())
+                .unwrap(),
+            "/api/Dokument" => mock::json(
+                200,
+                r#"{"value": [1], "odata.nextLink": "https://mirror.example/api/Dokument?$skip=1"}"#,
+            ),
+            _ => mock::json(404, "{}"),
+        });
+        let datasource = datasource
+            .with_redirect_policy(RedirectPolicy::Limited(5))
+            .with_header(
+                HeaderName::from_static("x-api-key"),
+                HeaderValue::from_static("secret"),
+            );
+
+        let _: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24).header(
+                hyper::header::PROXY_AUTHORIZATION,
+                HeaderValue::from_static("Basic b2RhdGE="),
+            ))
+            .await
+            .unwrap();
+        let _: Vec<u32> = datasource
+            .fetch_pages(ListRequest::new("Dokument"), 2)
+            .await
+            .unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests.len(), 4);
+        assert_eq!(requests[0].headers["x-api-key"], "secret");
+        assert_eq!(requests[0].headers["Proxy-Authorization"], "Basic b2RhdGE=");
+        assert_eq!(requests[2].headers["x-api-key"], "secret");
+        for request in [&requests[1], &requests[3]] {
+            assert_eq!(request.headers["Host"], "mirror.example");
+            assert!(!request.headers.contains_key("x-api-key"));
+            assert!(!request.headers.contains_key("Proxy-Authorization"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_verbose_envelope() {
+        let (datasource, _) = mock::datasource(|request| match request.uri.query() {
+            Some("$skip=1") => mock::json(200, r#"{"d": {"results": [2]}}"#),
+            _ => mock::json(
+                200,
+                r#"{"d": {"results": [1], "__next": "Dokument?$skip=1"}}"#,
+            ),
+        });
+        let datasource = datasource.with_json_envelope(JsonEnvelope::Verbose);
+
+        let values: Vec<u32> = datasource
+            .fetch_pages(ListRequest::new("Dokument"), 2)
+            .await
+            .unwrap();
+        assert_eq!(values, vec![1, 2]);
+    }
+
+    #[test]
+    fn test_validate() {
+        assert!(GetRequest::new("Dokument", 24).validate("/api").is_ok());
+        assert!(ListRequest::new("Dokument").top(5).validate("").is_ok());
+        assert!(ListRequest::new("Dokument").validate("/my api/v1").is_ok());
+        assert_eq!(
+            ListRequest::new("Dokument")
+                .to_query_string("/my api/v1")
+                .unwrap(),
+            "/my%20api/v1/Dokument"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_raw_get() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(404, ""));
+        let datasource = datasource.with_odata_version(ODataVersion::V3);
+
+        let uri = "https://oda.ft.dk/api/Dokument?$top=1".parse().unwrap();
+        let response = datasource.raw_get(uri).await.unwrap();
+        assert_eq!(response.status(), 404);
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/api/Dokument?$top=1");
+        assert_eq!(requests[0].headers["DataServiceVersion"], "3.0");
+    }
+
+    #[tokio::test]
+    async fn test_max_response_size() {
+        let (datasource, _) = mock::datasource(|_| mock::json(200, r#"{"id": 24}"#));
+
+        let result: Result<Value, _> = datasource
+            .clone()
+            .with_max_response_size(4)
+            .fetch(GetRequest::new("Dokument", 24))
+            .await;
+        assert!(matches!(result, Err(Error::ResponseTooLarge(10))));
+
+        let _: Value = datasource
+            .with_max_response_size(10)
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_max_response_size_chunked() {
+        let (datasource, _) = mock::datasource(|_| {
+            let chunks = ["{\"titel\": ", "\"Grund\"", "}"].map(Ok::<_, std::io::Error>);
+            Response::new(Body::wrap_stream(futures_util::stream::iter(chunks)))
+        });
+
+        let result: Result<Value, _> = datasource
+            .clone()
+            .with_max_response_size(15)
+            .fetch(GetRequest::new("Dokument", 24))
+            .await;
+        assert!(matches!(result, Err(Error::ResponseTooLarge(17))));
+
+        let uri = "https://oda.ft.dk/api/Dokument(24)".parse().unwrap();
+        let response = datasource
+            .clone()
+            .with_max_response_size(15)
+            .raw_get(uri)
+            .await
+            .unwrap();
+        let result = hyper::body::to_bytes(response).await.map_err(Error::from);
+        assert!(matches!(result, Err(Error::ResponseTooLarge(17))));
+
+        let dokument: Value = datasource
+            .with_max_response_size(18)
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(dokument["titel"], "Grund");
+    }
+
+    #[tokio::test]
+    async fn test_clone_with_base_path() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, "{}"));
+        let datasource = datasource.with_api_version("v2");
+
+        let other = datasource.clone_with_base_path(String::from("/other"));
+        let _: Value = other.fetch(GetRequest::new("Dokument", 24)).await.unwrap();
+        let _: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/other/v2/Dokument(24)?$format=json");
+        assert_eq!(requests[1].uri, "/api/v2/Dokument(24)?$format=json");
+    }
+
+    #[tokio::test]
+    async fn test_byte_order_mark() {
+        let (datasource, _) =
+            mock::datasource(|_| mock::json(200, "\u{feff}\n  {\"value\": [{\"id\": 24}]}"));
+
+        let page: Page<Value> = datasource
+            .fetch_paged(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+        assert_eq!(page.value[0]["id"], 24);
+    }
+
+    #[tokio::test]
+    async fn test_max_top() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"value": []}"#));
+        let datasource = datasource.with_max_top(100);
+
+        for request in [
+            ListRequest::new("Dokument"),
+            ListRequest::new("Dokument").top(10),
+            ListRequest::new("Dokument").top(1000),
+        ] {
+            let _: Page<Value> = datasource.fetch_paged(request).await.unwrap();
+        }
+        let _: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/api/Dokument?$format=json&$top=100");
+        assert_eq!(requests[1].uri, "/api/Dokument?$format=json&$top=10");
+        assert_eq!(requests[2].uri, "/api/Dokument?$format=json&$top=100");
+        assert_eq!(requests[3].uri, "/api/Dokument(24)?$format=json");
+
+        let result = datasource
+            .with_strict_max_top(true)
+            .fetch_paged::<Value>(ListRequest::new("Dokument").top(1000))
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::TopExceedsCap {
+                top: 1000,
+                cap: 100
+            })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_redirect_policy() {
+        let (datasource, connector) = mock::datasource(|request| match request.uri.path() {
+            "/api/Dokument(24)" => Response::builder()
+                .status(301)
+                .header("Location", "/api/v2/Dokument(24)?$format=json")
+                .body(Body::empty())
+                .unwrap(),
+            _ => mock::json(200, r#"{"id": 24}"#),
+        });
+
+        let result: Result<Value, _> = datasource.fetch(GetRequest::new("Dokument", 24)).await;
+        assert!(result.is_err());
+
+        let datasource = datasource
+            .with_redirect_policy(RedirectPolicy::Limited(5))
+            .with_host_header("gateway.internal");
+        let dokument: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(dokument["id"], 24);
+
+        let requests = connector.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(requests[2].uri, "/api/v2/Dokument(24)?$format=json");
+        assert_eq!(requests[2].headers["Accept"], "application/json");
+        assert_eq!(requests[2].headers["Host"], "gateway.internal");
+    }
+
+    #[tokio::test]
+    async fn test_detect_version() {
+        let (datasource, _) = mock::datasource(|request| match request.uri.path() {
+            "/v4/" => Response::builder()
+                .header("OData-Version", "4.0")
+                .body(Body::empty())
+                .unwrap(),
+            "/v3/" => Response::builder()
+                .header("DataServiceVersion", "3.0;NetFx")
+                .body(Body::empty())
+                .unwrap(),
+            "/light/" => mock::json(
+                200,
+                r#"{"odata.metadata": "https://oda.ft.dk/api/$metadata"}"#,
+            ),
+            _ => mock::json(200, "{}"),
+        });
+
+        for (base_path, version) in [
+            ("/v4", ODataVersion::V4),
+            ("/v3", ODataVersion::V3),
+            ("/light", ODataVersion::V3),
+        ] {
+            let detected = datasource
+                .clone_with_base_path(base_path.to_string())
+                .detect_version()
+                .await
+                .unwrap();
+            assert_eq!(detected, version);
+        }
+
+        assert!(matches!(
+            datasource.detect_version().await,
+            Err(Error::UnknownVersion)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_query_via_post() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"value": []}"#));
+        let datasource = datasource.with_query_via_post(100);
+
+        let _: Page<Value> = datasource
+            .fetch_paged(ListRequest::new("Dokument").top(5))
+            .await
+            .unwrap();
+        let titles = ["Grund- og nærhedsnotat vedr. sanktioner på toldområdet"; 4];
+        let pairs: Vec<_> = titles
+            .iter()
+            .map(|title| ("titel", Literal::string(title)))
+            .collect();
+        let _: Page<Value> = datasource
+            .fetch_paged(ListRequest::new("Dokument").filter_all_eq(&pairs))
+            .await
+            .unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].method, Method::GET);
+        assert_eq!(requests[0].uri, "/api/Dokument?$format=json&$top=5");
+        assert_eq!(requests[1].method, Method::POST);
+        assert_eq!(requests[1].uri, "/api/Dokument/$query");
+        assert_eq!(requests[1].headers["Content-Type"], "text/plain");
+    }
+
+    #[tokio::test]
+    async fn test_query_length_limit() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"value": []}"#));
+        let datasource = datasource.with_query_length_limit(40);
+        let request = ListRequest::new("Dokument").filter_any_of("typeid", &[5, 7, 9]);
+
+        let error = datasource
+            .fetch_paged::<Value>(request.clone())
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::QueryTooLong { length, limit: 40 } if length > 40
+        ));
+        assert!(connector.requests().is_empty());
+
+        let _: Page<Value> = datasource
+            .with_query_via_post(1000)
+            .fetch_paged(request)
+            .await
+            .unwrap();
+        let requests = connector.requests();
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].uri, "/api/Dokument/$query");
+    }
+
+    #[tokio::test]
+    async fn test_keep_alive() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, "{}"));
+
+        for keep_alive in [true, false] {
+            let _: Value = datasource
+                .clone()
+                .with_keep_alive(keep_alive)
+                .fetch(GetRequest::new("Dokument", 24))
+                .await
+                .unwrap();
+        }
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].headers["Connection"], "keep-alive");
+        assert_eq!(requests[1].headers["Connection"], "close");
+    }
+
+    #[tokio::test]
+    async fn test_fresh_connection() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"value": []}"#));
+        let datasource = datasource.with_keep_alive(true);
+
+        let _: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24).fresh_connection())
+            .await
+            .unwrap();
+        let _: Page<Value> = datasource
+            .fetch_paged(ListRequest::new("Dokument").fresh_connection())
+            .await
+            .unwrap();
+        let _: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].headers["Connection"], "close");
+        assert_eq!(requests[1].headers["Connection"], "close");
+        assert_eq!(requests[2].headers["Connection"], "keep-alive");
+    }
+
+    #[test]
+    fn test_numeric_count() {
+        for body in [
+            r#"{"value": [], "odata.count": "42"}"#,
+            r#"{"value": [], "@odata.count": 42}"#,
+        ] {
+            let page: Page<u32> = serde_json::from_str(body).unwrap();
+            assert_eq!(page.count.as_deref(), Some("42"));
+            assert_eq!(page.info().total, Some(42));
+        }
+
+        let page: Page<u32> = serde_json::from_str(r#"{"value": []}"#).unwrap();
+        assert!(page.count.is_none());
+    }
+
+    #[test]
+    fn test_page_extra() {
+        let page: Page<u32> = serde_json::from_str(
+            r#"{"@odata.context": "$metadata#Dokument", "@odata.deltaLink": "Dokument?$deltatoken=1", "value": [1], "vendor": {"region": "dk"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(page.metadata.as_deref(), Some("$metadata#Dokument"));
+        assert_eq!(page.extra.len(), 2);
+        assert_eq!(page.extra["@odata.deltaLink"], "Dokument?$deltatoken=1");
+        assert_eq!(page.extra["vendor"]["region"], "dk");
+    }
+
+    #[tokio::test]
+    async fn test_paging_headers() {
+        let (datasource, _) = mock::datasource(|request| {
+            let mut response = match request.uri.query().unwrap_or_default() {
+                query if query.contains("skip") => {
+                    mock::json(200, r#"{"value": [3], "odata.count": "3"}"#)
+                }
+                _ => mock::json(200, r#"{"value": [1, 2]}"#),
+            };
+            let headers = response.headers_mut();
+            let _ = headers.insert("OData-Count", HeaderValue::from_static("4"));
+            let _ = headers.insert(
+                "OData-NextLink",
+                HeaderValue::from_static("Dokument?$skip=2"),
+            );
+            response
+        });
+
+        let page: Page<u32> = datasource
+            .fetch_paged(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+        assert!(page.count.is_none());
+        assert!(page.next_link.is_none());
+
+        let datasource = datasource.with_paging_headers(
+            HeaderName::from_static("odata-count"),
+            HeaderName::from_static("odata-nextlink"),
+        );
+        let page: Page<u32> = datasource
+            .fetch_paged(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+        assert_eq!(page.count.as_deref(), Some("4"));
+        assert_eq!(page.next_link.as_deref(), Some("Dokument?$skip=2"));
+
+        let page: Page<u32> = datasource
+            .fetch_paged(ListRequest::new("Dokument").skip(2))
+            .await
+            .unwrap();
+        assert_eq!(page.count.as_deref(), Some("3"));
+    }
+
+    #[tokio::test]
+    async fn test_body_preprocessor() {
+        let (datasource, _) = mock::datasource(|request| match request.uri.path() {
+            "/api/Dokument(24)" => mock::json(200, ")]}'\u{feff}{\"id\": 24}"),
+            "/api/Dokument/$count" => mock::json(200, ")]}'42"),
+            _ => mock::json(200, r#"callback({"value": [1, 2]})"#),
+        });
+        let datasource = datasource.with_body_preprocessor(|body| {
+            if let Some(json) = body.strip_prefix(b")]}'") {
+                Cow::Borrowed(json)
+            } else if let Some(json) = body
+                .strip_prefix(b"callback(")
+                .and_then(|body| body.strip_suffix(b")"))
+            {
+                Cow::Owned(json.to_vec())
+            } else {
+                Cow::Borrowed(body)
+            }
+        });
+
+        let entity: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(entity["id"], 24);
+
+        let page: Page<u32> = datasource
+            .fetch_paged(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+        assert_eq!(page.value, [1, 2]);
+
+        let count = datasource
+            .count(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+        assert_eq!(count, 42);
+    }
+
+    #[cfg(not(feature = "encoding"))]
+    #[tokio::test]
+    async fn test_invalid_utf8() {
+        let (datasource, _) = mock::datasource(|_| {
+            Response::builder()
+                .body(Body::from(b"{\"titel\": \"N\xE6rhedsnotat\"}".to_vec()))
+                .unwrap()
+        });
+
+        let error = datasource
+            .fetch::<Value>(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap_err();
+        let Error::Io(error) = error else {
+            panic!("expected an io error, got {error:?}");
+        };
+        let bytes = error
+            .get_ref()
+            .and_then(|e| e.downcast_ref::<std::string::FromUtf8Error>())
+            .unwrap()
+            .as_bytes();
+        assert_eq!(bytes, b"{\"titel\": \"N\xE6rhedsnotat\"}");
+    }
+
+    #[tokio::test]
+    async fn test_try_fetch_paged() {
+        let (datasource, _) = mock::datasource(|_| mock::json(200, r#"{"value": [{"id": "x"}]}"#));
+
+        let (error, body) = datasource
+            .try_fetch_paged::<u32>(ListRequest::new("Dokument"))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Serde(..)));
+        assert_eq!(body, r#"{"value": [{"id": "x"}]}"#);
+
+        let (datasource, _) =
+            mock::datasource(|_| mock::json(404, r#"{"odata.error": {"message": "not found"}}"#));
+        let (error, body) = datasource
+            .try_fetch_paged::<u32>(ListRequest::new("Dokument"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            Error::Status {
+                code: StatusCode::NOT_FOUND,
+                ..
+            }
+        ));
+        assert_eq!(body, r#"{"odata.error": {"message": "not found"}}"#);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fetch_timed() {
+        let (datasource, _) = mock::datasource(|_| {
+            // The clock is paused, so it is advanced by exactly the delay of the body.
+            let delayed = futures_util::stream::once(async {
+                tokio::time::sleep(Duration::from_secs(2)).await;
+                Ok::<_, std::io::Error>(r#"{"value": [{"id": 24}]}"#)
+            });
+            Response::builder()
+                .body(Body::wrap_stream(delayed))
+                .unwrap()
+        });
+
+        let (page, elapsed) = datasource
+            .fetch_paged_timed::<Value>(ListRequest::new("Dokument"))
+            .await
+            .unwrap();
+        assert_eq!(page.value[0]["id"], 24);
+        assert!(elapsed >= Duration::from_secs(2) && elapsed < Duration::from_secs(3));
+    }
+
+    #[tokio::test]
+    async fn test_entity_sets() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, "{}"));
+        let datasource = datasource.with_entity_sets(["Dokument", "Aktør"]);
+
+        for resource_type in ["dokument", "AKTØR", "Sag"] {
+            let _: Value = datasource
+                .fetch(GetRequest::new(resource_type, 24))
+                .await
+                .unwrap();
+        }
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/api/Dokument(24)?$format=json");
+        assert_eq!(requests[1].uri, "/api/Akt%C3%B8r(24)?$format=json");
+        assert_eq!(requests[2].uri, "/api/Sag(24)?$format=json");
+    }
+
+    #[tokio::test]
+    async fn test_format_negotiation() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, "{}"));
+
+        for negotiation in [
+            FormatNegotiation::Both,
+            FormatNegotiation::QueryParameter,
+            FormatNegotiation::AcceptHeader,
+        ] {
+            let _: Value = datasource
+                .clone()
+                .with_format_negotiation(negotiation)
+                .fetch(GetRequest::new("Dokument", 24))
+                .await
+                .unwrap();
+        }
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/api/Dokument(24)?$format=json");
+        assert_eq!(requests[0].headers["Accept"], "application/json");
+        assert_eq!(requests[1].uri, "/api/Dokument(24)?$format=json");
+        assert!(!requests[1].headers.contains_key("Accept"));
+        assert_eq!(requests[2].uri, "/api/Dokument(24)?");
+        assert_eq!(requests[2].headers["Accept"], "application/json");
+    }
+
+    #[tokio::test]
+    async fn test_accept_types() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, "{}"));
+
+        let _: Value = datasource
+            .with_accept_types(&[("application/json", 1.0), ("application/xml", 0.25)])
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            connector.requests()[0].headers["Accept"],
+            "application/json;q=1.0, application/xml;q=0.25"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_odata_version_headers() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, "{}"));
+
+        let datasource = datasource.with_odata_version(ODataVersion::V4);
+        let _: Value = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+
+        let headers = &connector.requests()[0].headers;
+        assert_eq!(headers["OData-Version"], "4.0");
+        assert_eq!(headers["OData-MaxVersion"], "4.0");
+        assert!(!headers.contains_key("MaxDataServiceVersion"));
+    }
+
+    #[tokio::test]
+    async fn test_ping() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, ""));
+        datasource.ping().await.unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].method, Method::HEAD);
+        assert_eq!(requests[0].uri, "/api/");
+    }
+
+    #[tokio::test]
+    async fn test_status_error() {
+        let body = r#"{"odata.error": {"message": {"value": "Resource not found"}}}"#;
+        let (datasource, _) = mock::datasource(move |_| mock::json(404, body));
+
+        match datasource
+            .fetch::<Value>(GetRequest::new("Dokument", 24))
+            .await
+        {
+            Err(Error::Status {
+                code,
+                body: content,
+            }) => {
+                assert_eq!(code, 404);
+                assert_eq!(content, body);
+            }
+            other => panic!("expected status error, got {other:?}"),
+        }
+
+        assert!(matches!(
+            datasource
+                .fetch_paged::<Value>(ListRequest::new("Dokument"))
+                .await,
+            Err(Error::Status { code, .. }) if code == 404
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_ping_failure() {
+        let (datasource, _) = mock::datasource(|_| mock::json(503, ""));
+
+        match datasource.ping().await {
+            Err(Error::Status { code, .. }) => assert_eq!(code, 503),
+            other => panic!("expected status error, got {other:?}"),
+        }
     }
 }
@@ -0,0 +1,186 @@
+use std::{
+    fmt::{self, Display},
+    time::Duration,
+};
+
+use crate::{path::encode_expression, ODataVersion};
+
+/// A value used in an OData expression, such as the right-hand side of a [`ListRequest::filter`](`crate::ListRequest::filter`).
+///
+/// String slices are used as-is, which means they must already be valid OData literals (`24`, `'text'`, `true`).
+/// Use the typed constructors to have the correct OData representation produced for you.
+/// ```rust
+/// use odata_simple_client::Literal;
+///
+/// assert_eq!(Literal::string("O'Brien").to_string(), "'O''Brien'");
+/// assert_eq!(Literal::enum_value("Namespace.StatusType", "Active").to_string(), "Namespace.StatusType'Active'");
+/// assert_eq!(Literal::from(24).to_string(), "24");
+/// ```
+///
+/// Temporal values are formatted according to their Edm type:
+/// ```rust
+/// use odata_simple_client::{Literal, ODataVersion};
+/// use std::time::Duration;
+///
+/// let hour = Duration::from_secs(3600);
+/// assert_eq!(Literal::duration(hour, ODataVersion::V4).to_string(), "duration'PT1H'");
+/// assert_eq!(Literal::duration(hour, ODataVersion::V3).to_string(), "time'PT1H'");
+/// assert_eq!(
+///     Literal::duration(Duration::from_millis(90_061_500), ODataVersion::V4).to_string(),
+///     "duration'P1DT1H1M1.5S'"
+/// );
+/// assert_eq!(Literal::time_of_day(13, 5, 0).to_string(), "13:05:00");
+/// ```
+///
+/// Some types are represented differently depending on the protocol version:
+/// ```rust
+/// use odata_simple_client::{Literal, ODataVersion};
+///
+/// let id = "01234567-89ab-cdef-0123-456789abcdef";
+/// assert_eq!(Literal::guid(id, ODataVersion::V3).to_string(), "guid'01234567-89ab-cdef-0123-456789abcdef'");
+/// assert_eq!(Literal::guid(id, ODataVersion::V4).to_string(), "01234567-89ab-cdef-0123-456789abcdef");
+///
+/// assert_eq!(Literal::decimal("19.95", ODataVersion::V3).to_string(), "19.95M");
+/// assert_eq!(Literal::decimal("19.95", ODataVersion::V4).to_string(), "19.95");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Literal(String);
+
+/// Percent-encode `value` exactly like the request builders encode the literals of a filter, for use in
+/// hand-built query strings, such as links in a user interface.
+/// ```rust
+/// use odata_simple_client::{encode_literal, Literal};
+///
+/// assert_eq!(encode_literal(&Literal::string("O'Brien & Co")), "%27O%27%27Brien%20%26%20Co%27");
+/// assert_eq!(encode_literal(&Literal::from(24)), "24");
+/// ```
+pub fn encode_literal(value: &Literal) -> String {
+    encode_expression(&value.to_string())
+}
+
+fn quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+impl Literal {
+    /// Use `value` verbatim. It is up to the caller to ensure it is a valid OData literal.
+    pub fn raw(value: &str) -> Self {
+        Literal(value.to_string())
+    }
+
+    /// A string literal. The value is wrapped in single quotes, and any single quotes within it are escaped.
+    pub fn string(value: &str) -> Self {
+        Literal(quote(value))
+    }
+
+    /// A member of an enumeration type, such as `Namespace.StatusType'Active'`.
+    pub fn enum_value(type_name: &str, member: &str) -> Self {
+        Literal(format!("{type_name}{}", quote(member)))
+    }
+
+    /// An `Edm.Guid` value, such as `guid'01234567-89ab-cdef-0123-456789abcdef'` in OData 3.0.
+    /// OData 4.0 uses the unquoted form instead.
+    pub fn guid(value: &str, version: ODataVersion) -> Self {
+        match version {
+            ODataVersion::V3 => Literal(format!("guid{}", quote(value))),
+            ODataVersion::V4 => Literal(value.to_string()),
+        }
+    }
+
+    /// An `Edm.Duration` value in its ISO 8601 representation, such as `duration'P1DT2H'`, introduced in OData 4.0.
+    /// OData 3.0 represents durations using `Edm.Time` instead, such as `time'P1DT2H'`.
+    pub fn duration(duration: Duration, version: ODataVersion) -> Self {
+        let seconds = duration.as_secs();
+        let (days, hours, minutes, seconds) = (
+            seconds / 86_400,
+            seconds / 3600 % 24,
+            seconds / 60 % 60,
+            seconds % 60,
+        );
+
+        let mut value = String::from("P");
+        if days > 0 {
+            value.push_str(&format!("{days}D"));
+        }
+
+        let mut time = String::new();
+        if hours > 0 {
+            time.push_str(&format!("{hours}H"));
+        }
+        if minutes > 0 {
+            time.push_str(&format!("{minutes}M"));
+        }
+        let nanos = duration.subsec_nanos();
+        if nanos > 0 {
+            let fraction = format!("{nanos:09}");
+            time.push_str(&format!("{seconds}.{}S", fraction.trim_end_matches('0')));
+        } else if seconds > 0 || (days == 0 && time.is_empty()) {
+            time.push_str(&format!("{seconds}S"));
+        }
+
+        if !time.is_empty() {
+            value.push('T');
+            value.push_str(&time);
+        }
+        let prefix = match version {
+            ODataVersion::V3 => "time",
+            ODataVersion::V4 => "duration",
+        };
+        Literal(format!("{prefix}{}", quote(&value)))
+    }
+
+    /// An `Edm.TimeOfDay` value, such as `13:05:00`. Introduced in OData 4.0.
+    ///
+    /// The components are used as-is, so it is up to the caller to ensure they form a valid time of day.
+    pub fn time_of_day(hour: u32, minute: u32, second: u32) -> Self {
+        Literal(format!("{hour:02}:{minute:02}:{second:02}"))
+    }
+
+    /// An `Edm.Decimal` value, such as `19.95M` in OData 3.0. OData 4.0 uses the unsuffixed form instead.
+    ///
+    /// The value is taken as a string to avoid losing precision, and is used verbatim apart from the suffix.
+    pub fn decimal(value: &str, version: ODataVersion) -> Self {
+        match version {
+            ODataVersion::V3 => Literal(format!("{value}M")),
+            ODataVersion::V4 => Literal(value.to_string()),
+        }
+    }
+}
+
+impl Display for Literal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl From<&str> for Literal {
+    fn from(value: &str) -> Self {
+        Literal::raw(value)
+    }
+}
+
+impl From<String> for Literal {
+    fn from(value: String) -> Self {
+        Literal(value)
+    }
+}
+
+impl From<bool> for Literal {
+    fn from(value: bool) -> Self {
+        Literal(value.to_string())
+    }
+}
+
+macro_rules! numeric_literal {
+    ($($ty:ty),*) => {
+        $(
+            impl From<$ty> for Literal {
+                fn from(value: $ty) -> Self {
+                    Literal(value.to_string())
+                }
+            }
+        )*
+    };
+}
+
+numeric_literal!(i8, i16, i32, i64, u8, u16, u32, u64, usize);
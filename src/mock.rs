@@ -0,0 +1,150 @@
+//! In-process mock of an OData API, used to test the request and response handling of a
+//! [`DataSource`] without going over the network.
+use crate::DataSource;
+use hyper::{
+    client::connect::{Connected, Connection},
+    header::HeaderMap,
+    server::conn::Http,
+    service::{service_fn, Service},
+    Body, Client, Method, Request, Response, Uri,
+};
+use std::{
+    convert::Infallible,
+    future::{ready, Ready},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncWrite, DuplexStream, ReadBuf};
+
+/// A request as it was received by the mock server.
+#[derive(Debug, Clone)]
+pub(crate) struct RecordedRequest {
+    pub method: Method,
+    pub uri: Uri,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+type Handler = dyn Fn(&RecordedRequest) -> Response<Body> + Send + Sync;
+
+/// Connector which serves every connection using a handler function, recording all requests.
+#[derive(Clone)]
+pub(crate) struct MockConnector {
+    handler: Arc<Handler>,
+    requests: Arc<Mutex<Vec<RecordedRequest>>>,
+}
+
+impl std::fmt::Debug for MockConnector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockConnector").finish_non_exhaustive()
+    }
+}
+
+impl MockConnector {
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+/// Construct a [`DataSource`] for `oda.ft.dk/api`, backed by a [`MockConnector`] using `handler`.
+pub(crate) fn datasource<F>(handler: F) -> (DataSource<MockConnector>, MockConnector)
+where
+    F: Fn(&RecordedRequest) -> Response<Body> + Send + Sync + 'static,
+{
+    let connector = MockConnector {
+        handler: Arc::new(handler),
+        requests: Arc::new(Mutex::new(Vec::new())),
+    };
+
+    let client = Client::builder().build(connector.clone());
+    let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    (datasource, connector)
+}
+
+/// Construct a JSON response with the given status.
+pub(crate) fn json(status: u16, body: &str) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header("Content-Type", "application/json")
+        .body(Body::from(body.to_string()))
+        .unwrap()
+}
+
+pub(crate) struct MockStream(DuplexStream);
+
+impl Connection for MockStream {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl AsyncRead for MockStream {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for MockStream {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+impl Service<Uri> for MockConnector {
+    type Response = MockStream;
+    type Error = Infallible;
+    type Future = Ready<Result<MockStream, Infallible>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _: Uri) -> Self::Future {
+        let (client, server) = tokio::io::duplex(1 << 16);
+        let connector = self.clone();
+
+        let service = service_fn(move |request: Request<Body>| {
+            let connector = connector.clone();
+            async move {
+                let (parts, body) = request.into_parts();
+                let recorded = RecordedRequest {
+                    method: parts.method,
+                    uri: parts.uri,
+                    headers: parts.headers,
+                    body: hyper::body::to_bytes(body)
+                        .await
+                        .map(|body| body.to_vec())
+                        .unwrap_or_default(),
+                };
+
+                let response = (connector.handler)(&recorded);
+                connector.requests.lock().unwrap().push(recorded);
+                Ok::<_, Infallible>(response)
+            }
+        });
+
+        drop(tokio::spawn(
+            Http::new()
+                .http1_only(true)
+                .serve_connection(server, service),
+        ));
+        ready(Ok(MockStream(client)))
+    }
+}
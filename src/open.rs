@@ -0,0 +1,65 @@
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// Wraps a deserialized instance of an open entity type, capturing the dynamic properties not declared by `T`.
+///
+/// Open entity types may carry properties which aren't part of the schema, and are therefore silently dropped
+/// when deserializing into a fixed struct. Use `OpenEntity<T>` in place of `T` to keep them:
+/// ```rust
+/// use odata_simple_client::OpenEntity;
+/// use serde::Deserialize;
+///
+/// #[derive(Deserialize)]
+/// struct Kunde {
+///     navn: String,
+/// }
+///
+/// let kunde: OpenEntity<Kunde> =
+///     serde_json::from_str(r#"{"navn": "Jensen", "Branche": "Landbrug"}"#).unwrap();
+///
+/// assert_eq!(kunde.value.navn, "Jensen");
+/// assert_eq!(kunde.dynamic["Branche"], "Landbrug");
+/// ```
+///
+/// `T` must not use `#[serde(deny_unknown_fields)]`, since the dynamic properties are part of the same object.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OpenEntity<T> {
+    /// The declared properties of the entity.
+    #[serde(flatten)]
+    pub value: T,
+    /// Every property of the entity which was not consumed by `T`, including any control annotations.
+    #[serde(flatten)]
+    pub dynamic: Map<String, Value>,
+}
+
+impl<T> OpenEntity<T> {
+    /// Discard the dynamic properties, returning only the declared ones.
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::OpenEntity;
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Kunde {
+        id: usize,
+    }
+
+    #[test]
+    fn test_dynamic_properties_captured() {
+        let kunde: OpenEntity<Kunde> = serde_json::from_str(
+            r#"{"id": 5, "Branche": "Landbrug", "Ansatte": 12, "Adresse": {"By": "Odense"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(kunde.dynamic.len(), 3);
+        assert!(!kunde.dynamic.contains_key("id"));
+        assert_eq!(kunde.dynamic["Ansatte"], 12);
+        assert_eq!(kunde.dynamic["Adresse"]["By"], "Odense");
+        assert_eq!(kunde.into_inner().id, 5);
+    }
+}
@@ -0,0 +1,282 @@
+use crate::{
+    path::Format, status_error, Connector, DataSource, Error, GetRequest, ListRequest, Page,
+    PathBuilder,
+};
+use hyper::{
+    header::{LOCATION, RETRY_AFTER},
+    Body, Request, Response, StatusCode,
+};
+use log::debug;
+use serde::de::DeserializeOwned;
+use std::time::Duration;
+
+/// Status monitor of an asynchronous operation, which can be polled using [`DataSource::poll_until_complete`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Monitor {
+    /// Url of the status monitor resource.
+    pub location: String,
+    /// Time the server asked the client to wait before polling the monitor, if specified.
+    pub retry_after: Option<Duration>,
+}
+
+/// Outcome of a request which the server was allowed to process asynchronously.
+#[derive(Debug)]
+pub enum AsyncResponse<T> {
+    /// The server processed the request synchronously, and returned the result immediately.
+    Complete(T),
+    /// The server accepted the request for asynchronous processing.
+    Accepted(Monitor),
+}
+
+/// Parse the `Retry-After` header of a response. Only the delay-seconds form is supported.
+pub(crate) fn retry_after(response: &Response<Body>) -> Option<Duration> {
+    response
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+fn location(response: &Response<Body>) -> Result<String, Error> {
+    response
+        .headers()
+        .get(LOCATION)
+        .and_then(|location| location.to_str().ok())
+        .map(str::to_string)
+        .ok_or(Error::Header("Location"))
+}
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    async fn execute_async<T>(&self, builder: PathBuilder) -> Result<AsyncResponse<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self
+            .execute(builder.format(Format::Json).prefer("respond-async"))
+            .await?;
+
+        match response.status() {
+            StatusCode::ACCEPTED => Ok(AsyncResponse::Accepted(Monitor {
+                location: location(&response)?,
+                retry_after: retry_after(&response),
+            })),
+            status if status.is_success() => Ok(AsyncResponse::Complete(
+                self.deserialize_as(response).await?,
+            )),
+            _ => Err(status_error(response).await),
+        }
+    }
+
+    /// Fetch a single resource using a [`GetRequest`], allowing the server to process it asynchronously
+    /// using the `Prefer: respond-async` header.
+    ///
+    /// If the server accepts the request for asynchronous processing, the returned [`Monitor`] can be
+    /// passed to [`DataSource::poll_until_complete`] to retrieve the result.
+    /// Use [`GetRequest::wait`] to let the server respond synchronously to requests which complete quickly.
+    pub async fn fetch_async<T>(&self, request: GetRequest) -> Result<AsyncResponse<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        self.execute_async(request.into()).await
+    }
+
+    /// Fetch a [`Page`]d list of resources using a [`ListRequest`], allowing the server to process it
+    /// asynchronously. See [`DataSource::fetch_async`].
+    pub async fn fetch_paged_async<T>(
+        &self,
+        request: ListRequest,
+    ) -> Result<AsyncResponse<Page<T>>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        self.execute_async(request.into()).await
+    }
+
+    /// Poll the status monitor at `location` until the asynchronous operation completes, and return its result.
+    ///
+    /// While the monitor responds with `202 Accepted` the operation is still running, and the monitor is polled
+    /// again after the delay given by its `Retry-After` header, or `interval` if none was given.
+    /// If the monitor redirects to the result using `303 See Other`, the redirect is followed.
+    ///
+    /// Fails with [`Error::OperationPending`] once the monitor has responded with `202 Accepted` `max_polls` times,
+    /// so an operation which never completes doesn't keep the caller waiting forever.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{AsyncResponse, DataSource, ListRequest, Page};
+    /// # use serde::Deserialize;
+    /// # use std::time::Duration;
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let page: Page<Dokument> = match datasource
+    ///     .fetch_paged_async(ListRequest::new("Dokument"))
+    ///     .await
+    ///     .unwrap()
+    /// {
+    ///     AsyncResponse::Complete(page) => page,
+    ///     AsyncResponse::Accepted(monitor) => datasource
+    ///         .poll_until_complete(&monitor.location, Duration::from_secs(1), 60)
+    ///         .await
+    ///         .unwrap(),
+    /// };
+    /// # });
+    /// ```
+    pub async fn poll_until_complete<T>(
+        &self,
+        location: &str,
+        interval: Duration,
+        max_polls: u32,
+    ) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let mut location = location.to_string();
+        let mut polls = 0;
+        loop {
+            let uri = self.resolve_link(&location)?;
+
+            debug!("polling status monitor {}", uri);
+            let response = self.send(Request::get(uri)).await?;
+
+            match response.status() {
+                StatusCode::ACCEPTED => {
+                    polls += 1;
+                    if polls >= max_polls {
+                        return Err(Error::OperationPending(location));
+                    }
+                    tokio::time::sleep(retry_after(&response).unwrap_or(interval)).await
+                }
+                StatusCode::SEE_OTHER => location = self::location(&response)?,
+                status if status.is_success() => return self.deserialize_as(response).await,
+                _ => return Err(status_error(response).await),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock, AsyncResponse, Error, GetRequest, Monitor};
+    use hyper::{Body, Response};
+    use serde_json::Value;
+    use std::{
+        sync::atomic::{AtomicUsize, Ordering},
+        time::Duration,
+    };
+
+    fn accepted(location: &str) -> Response<Body> {
+        Response::builder()
+            .status(202)
+            .header("Location", location)
+            .header("Retry-After", "0")
+            .body(Body::empty())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_poll_until_complete() {
+        let polls = AtomicUsize::new(0);
+        let (datasource, connector) = mock::datasource(move |request| match request.uri.path() {
+            "/api/Dokument(24)" => accepted("https://oda.ft.dk/api/monitor/1"),
+            "/api/monitor/1" if polls.fetch_add(1, Ordering::SeqCst) < 2 => {
+                accepted("https://oda.ft.dk/api/monitor/1")
+            }
+            "/api/monitor/1" => Response::builder()
+                .status(303)
+                .header("Location", "/api/result/1")
+                .body(Body::empty())
+                .unwrap(),
+            _ => mock::json(200, r#"{"id": 24}"#),
+        });
+
+        let monitor = match datasource
+            .fetch_async::<Value>(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap()
+        {
+            AsyncResponse::Accepted(monitor) => monitor,
+            AsyncResponse::Complete(_) => panic!("expected request to be accepted"),
+        };
+        assert_eq!(
+            monitor,
+            Monitor {
+                location: "https://oda.ft.dk/api/monitor/1".to_string(),
+                retry_after: Some(Duration::ZERO),
+            }
+        );
+
+        let result: Value = datasource
+            .poll_until_complete(&monitor.location, Duration::from_secs(60), 10)
+            .await
+            .unwrap();
+        assert_eq!(result["id"], 24);
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].headers["Prefer"], "respond-async");
+        assert_eq!(requests.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_poll_until_complete_max_polls() {
+        let (datasource, connector) =
+            mock::datasource(|_| accepted("https://oda.ft.dk/api/monitor/1"));
+
+        let error = datasource
+            .poll_until_complete::<Value>(
+                "https://oda.ft.dk/api/monitor/1",
+                Duration::from_secs(60),
+                3,
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, Error::OperationPending(location) if location == "https://oda.ft.dk/api/monitor/1")
+        );
+        assert_eq!(connector.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_wait_preference() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"id": 24}"#));
+
+        let response = datasource
+            .fetch_async::<Value>(GetRequest::new("Dokument", 24).wait(Duration::from_secs(10)))
+            .await
+            .unwrap();
+        assert!(matches!(response, AsyncResponse::Complete(_)));
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].headers["Prefer"], "wait=10, respond-async");
+    }
+
+    #[tokio::test]
+    async fn test_synchronous_completion() {
+        let (datasource, _) = mock::datasource(|_| mock::json(200, r#"{"id": 24}"#));
+
+        match datasource
+            .fetch_async::<Value>(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap()
+        {
+            AsyncResponse::Complete(value) => assert_eq!(value["id"], 24),
+            AsyncResponse::Accepted(_) => panic!("expected request to complete"),
+        }
+    }
+}
@@ -1,10 +1,18 @@
-use std::{collections::HashMap, convert::TryInto};
+use std::{borrow::Cow, convert::TryInto, fmt};
 
-use hyper::http::uri::{InvalidUri, PathAndQuery};
+use hyper::{
+    header::{HeaderName, HeaderValue},
+    http::uri::{InvalidUri, PathAndQuery},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{Error, FilterExpr, Literal};
+use log::warn;
 
 /// Specifies direction in which the returned results are listed. Use [`ListRequest::order_by`](`crate::ListRequest::order_by`) to change it.
 /// If nothing else is specified, it defaults to [`Direction::Ascending`]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Direction {
     /// List results in descending order (largest to smallest)
     Descending,
@@ -15,7 +23,10 @@ pub enum Direction {
 /// Used by [`ListRequest::filter`](`crate::ListRequest::filter`) to apply conditional filtering to the returned results.
 ///
 /// See [the OData 3.0 documentation (section 5.1.2)](https://www.odata.org/documentation/odata-version-3-0/url-conventions/) for more information.
-#[derive(Debug, Clone, Copy)]
+///
+/// More operators may be added in the future, so matching on a Comparison requires a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum Comparison {
     /// The Equal operator evaluates to true if the field is equal to the value, otherwise if evaluates to false.
     Equal,
@@ -31,6 +42,26 @@ pub enum Comparison {
     LessOrEqual,
 }
 
+impl Comparison {
+    /// The OData operator corresponding to the comparison, such as `eq` or `gt`,
+    /// for use when building expressions by hand.
+    /// ```rust
+    /// use odata_simple_client::Comparison;
+    ///
+    /// assert_eq!(Comparison::GreaterOrEqual.as_odata_str(), "ge");
+    /// ```
+    pub fn as_odata_str(&self) -> &'static str {
+        match self {
+            Comparison::Equal => "eq",
+            Comparison::NotEqual => "ne",
+            Comparison::GreaterThan => "gt",
+            Comparison::GreaterOrEqual => "ge",
+            Comparison::LessThan => "lt",
+            Comparison::LessOrEqual => "le",
+        }
+    }
+}
+
 /// Format of the returned API data. [`DataSource::fetch_paged`](`crate::DataSource::fetch_paged`) forces [`Format::Json`].
 #[derive(Debug, Clone, Copy)]
 pub enum Format {
@@ -49,26 +80,377 @@ pub enum InlineCount {
     AllPages,
 }
 
+/// Strategy for deriving the name of an entity set from the name of a Rust type.
+/// Used by [`ResourceType::from_type_name`].
+#[derive(Debug, Clone, Copy, Default)]
+pub enum NamingStrategy {
+    /// Use the name of the type as-is, such as `Dokument` for `Dokument`.
+    #[default]
+    AsIs,
+    /// Pluralize the name of the type using simple English rules, such as `Categories` for `Category`.
+    Pluralized,
+    /// Derive the name using a custom function, which receives the name of the type.
+    Custom(fn(&str) -> String),
+}
+
+impl NamingStrategy {
+    fn apply(self, name: &str) -> String {
+        match self {
+            NamingStrategy::AsIs => name.to_string(),
+            NamingStrategy::Pluralized => pluralize(name),
+            NamingStrategy::Custom(apply) => apply(name),
+        }
+    }
+}
+
+fn pluralize(name: &str) -> String {
+    let lowercase = name.to_ascii_lowercase();
+    if ["s", "x", "z", "ch", "sh"]
+        .iter()
+        .any(|suffix| lowercase.ends_with(suffix))
+    {
+        format!("{name}es")
+    } else if lowercase.ends_with('y')
+        && !lowercase
+            .chars()
+            .rev()
+            .nth(1)
+            .is_some_and(|c| "aeiou".contains(c))
+    {
+        format!("{}ies", &name[..name.len() - 1])
+    } else {
+        format!("{name}s")
+    }
+}
+
+/// Name of an entity set exposed by the API, such as `Dokument`.
+///
+/// Used by [`GetRequest::new`](`crate::GetRequest::new`) and [`ListRequest::new`](`crate::ListRequest::new`),
+/// which accept anything convertible into a `ResourceType`, including string slices. Define constants
+/// for the entity sets you use, to avoid misspelling them across a code base:
+/// ```rust
+/// use odata_simple_client::{ListRequest, ResourceType};
+///
+/// const DOKUMENT: ResourceType = ResourceType::from_static("Dokument");
+///
+/// let request = ListRequest::new(DOKUMENT);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ResourceType(Cow<'static, str>);
+
+impl ResourceType {
+    /// Construct a ResourceType from a static string, usable in `const` contexts.
+    pub const fn from_static(name: &'static str) -> Self {
+        ResourceType(Cow::Borrowed(name))
+    }
+
+    /// Derive the name of the entity set from the name of the type `T`, without its module path
+    /// and generic parameters, using the naming `strategy`.
+    /// ```rust
+    /// use odata_simple_client::{NamingStrategy, ResourceType};
+    ///
+    /// struct Category;
+    ///
+    /// let resource_type = ResourceType::from_type_name::<Category>(NamingStrategy::Pluralized);
+    /// assert_eq!(resource_type.as_str(), "Categories");
+    /// ```
+    pub fn from_type_name<T: ?Sized>(strategy: NamingStrategy) -> Self {
+        let name = std::any::type_name::<T>();
+        let name = name.split('<').next().unwrap_or(name);
+        let name = name.rsplit("::").next().unwrap_or(name);
+
+        ResourceType(Cow::Owned(strategy.apply(name)))
+    }
+
+    /// Name of the entity set.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for ResourceType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for ResourceType {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl From<&str> for ResourceType {
+    fn from(name: &str) -> Self {
+        ResourceType(Cow::Owned(name.to_string()))
+    }
+}
+
+impl From<String> for ResourceType {
+    fn from(name: String) -> Self {
+        ResourceType(Cow::Owned(name))
+    }
+}
+
+impl From<&ResourceType> for ResourceType {
+    fn from(resource_type: &ResourceType) -> Self {
+        resource_type.clone()
+    }
+}
+
+impl From<ResourceType> for String {
+    fn from(resource_type: ResourceType) -> Self {
+        resource_type.0.into_owned()
+    }
+}
+
+/// Order in which query parameters are emitted. Use [`DataSource::with_parameter_order`](`crate::DataSource::with_parameter_order`) to change it.
+/// If nothing else is specified, it defaults to [`ParameterOrder::Sorted`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParameterOrder {
+    /// Sort the query parameters alphabetically, producing the same query regardless of the order the request was built in.
+    Sorted,
+    /// Emit the query parameters in the order they were first set on the request.
+    Insertion,
+}
+
+/// Expansion of a navigation property using OData v4 nested query options, such as `Fil($select=titel,format)`.
+/// Use [`ListRequest::expand_with`](`crate::ListRequest::expand_with`) to apply it.
+///
+/// In v4, `$select` and `$expand` are independent: the top-level `$select` only shapes the properties of the
+/// resource itself, so the properties of an expanded navigation must be selected inside its expansion.
+/// Selecting a path into an expanded navigation at the top level, such as `Fil/titel`, is therefore moved into
+/// the nested `$select` of its expansion, leaving only the navigation property `Fil` itself in the top-level `$select`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpandOption {
+    navigation: String,
+    select: Vec<String>,
+}
+
+impl ExpandOption {
+    /// Expand the `navigation` property, retrieving all of its properties.
+    pub fn new(navigation: &str) -> Self {
+        ExpandOption {
+            navigation: navigation.to_string(),
+            select: Vec::new(),
+        }
+    }
+
+    /// Only retrieve specific properties of the expanded navigation using a nested `$select`.
+    ///
+    /// Repeated calls append to the list of selected properties.
+    pub fn select<'f, F>(mut self, field: F) -> Self
+    where
+        F: IntoIterator<Item = &'f str>,
+    {
+        self.select.extend(
+            field
+                .into_iter()
+                .filter(|field| !field.is_empty())
+                .map(str::to_string),
+        );
+        self
+    }
+
+    fn render(&self, select: &[String]) -> String {
+        let navigation = encode_expression(&self.navigation);
+        if select.is_empty() {
+            navigation
+        } else {
+            format!("{navigation}($select={})", select.join(","))
+        }
+    }
+}
+
+/// Split a comma-separated list of query option values, ignoring commas nested inside parentheses.
+fn split_top_level(list: &str) -> Vec<&str> {
+    let mut items = Vec::new();
+    let (mut depth, mut start) = (0usize, 0);
+    for (index, character) in list.char_indices() {
+        match character {
+            '(' => depth += 1,
+            ')' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                items.push(&list[start..index]);
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    items.push(&list[start..]);
+    items
+}
+
+/// Percent-encode an expression for use as a query parameter value.
+///
+/// Slashes are left intact, since they are valid within a query string and
+/// are used by OData to separate the segments of property paths, such as `Address/City`.
+pub(crate) fn encode_expression(expression: &str) -> String {
+    urlencoding::encode(expression).replace("%2F", "/")
+}
+
+/// Query options set by dedicated methods of the request builders, which can't be set using `raw_param`.
+const RESERVED_PARAMETERS: &[&str] = &[
+    "compute",
+    "count",
+    "expand",
+    "filter",
+    "format",
+    "inlinecount",
+    "orderby",
+    "search",
+    "select",
+    "skip",
+    "top",
+];
+
+/// Percent-encode a path segment, such as the namespace-qualified name of a bound function.
+///
+/// Parentheses, commas, equals signs and single quotes are left intact, since they are valid within a path segment
+/// and are used by OData for function parameters, such as `Model.Rate(rating=5)`. Dollar signs are left intact
+/// for the same reason, since they prefix segments such as `$ref` and `$value`.
+///
+/// Valid percent-encoded escapes, such as the `%20` of a base path given as `/my%20api`, are also left intact,
+/// so segments which are already encoded aren't encoded twice. Any other `%` is encoded as `%25`.
+fn encode_segment(segment: &str) -> String {
+    let encode = |unescaped: &str| {
+        urlencoding::encode(unescaped)
+            .replace("%24", "$")
+            .replace("%28", "(")
+            .replace("%29", ")")
+            .replace("%2C", ",")
+            .replace("%3D", "=")
+            .replace("%27", "'")
+    };
+
+    let mut encoded = String::with_capacity(segment.len());
+    let mut rest = segment;
+    while let Some(index) = rest.find('%') {
+        encoded.push_str(&encode(&rest[..index]));
+        match rest.get(index + 1..index + 3) {
+            Some(hex) if hex.bytes().all(|b| b.is_ascii_hexdigit()) => {
+                encoded.push('%');
+                encoded.push_str(hex);
+                rest = &rest[index + 3..];
+            }
+            _ => {
+                encoded.push_str("%25");
+                rest = &rest[index + 1..];
+            }
+        }
+    }
+    encoded.push_str(&encode(rest));
+    encoded
+}
+
+/// Percent-encode each segment of a `path`, such as a base path containing spaces or non-ASCII characters,
+/// leaving the slashes separating them intact.
+pub(crate) fn encode_path(path: &str) -> String {
+    path.split('/')
+        .map(encode_segment)
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Split a path segment addressing a single resource by key, such as `Dokument(24)`, into its name and key.
+fn keyed_segment(segment: &str) -> Option<(&str, usize)> {
+    let (name, id) = segment.strip_suffix(')')?.split_once('(')?;
+    Some((name, id.parse().ok()?))
+}
+
+/// Query parameters, in the order they were first inserted.
+#[derive(Debug, Clone, Default)]
+struct Parameters(Vec<(Cow<'static, str>, String)>);
+
+impl Parameters {
+    fn insert<K>(&mut self, key: K, value: String) -> Option<String>
+    where
+        K: Into<Cow<'static, str>>,
+    {
+        let key = key.into();
+        match self.0.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, current)) => Some(std::mem::replace(current, value)),
+            None => {
+                self.0.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Append `value` to the comma-separated list of values held by `key`.
+    fn append(&mut self, key: &'static str, value: String) {
+        match self.0.iter_mut().find(|(existing, _)| *existing == key) {
+            Some((_, current)) => {
+                current.push(',');
+                current.push_str(&value);
+            }
+            None => self.0.push((key.into(), value)),
+        }
+    }
+
+    fn get(&self, key: &str) -> Option<&str> {
+        self.0
+            .iter()
+            .find(|(existing, _)| *existing == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn remove(&mut self, key: &str) -> Option<String> {
+        let index = self.0.iter().position(|(existing, _)| *existing == key)?;
+        Some(self.0.remove(index).1)
+    }
+
+    fn contains_key(&self, key: &str) -> bool {
+        self.0.iter().any(|(existing, _)| *existing == key)
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&str, &String)> {
+        self.0.iter().map(|(key, value)| (key.as_ref(), value))
+    }
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct PathBuilder {
-    pub(crate) base_path: String,
+    base_path: Option<String>,
     resource_type: String,
     id: Option<usize>,
-    inner: HashMap<&'static str, String>,
+    segments: Vec<String>,
+    count: bool,
+    order: ParameterOrder,
+    inner: Parameters,
+    preferences: Vec<String>,
+    headers: Vec<(HeaderName, HeaderValue)>,
+    expand_options: Vec<ExpandOption>,
+    filter: Option<FilterExpr>,
 }
 
 impl PathBuilder {
-    pub fn new_with_base(base_path: String, resource_type: String) -> Self {
+    pub fn new(resource_type: String) -> Self {
         PathBuilder {
             id: None,
-            base_path,
+            segments: Vec::new(),
+            count: false,
+            base_path: None,
             resource_type,
-            inner: HashMap::new(),
+            order: ParameterOrder::Sorted,
+            inner: Parameters::default(),
+            preferences: Vec::new(),
+            headers: Vec::new(),
+            expand_options: Vec::new(),
+            filter: None,
         }
     }
 
-    pub fn new(resource_type: String) -> Self {
-        Self::new_with_base(String::new(), resource_type)
+    pub fn resource_type(&self) -> &str {
+        &self.resource_type
+    }
+
+    /// Value of the query parameter `key` as it was given, before percent-encoding.
+    pub fn decoded_parameter(&self, key: &str) -> Option<String> {
+        self.inner.get(key).map(|value| {
+            urlencoding::decode(value)
+                .map_or_else(|_| value.to_string(), |value| value.into_owned())
+        })
     }
 
     pub fn id(mut self, id: usize) -> Self {
@@ -76,22 +458,91 @@ impl PathBuilder {
         self
     }
 
+    /// Append a path segment after the resource and key, such as the namespace-qualified name
+    /// of a bound function, or a navigation property.
+    pub fn segment(mut self, segment: &str) -> Self {
+        self.segments.push(segment.to_string());
+        self
+    }
+
+    /// Request only the number of matching resources, by addressing the `/$count` segment of the resource.
+    pub fn count(mut self) -> Self {
+        self.count = true;
+        self
+    }
+
+    pub fn is_count(&self) -> bool {
+        self.count
+    }
+
+    /// Send the request to `base_path`, regardless of the base path of the DataSource executing it.
     pub fn base_path(mut self, base_path: String) -> Self {
-        self.base_path = base_path;
+        self.base_path = Some(base_path);
+        self
+    }
+
+    /// Use `base_path`, unless the request has already been given one using [`PathBuilder::base_path`].
+    pub fn default_base_path(mut self, base_path: String) -> Self {
+        if self.base_path.is_none() {
+            self.base_path = Some(base_path);
+        }
+        self
+    }
+
+    pub fn parameter_order(mut self, order: ParameterOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Add a preference to the `Prefer` header sent along with the request.
+    pub fn prefer(mut self, preference: &str) -> Self {
+        if !self
+            .preferences
+            .iter()
+            .any(|existing| existing == preference)
+        {
+            self.preferences.push(preference.to_string());
+        }
         self
     }
 
+    pub fn preferences(&self) -> &[String] {
+        &self.preferences
+    }
+
+    /// Send an additional header along with the request, such as the condition of a conditional request.
+    pub fn header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.push((name, value));
+        self
+    }
+
+    pub fn headers(&self) -> &[(HeaderName, HeaderValue)] {
+        &self.headers
+    }
+
     pub fn order_by(mut self, field: &str, order: Direction) -> Self {
         let order = match order {
             Direction::Descending => "desc",
             Direction::Ascending => "asc",
         };
 
-        // We don't really care if the value is overwritten.
-        let _ = self.inner.insert(
-            "orderby",
-            urlencoding::encode(&format!("{field} {order}")).to_string(),
-        );
+        self.inner
+            .append("orderby", encode_expression(&format!("{field} {order}")));
+        self
+    }
+
+    pub fn requests_json(&self) -> bool {
+        self.inner.get("format") == Some("json")
+    }
+
+    #[cfg(feature = "xml")]
+    pub fn requests_xml(&self) -> bool {
+        self.inner.get("format") == Some("xml")
+    }
+
+    pub fn without_format(mut self) -> Self {
+        // We don't really care if there was no format to begin with.
+        let _ = self.inner.remove("format");
         self
     }
 
@@ -137,51 +588,328 @@ impl PathBuilder {
         self
     }
 
-    pub fn filter(mut self, field: &str, comparison: Comparison, value: &str) -> Self {
-        let comparison = match comparison {
-            Comparison::Equal => "eq",
-            Comparison::NotEqual => "ne",
-            Comparison::GreaterThan => "gt",
-            Comparison::GreaterOrEqual => "ge",
-            Comparison::LessThan => "lt",
-            Comparison::LessOrEqual => "le",
+    pub fn default_inline_count(mut self, value: InlineCount) -> Self {
+        // Only applies to collections, and never overrides an explicitly requested inline count.
+        if self.id.is_none() && !self.inner.contains_key("inlinecount") {
+            self = self.inline_count(value);
+        }
+        self
+    }
+
+    pub fn canonical_resource_type(mut self, entity_sets: &[String]) -> Self {
+        let resource_type = self.resource_type.to_lowercase();
+        if let Some(canonical) = entity_sets
+            .iter()
+            .find(|name| name.to_lowercase() == resource_type)
+        {
+            self.resource_type = canonical.clone();
+        }
+        self
+    }
+
+    pub fn max_top(self, cap: u32, strict: bool) -> Result<Self, Error> {
+        // Only applies to collections, whose count doesn't include any resources either way.
+        if self.id.is_some() || self.count {
+            return Ok(self);
+        }
+
+        match self
+            .inner
+            .get("top")
+            .and_then(|top| top.parse::<u32>().ok())
+        {
+            Some(top) if top <= cap => Ok(self),
+            Some(top) if strict => Err(Error::TopExceedsCap { top, cap }),
+            _ => Ok(self.top(cap)),
+        }
+    }
+
+    pub fn filter(self, field: &str, comparison: Comparison, value: &Literal) -> Self {
+        // A comparison without a field, such as ` eq ''`, is rejected by the server, so it is treated as no filter.
+        if field.trim().is_empty() {
+            return self;
+        }
+
+        self.filter_expr(&FilterExpr::field(field).compare(comparison, value.clone()))
+    }
+
+    /// Combine `expression` with the current filter using `and`.
+    pub fn filter_expr(self, expression: &FilterExpr) -> Self {
+        self.combine_filter(expression, FilterExpr::and)
+    }
+
+    /// Combine the verbatim OData filter `expression` with the current filter using `and`.
+    pub fn filter_raw(self, expression: &str) -> Self {
+        if expression.trim().is_empty() {
+            return self;
+        }
+
+        self.filter_expr(&FilterExpr::raw(expression))
+    }
+
+    /// Combine `expression` with the current filter using `or`.
+    pub fn filter_expr_or(self, expression: &FilterExpr) -> Self {
+        self.combine_filter(expression, FilterExpr::or)
+    }
+
+    /// Set the query option `key`, with or without a leading `$`, to `value`, replacing any previous value.
+    /// Keys reserved for options with dedicated methods are ignored.
+    pub fn raw_param(mut self, key: &'static str, value: &str) -> Self {
+        let key = key.strip_prefix('$').unwrap_or(key);
+        if RESERVED_PARAMETERS.contains(&key.to_ascii_lowercase().as_str()) {
+            warn!(
+                "ignoring raw query option `${key}`, which must be set using its dedicated method"
+            );
+            return self;
+        }
+
+        let _ = self.inner.insert(key, encode_expression(value));
+        self
+    }
+
+    fn combine_filter<F>(mut self, expression: &FilterExpr, combine: F) -> Self
+    where
+        F: FnOnce(FilterExpr, FilterExpr) -> FilterExpr,
+    {
+        let expression = match self.filter.take() {
+            Some(existing) => combine(existing, expression.clone()),
+            None => expression.clone(),
         };
 
-        // We don't really care if the value is overwritten.
-        let _ = self.inner.insert(
-            "filter",
-            urlencoding::encode(&format!("{field} {comparison} {value}")).to_string(),
-        );
+        let _ = self
+            .inner
+            .insert("filter", encode_expression(&expression.to_string()));
+        self.filter = Some(expression);
         self
     }
 
+    pub fn filter_has(self, field: &str, flags: &Literal) -> Self {
+        if field.trim().is_empty() {
+            return self;
+        }
+
+        self.filter_expr(&FilterExpr::field(field).has(flags.clone()))
+    }
+
     pub fn expand<'f, F>(mut self, field: F) -> Self
     where
         F: IntoIterator<Item = &'f str>,
     {
         let encoded = field
             .into_iter()
-            .map(|field| urlencoding::encode(field).into_owned())
-            .collect::<Vec<_>>()
-            .join(",");
+            .filter(|field| !field.is_empty())
+            .map(encode_expression)
+            .collect::<Vec<_>>();
 
-        // We don't really care if the value is overwritten.
-        let _ = self
-            .inner
-            .entry("expand")
-            .and_modify(|current| {
-                current.push(',');
-                current.push_str(&encoded)
-            })
-            .or_insert_with(|| encoded.to_string());
+        // Appending nothing would leave an empty segment in the list, and everything is already expanded by `*`.
+        if !encoded.is_empty() && self.inner.get("expand") != Some("*") {
+            self.inner.append("expand", encoded.join(","));
+        }
+        self
+    }
+
+    pub fn expand_with(mut self, option: ExpandOption) -> Self {
+        if self.inner.get("expand") != Some("*") {
+            self.inner.append(
+                "expand",
+                option.render(
+                    &option
+                        .select
+                        .iter()
+                        .map(|field| encode_expression(field))
+                        .collect::<Vec<_>>(),
+                ),
+            );
+            self.expand_options.push(option);
+        }
+        self
+    }
+
+    pub fn expand_all(mut self) -> Self {
+        // Replaces any specific expansions, since they are all included.
+        let _ = self.inner.insert("expand", String::from("*"));
+        self
+    }
+
+    pub fn select<'f, F>(mut self, field: F) -> Self
+    where
+        F: IntoIterator<Item = &'f str>,
+    {
+        let encoded = field
+            .into_iter()
+            .filter(|field| !field.is_empty())
+            .map(encode_expression)
+            .collect::<Vec<_>>();
+
+        // Appending nothing would leave an empty segment in the list.
+        if !encoded.is_empty() {
+            self.inner.append("select", encoded.join(","));
+        }
+        self
+    }
+
+    /// Select `fields`, unless the request already selects its own properties, in which case `fields`
+    /// are only added to them if `extend` is set.
+    pub fn default_select(self, fields: &[String], extend: bool) -> Self {
+        match self.inner.get("select") {
+            None => self.select(fields.iter().map(String::as_str)),
+            Some(selected) if extend => {
+                let selected: Vec<_> = selected.split(',').map(str::to_string).collect();
+                let missing: Vec<_> = fields
+                    .iter()
+                    .filter(|field| !selected.contains(&encode_expression(field)))
+                    .map(String::as_str)
+                    .collect();
+                self.select(missing)
+            }
+            Some(_) => self,
+        }
+    }
+
+    pub fn compute(mut self, expression: &str, alias: &str) -> Self {
+        let encoded = urlencoding::encode(&format!("{expression} as {alias}")).into_owned();
+
+        self.inner.append("compute", encoded);
         self
     }
 
+    /// Reconstruct a PathBuilder from the path and query produced by [`PathBuilder::build`].
+    ///
+    /// The resource is the first segment addressed by key, or the last segment if none are. Any path segments
+    /// preceding the resource are kept as the base path of the request, and segments following it are appended.
+    pub fn parse(path_and_query: &str) -> Result<Self, Error> {
+        let invalid = |reason: String| Error::InvalidRequest(reason);
+
+        let (path, query) = path_and_query
+            .split_once('?')
+            .unwrap_or((path_and_query, ""));
+        let path = path.strip_prefix('/').unwrap_or(path);
+        let (path, count) = match path.strip_suffix("/$count") {
+            Some(path) => (path, true),
+            None => (path, false),
+        };
+        let segments: Vec<&str> = path.split('/').collect();
+        let resource = segments
+            .iter()
+            .position(|segment| keyed_segment(segment).is_some())
+            .unwrap_or(segments.len() - 1);
+
+        let base_path = match &segments[..resource] {
+            [] => None,
+            base_path => Some(format!(
+                "/{}",
+                urlencoding::decode(&base_path.join("/"))
+                    .map_err(|_| invalid(format!("invalid base path `{}`", base_path.join("/"))))?
+            )),
+        };
+
+        let (resource_type, id) = match keyed_segment(segments[resource]) {
+            Some((resource_type, id)) => (resource_type, Some(id)),
+            None if segments[resource].ends_with(')') => {
+                return Err(invalid(format!(
+                    "unsupported key in `{}`",
+                    segments[resource]
+                )));
+            }
+            None => (segments[resource], None),
+        };
+
+        if resource_type.is_empty() {
+            return Err(invalid(format!("unsupported path `{path}`")));
+        }
+
+        let resource_type = urlencoding::decode(resource_type)
+            .map_err(|_| invalid(format!("invalid resource type `{resource_type}`")))?;
+
+        let mut builder = PathBuilder::new(resource_type.into_owned());
+        builder.id = id;
+        builder.count = count;
+        for segment in &segments[resource + 1..] {
+            let segment = urlencoding::decode(segment)
+                .map_err(|_| invalid(format!("invalid path segment `{segment}`")))?;
+            builder.segments.push(segment.into_owned());
+        }
+        builder.base_path = base_path;
+
+        for parameter in query.split('&').filter(|parameter| !parameter.is_empty()) {
+            let (key, value) = parameter.split_once('=').unwrap_or((parameter, ""));
+            let key = urlencoding::decode(key)
+                .map_err(|_| invalid(format!("invalid query option `{key}`")))?;
+            let key = key
+                .strip_prefix('$')
+                .ok_or_else(|| invalid(format!("unsupported query option `{key}`")))?;
+
+            let _ = builder.inner.insert(key.to_string(), value.to_string());
+        }
+        builder.filter = builder
+            .decoded_parameter("filter")
+            .map(|filter| FilterExpr::raw(&filter));
+
+        Ok(builder)
+    }
+
+    /// Move top-level selections of paths into navigations expanded with an [`ExpandOption`], such as `Fil/titel`,
+    /// into the nested `$select` of their expansion.
+    fn nest_selections(&self) -> Cow<'_, Parameters> {
+        let (Some(selected), Some(expanded)) = (self.inner.get("select"), self.inner.get("expand"))
+        else {
+            return Cow::Borrowed(&self.inner);
+        };
+        if self.expand_options.is_empty() {
+            return Cow::Borrowed(&self.inner);
+        }
+
+        let mut selected: Vec<String> = selected.split(',').map(str::to_string).collect();
+        let mut expanded: Vec<String> = split_top_level(expanded)
+            .into_iter()
+            .map(str::to_string)
+            .collect();
+        for option in &self.expand_options {
+            let navigation = encode_expression(&option.navigation);
+            let prefix = format!("{navigation}/");
+            let mut nested: Vec<String> = option
+                .select
+                .iter()
+                .map(|field| encode_expression(field))
+                .collect();
+            let original = option.render(&nested);
+
+            let mut moved = false;
+            selected.retain(|field| match field.strip_prefix(&prefix) {
+                Some(property) => {
+                    if !nested.iter().any(|existing| existing == property) {
+                        nested.push(property.to_string());
+                    }
+                    moved = true;
+                    false
+                }
+                None => true,
+            });
+            if !moved {
+                continue;
+            }
+
+            if !selected.contains(&navigation) {
+                selected.push(navigation);
+            }
+            if let Some(item) = expanded.iter_mut().find(|item| **item == original) {
+                *item = option.render(&nested);
+            }
+        }
+
+        let mut inner = self.inner.clone();
+        let _ = inner.insert("select", selected.join(","));
+        let _ = inner.insert("expand", expanded.join(","));
+        Cow::Owned(inner)
+    }
+
     pub fn build(&self) -> Result<PathAndQuery, InvalidUri> {
         let query = {
             let mut kv = self
-                .inner
+                .nest_selections()
                 .iter()
+                // The count is returned as plain text, and can't be combined with these.
+                .filter(|(key, _)| !(self.count && matches!(*key, "format" | "inlinecount")))
                 .map(|(key, value)| {
                     format!(
                         "${key}={value}",
@@ -190,18 +918,26 @@ impl PathBuilder {
                     )
                 })
                 .collect::<Vec<_>>();
-            kv.sort();
+            if self.order == ParameterOrder::Sorted {
+                kv.sort();
+            }
             kv
         };
 
         format!(
-            "{base_path}/{resource_type}{id}?{query}",
-            base_path = self.base_path,
+            "{base_path}/{resource_type}{id}{segments}{count}?{query}",
+            base_path = encode_path(self.base_path.as_deref().unwrap_or_default()),
             resource_type = urlencoding::encode(&self.resource_type),
             id = self
                 .id
                 .map(|id| format!("({})", urlencoding::encode(&id.to_string())))
                 .unwrap_or_default(),
+            segments = self
+                .segments
+                .iter()
+                .map(|segment| format!("/{}", encode_segment(segment)))
+                .collect::<String>(),
+            count = if self.count { "/$count" } else { "" },
             query = query.join("&")
         )
         .parse()
@@ -218,8 +954,40 @@ impl TryInto<PathAndQuery> for PathBuilder {
 
 #[cfg(test)]
 mod tests {
-    use super::PathBuilder;
-    use crate::Direction;
+    use super::{ExpandOption, NamingStrategy, ParameterOrder, PathBuilder, ResourceType};
+    use crate::{Comparison, Direction, FilterExpr, Format, InlineCount, ListRequest, Literal};
+
+    #[test]
+    fn test_resource_type_from_type_name() {
+        struct Dokument;
+        struct Box;
+        struct Company<T>(T);
+        struct Key;
+
+        assert_eq!(
+            ResourceType::from_type_name::<Dokument>(NamingStrategy::AsIs).as_str(),
+            "Dokument"
+        );
+        assert_eq!(
+            ResourceType::from_type_name::<Box>(NamingStrategy::Pluralized).as_str(),
+            "Boxes"
+        );
+        assert_eq!(
+            ResourceType::from_type_name::<Company<u32>>(NamingStrategy::Pluralized).as_str(),
+            "Companies"
+        );
+        assert_eq!(
+            ResourceType::from_type_name::<Key>(NamingStrategy::Pluralized).as_str(),
+            "Keys"
+        );
+        assert_eq!(
+            ResourceType::from_type_name::<Dokument>(NamingStrategy::Custom(|name| {
+                name.to_uppercase()
+            }))
+            .as_str(),
+            "DOKUMENT"
+        );
+    }
 
     #[test]
     fn test_query_builder() {
@@ -244,4 +1012,506 @@ mod tests {
 
         assert_eq!("/test_resource(100)?$expand=DoThing,What,Hello", query);
     }
+
+    #[test]
+    fn test_default_inline_count() {
+        let query = PathBuilder::new("test_resource".into())
+            .default_inline_count(InlineCount::AllPages)
+            .build()
+            .unwrap();
+        assert_eq!("/test_resource?$inlinecount=allpages", query);
+
+        let query = PathBuilder::new("test_resource".into())
+            .inline_count(InlineCount::None)
+            .default_inline_count(InlineCount::AllPages)
+            .build()
+            .unwrap();
+        assert_eq!("/test_resource?$inlinecount=none", query);
+
+        let query = PathBuilder::new("test_resource".into())
+            .id(1)
+            .default_inline_count(InlineCount::AllPages)
+            .build()
+            .unwrap();
+        assert_eq!("/test_resource(1)?", query);
+    }
+
+    #[test]
+    fn test_parse_round_trip() {
+        let builder = PathBuilder::new("Dokument".into())
+            .id(24)
+            .expand(["DokumentAktør", "Fil"])
+            .filter("titel", Comparison::Equal, &Literal::string("a&b"));
+        let built = builder.build().unwrap();
+
+        let parsed = PathBuilder::parse(built.as_str()).unwrap();
+        assert_eq!(built, parsed.build().unwrap());
+
+        assert!(PathBuilder::parse("/Dokument(abc)").is_err());
+        assert!(PathBuilder::parse("/Dokument?custom=1").is_err());
+        assert!(PathBuilder::parse("/").is_err());
+
+        let parsed = PathBuilder::parse("/other/service/Dokument?$top=1").unwrap();
+        assert_eq!(parsed.base_path.as_deref(), Some("/other/service"));
+    }
+
+    #[test]
+    fn test_insertion_order() {
+        let query = PathBuilder::new("test_resource".into())
+            .parameter_order(ParameterOrder::Insertion)
+            .top(2)
+            .skip(3)
+            .order_by("date", Direction::Ascending)
+            .top(5)
+            .build()
+            .unwrap();
+
+        assert_eq!("/test_resource?$top=5&$skip=3&$orderby=date%20asc", query);
+    }
+
+    #[test]
+    fn test_enum_filter() {
+        let query = PathBuilder::new("Orders".into())
+            .filter(
+                "Status",
+                Comparison::Equal,
+                &Literal::enum_value("Namespace.StatusType", "Active"),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            "/Orders?$filter=Status%20eq%20Namespace.StatusType%27Active%27",
+            query
+        );
+    }
+
+    #[test]
+    fn test_multiple_order_by() {
+        let query = PathBuilder::from(
+            ListRequest::new("Dokument")
+                .order_by_field("typeid")
+                .order_by_desc("dato")
+                .order_by_asc("id"),
+        )
+        .build()
+        .unwrap();
+        assert_eq!(
+            "/Dokument?$orderby=typeid%20asc,dato%20desc,id%20asc",
+            query
+        );
+    }
+
+    #[test]
+    fn test_function_order_by() {
+        let expression = FilterExpr::function("tolower", [FilterExpr::field("titel")]);
+        let query = PathBuilder::new("Dokument".into())
+            .order_by(&expression.to_string(), Direction::Descending)
+            .build()
+            .unwrap();
+
+        assert_eq!("/Dokument?$orderby=tolower%28titel%29%20desc", query);
+    }
+
+    #[test]
+    fn test_empty_expand() {
+        let query = PathBuilder::new("Dokument".into())
+            .expand([])
+            .build()
+            .unwrap();
+        assert_eq!("/Dokument?", query);
+
+        let query = PathBuilder::new("Dokument".into())
+            .expand(["DokumentAktør"])
+            .expand([""])
+            .expand(["", "Fil"])
+            .build()
+            .unwrap();
+        assert_eq!("/Dokument?$expand=DokumentAkt%C3%B8r,Fil", query);
+    }
+
+    #[test]
+    fn test_count() {
+        let builder = PathBuilder::new("Dokument".into())
+            .filter("typeid", Comparison::Equal, &Literal::from(5))
+            .inline_count(InlineCount::AllPages)
+            .format(Format::Json)
+            .count();
+        let query = builder.build().unwrap();
+        assert_eq!("/Dokument/$count?$filter=typeid%20eq%205", query);
+
+        let parsed = PathBuilder::parse(query.as_str()).unwrap();
+        assert_eq!(parsed.build().unwrap(), query);
+    }
+
+    #[test]
+    fn test_bool_filter() {
+        let query = PathBuilder::from(ListRequest::new("Dokument").filter_bool("aktiv", false))
+            .build()
+            .unwrap();
+
+        assert_eq!("/Dokument?$filter=aktiv%20eq%20false", query);
+    }
+
+    #[test]
+    fn test_segments() {
+        let builder = PathBuilder::new("Products".into())
+            .id(1)
+            .segment("Model.Rate(rating=5)")
+            .base_path("/api".into());
+        let query = builder.build().unwrap();
+        assert_eq!("/api/Products(1)/Model.Rate(rating=5)?", query);
+
+        let parsed = PathBuilder::parse(query.as_str()).unwrap();
+        assert_eq!(parsed.base_path.as_deref(), Some("/api"));
+        assert_eq!(parsed.build().unwrap(), query);
+    }
+
+    #[test]
+    fn test_filter_all_eq() {
+        let query = PathBuilder::from(ListRequest::new("Aktør").filter_all_eq(&[
+            ("fornavn", Literal::string("Lars")),
+            ("type", Literal::string("O'Brien")),
+            ("typeid", Literal::from(5)),
+        ]))
+        .build()
+        .unwrap();
+        assert_eq!(
+            "/Akt%C3%B8r?$filter=fornavn%20eq%20%27Lars%27%20and%20type%20eq%20%27O%27%27Brien%27%20and%20typeid%20eq%205",
+            query
+        );
+
+        let query = PathBuilder::from(ListRequest::new("Aktør").filter_all_eq::<Literal>(&[]))
+            .build()
+            .unwrap();
+        assert_eq!("/Akt%C3%B8r?", query);
+    }
+
+    #[test]
+    fn test_cast_path() {
+        let query = PathBuilder::from(
+            ListRequest::new("Sag")
+                .filter_expr(
+                    FilterExpr::cast("FT.Lovforslag")
+                        .field("nummer")
+                        .compare(Comparison::Equal, FilterExpr::literal(12)),
+                )
+                .order_by(
+                    FilterExpr::cast("FT.Lovforslag").field("nummer"),
+                    Direction::Ascending,
+                ),
+        )
+        .build()
+        .unwrap();
+        assert_eq!(
+            "/Sag?$filter=cast%28FT.Lovforslag%29/nummer%20eq%2012&$orderby=cast%28FT.Lovforslag%29/nummer%20asc",
+            query
+        );
+    }
+
+    #[test]
+    fn test_expand_all() {
+        let query = PathBuilder::from(
+            ListRequest::new("Dokument")
+                .expand(["Fil"])
+                .expand_all()
+                .expand(["DokumentAktør"]),
+        )
+        .build()
+        .unwrap();
+        assert_eq!("/Dokument?$expand=*", query);
+    }
+
+    #[test]
+    fn test_decoded_parameter() {
+        let builder = PathBuilder::from(ListRequest::new("Dokument").filter(
+            "titel",
+            Comparison::Equal,
+            Literal::string("Grund- og nærhedsnotat"),
+        ));
+        assert_eq!(builder.resource_type(), "Dokument");
+        assert_eq!(
+            builder.decoded_parameter("filter").as_deref(),
+            Some("titel eq 'Grund- og nærhedsnotat'")
+        );
+        assert_eq!(builder.decoded_parameter("expand"), None);
+    }
+
+    #[test]
+    fn test_select() {
+        let query = PathBuilder::new("Dokument".into())
+            .select(["titel"])
+            .expand(["DokumentAktør"])
+            .select(["id", "Fil/filurl"])
+            .build()
+            .unwrap();
+        assert_eq!(
+            "/Dokument?$expand=DokumentAkt%C3%B8r&$select=titel,id,Fil/filurl",
+            query
+        );
+    }
+
+    #[test]
+    fn test_default_select() {
+        let defaults = [String::from("id"), String::from("titel")];
+
+        let query = PathBuilder::new("Dokument".into())
+            .default_select(&defaults, false)
+            .build()
+            .unwrap();
+        assert_eq!("/Dokument?$select=id,titel", query);
+
+        let query = PathBuilder::new("Dokument".into())
+            .select(["dato"])
+            .default_select(&defaults, false)
+            .build()
+            .unwrap();
+        assert_eq!("/Dokument?$select=dato", query);
+
+        let query = PathBuilder::new("Dokument".into())
+            .select(["id", "dato"])
+            .default_select(&defaults, true)
+            .build()
+            .unwrap();
+        assert_eq!("/Dokument?$select=id,dato,titel", query);
+    }
+
+    #[test]
+    fn test_combined_filters() {
+        let query = PathBuilder::from(
+            ListRequest::new("Dokument")
+                .filter("id", Comparison::GreaterThan, 10)
+                .filter("id", Comparison::LessThan, 20),
+        )
+        .build()
+        .unwrap();
+        assert_eq!("/Dokument?$filter=id%20gt%2010%20and%20id%20lt%2020", query);
+
+        let builder = PathBuilder::from(
+            ListRequest::new("Dokument")
+                .filter("a", Comparison::Equal, 1)
+                .filter_or("b", Comparison::Equal, 2)
+                .filter("c", Comparison::Equal, 3),
+        );
+        assert_eq!(
+            builder.decoded_parameter("filter").as_deref(),
+            Some("(a eq 1 or b eq 2) and c eq 3")
+        );
+
+        let builder = PathBuilder::from(
+            ListRequest::new("Dokument")
+                .filter_any_of("typeid", &[5, 7])
+                .filter_has("rights", "Namespace.Permission", "Read"),
+        );
+        assert_eq!(
+            builder.decoded_parameter("filter").as_deref(),
+            Some("(typeid eq 5 or typeid eq 7) and rights has Namespace.Permission'Read'")
+        );
+
+        let parsed = PathBuilder::parse(builder.build().unwrap().as_str())
+            .unwrap()
+            .filter("id", Comparison::Equal, &Literal::from(24));
+        assert_eq!(
+            parsed.decoded_parameter("filter").as_deref(),
+            Some("((typeid eq 5 or typeid eq 7) and rights has Namespace.Permission'Read') and id eq 24")
+        );
+
+        let builder = PathBuilder::from(
+            ListRequest::new("Dokument")
+                .filter("typeid", Comparison::Equal, 5)
+                .filter_raw("year(dato) eq 2020"),
+        );
+        assert_eq!(
+            builder.decoded_parameter("filter").as_deref(),
+            Some("typeid eq 5 and (year(dato) eq 2020)")
+        );
+    }
+
+    #[test]
+    fn test_raw_param() {
+        let query = PathBuilder::from(
+            ListRequest::new("Dokument")
+                .raw_param("$apply", "groupby((typeid),aggregate($count as antal))")
+                .raw_param("skiptoken", "'24'")
+                .raw_param("filter", "id eq 1")
+                .raw_param("$Top", "1"),
+        )
+        .build()
+        .unwrap();
+        assert_eq!(
+            "/Dokument?$apply=groupby%28%28typeid%29%2Caggregate%28%24count%20as%20antal%29%29&$skiptoken=%2724%27",
+            query
+        );
+    }
+
+    #[test]
+    fn test_empty_filter_field() {
+        let query = PathBuilder::new("Dokument".into())
+            .filter("", Comparison::Equal, &Literal::from(""))
+            .filter_has(" ", &Literal::enum_value("Namespace.Permission", "Read"))
+            .build()
+            .unwrap();
+        assert_eq!("/Dokument?", query);
+
+        let query = PathBuilder::from(
+            ListRequest::new("Dokument")
+                .filter("id", Comparison::Equal, 24)
+                .filter("", Comparison::Equal, ""),
+        )
+        .build()
+        .unwrap();
+        assert_eq!("/Dokument?$filter=id%20eq%2024", query);
+
+        let query = PathBuilder::from(
+            ListRequest::new("Dokument")
+                .filter_all_eq(&[("", "x")])
+                .filter_any_of("", &[Literal::string("Lars")])
+                .filter_in_typed("", &[Literal::from(1)])
+                .filter_fields("", Comparison::LessThan, "slutdato")
+                .filter_fields("startdato", Comparison::LessThan, " "),
+        )
+        .build()
+        .unwrap();
+        assert_eq!("/Dokument?", query);
+    }
+
+    #[test]
+    fn test_expand_with() {
+        let query = PathBuilder::new("Dokument".into())
+            .expand(["DokumentAktør"])
+            .expand_with(ExpandOption::new("Fil").select(["titel", "format"]))
+            .build()
+            .unwrap();
+        assert_eq!(
+            "/Dokument?$expand=DokumentAkt%C3%B8r,Fil($select=titel,format)",
+            query
+        );
+
+        let query = PathBuilder::new("Dokument".into())
+            .select(["id", "Fil/titel", "Fil/filurl", "DokumentAktør/rolle"])
+            .expand_with(ExpandOption::new("Fil").select(["titel"]))
+            .expand(["DokumentAktør"])
+            .build()
+            .unwrap();
+        assert_eq!(
+            "/Dokument?$expand=Fil($select=titel,filurl),DokumentAkt%C3%B8r&$select=id,DokumentAkt%C3%B8r/rolle,Fil",
+            query
+        );
+
+        let query = PathBuilder::new("Dokument".into())
+            .expand_all()
+            .expand_with(ExpandOption::new("Fil").select(["titel"]))
+            .build()
+            .unwrap();
+        assert_eq!("/Dokument?$expand=*", query);
+    }
+
+    #[test]
+    fn test_encoded_base_path() {
+        let builder = PathBuilder::new("Dokument".into()).base_path("/my api/vær".into());
+        let query = builder.build().unwrap();
+        assert_eq!("/my%20api/v%C3%A6r/Dokument?", query);
+
+        let parsed = PathBuilder::parse(query.as_str()).unwrap();
+        assert_eq!(parsed.base_path.as_deref(), Some("/my api/vær"));
+        assert_eq!(parsed.build().unwrap(), query);
+    }
+
+    #[test]
+    fn test_pre_encoded_base_path() {
+        let builder = PathBuilder::new("Dokument".into()).base_path("/my%20api/v%C3%A6r".into());
+        assert_eq!("/my%20api/v%C3%A6r/Dokument?", builder.build().unwrap());
+
+        let builder = PathBuilder::new("Dokument".into()).base_path("/100%/50%2".into());
+        assert_eq!("/100%25/50%252/Dokument?", builder.build().unwrap());
+    }
+
+    #[test]
+    fn test_navigation_filter() {
+        let query = PathBuilder::from(ListRequest::new("Products").filter(
+            "Category/Name",
+            Comparison::Equal,
+            Literal::string("Beverages"),
+        ))
+        .build()
+        .unwrap();
+        assert_eq!(
+            "/Products?$filter=Category/Name%20eq%20%27Beverages%27",
+            query
+        );
+    }
+
+    #[test]
+    fn test_filter_fields() {
+        let query = PathBuilder::from(ListRequest::new("Periode").filter_fields(
+            "startdato",
+            Comparison::LessThan,
+            "slutdato",
+        ))
+        .build()
+        .unwrap();
+        assert_eq!("/Periode?$filter=startdato%20lt%20slutdato", query);
+    }
+
+    #[test]
+    fn test_filter_count() {
+        let query = PathBuilder::from(ListRequest::new("Dokument").filter_count(
+            "Fil",
+            Comparison::GreaterThan,
+            5,
+        ))
+        .build()
+        .unwrap();
+        assert_eq!("/Dokument?$filter=Fil/%24count%20gt%205", query);
+    }
+
+    #[test]
+    fn test_has_filter() {
+        let query = PathBuilder::new("Documents".into())
+            .filter_has(
+                "AccessRights",
+                &Literal::enum_value("Namespace.Permission", "Read"),
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            "/Documents?$filter=AccessRights%20has%20Namespace.Permission%27Read%27",
+            query
+        );
+    }
+
+    #[test]
+    fn test_property_path_filter() {
+        let query = PathBuilder::new("Customers".into())
+            .filter(
+                "Address/City",
+                Comparison::Equal,
+                &Literal::string("Copenhagen"),
+            )
+            .order_by("Address/Street", Direction::Ascending)
+            .expand(["Orders/Items"])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            "/Customers?$expand=Orders/Items&$filter=Address/City%20eq%20%27Copenhagen%27&$orderby=Address/Street%20asc",
+            query
+        );
+    }
+
+    #[test]
+    fn test_compute_alias_in_order_by() {
+        let query = PathBuilder::new("Orders".into())
+            .compute("Price mul Qty", "Total")
+            .compute("Price mul 2", "Double")
+            .order_by("Total", Direction::Descending)
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            "/Orders?$compute=Price%20mul%20Qty%20as%20Total,Price%20mul%202%20as%20Double&$orderby=Total%20desc",
+            query
+        );
+    }
 }
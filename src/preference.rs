@@ -0,0 +1,165 @@
+use crate::{
+    path::Format, Connector, DataSource, Error, GetRequest, ListRequest, Page, PathBuilder,
+};
+use hyper::{header::HeaderMap, Body, Response};
+use serde::de::DeserializeOwned;
+
+/// Preferences which the server reported as honored using the `Preference-Applied` response header.
+///
+/// Servers silently ignore preferences they don't support, so this can be used to detect whether
+/// for example a [`ListRequest::max_page_size`] took effect.
+/// ```rust
+/// use hyper::header::HeaderMap;
+/// use odata_simple_client::AppliedPreferences;
+///
+/// let mut headers = HeaderMap::new();
+/// headers.insert("Preference-Applied", "odata.maxpagesize=50, omit-values=nulls".parse().unwrap());
+///
+/// let applied = AppliedPreferences::from_headers(&headers);
+/// assert_eq!(applied.get("odata.maxpagesize"), Some("50"));
+/// assert!(applied.contains("omit-values"));
+/// assert!(!applied.contains("respond-async"));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppliedPreferences {
+    preferences: Vec<(String, Option<String>)>,
+}
+
+impl AppliedPreferences {
+    /// Parse every `Preference-Applied` header of a response. Headers which aren't valid UTF-8 are ignored.
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        let preferences = headers
+            .get_all("Preference-Applied")
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .filter_map(|preference| {
+                // Parameters following the value, such as `;charset=...`, aren't relevant for honored preferences.
+                let preference = preference.split(';').next().unwrap_or_default().trim();
+                let (name, value) = match preference.split_once('=') {
+                    Some((name, value)) => (name, Some(value.trim().trim_matches('"').to_string())),
+                    None => (preference, None),
+                };
+                (!name.trim().is_empty()).then(|| (name.trim().to_string(), value))
+            })
+            .collect();
+
+        AppliedPreferences { preferences }
+    }
+
+    /// Returns `true` if the preference `name` was applied, case-insensitively.
+    pub fn contains(&self, name: &str) -> bool {
+        self.preferences
+            .iter()
+            .any(|(applied, _)| applied.eq_ignore_ascii_case(name))
+    }
+
+    /// Value of the applied preference `name`, such as the page size of `odata.maxpagesize`.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.preferences
+            .iter()
+            .find(|(applied, _)| applied.eq_ignore_ascii_case(name))
+            .and_then(|(_, value)| value.as_deref())
+    }
+
+    /// Returns `true` if the server didn't report any applied preferences.
+    pub fn is_empty(&self) -> bool {
+        self.preferences.is_empty()
+    }
+}
+
+impl From<&Response<Body>> for AppliedPreferences {
+    fn from(response: &Response<Body>) -> Self {
+        Self::from_headers(response.headers())
+    }
+}
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    /// Fetch a single resource using a [`GetRequest`], along with the preferences the server reported as applied.
+    /// See [`AppliedPreferences`].
+    pub async fn fetch_with_preferences<T>(
+        &self,
+        request: GetRequest,
+    ) -> Result<(T, AppliedPreferences), Error>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self
+            .execute(Into::<PathBuilder>::into(request).format(Format::Json))
+            .await?;
+
+        let applied = AppliedPreferences::from(&response);
+        Ok((self.deserialize_entity::<T>(response).await?, applied))
+    }
+
+    /// Fetch a [`Page`]d list of resources using a [`ListRequest`], along with the preferences the server reported
+    /// as applied, for example to check whether a [`ListRequest::max_page_size`] was honored.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, ListRequest, Page};
+    /// # use serde::Deserialize;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let (page, applied) = datasource
+    ///     .fetch_paged_with_preferences::<Dokument>(ListRequest::new("Dokument").max_page_size(50))
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// if !applied.contains("odata.maxpagesize") {
+    ///     println!("page size not applied, got {} documents", page.len());
+    /// }
+    /// # });
+    /// ```
+    pub async fn fetch_paged_with_preferences<T>(
+        &self,
+        request: ListRequest,
+    ) -> Result<(Page<T>, AppliedPreferences), Error>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self
+            .execute(Into::<PathBuilder>::into(request).format(Format::Json))
+            .await?;
+
+        let applied = AppliedPreferences::from(&response);
+        Ok((self.deserialize_page::<T>(response).await?, applied))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock, ListRequest};
+
+    #[tokio::test]
+    async fn test_fetch_paged_with_preferences() {
+        let (datasource, connector) = mock::datasource(|_| {
+            let mut response = mock::json(200, r#"{"value": [1, 2]}"#);
+            let _ = response
+                .headers_mut()
+                .insert("Preference-Applied", "odata.maxpagesize=2".parse().unwrap());
+            response
+        });
+
+        let (page, applied) = datasource
+            .fetch_paged_with_preferences::<u32>(ListRequest::new("Dokument").max_page_size(2))
+            .await
+            .unwrap();
+        assert_eq!(page.len(), 2);
+        assert_eq!(applied.get("odata.maxpagesize"), Some("2"));
+        assert_eq!(
+            connector.requests()[0].headers["Prefer"],
+            "odata.maxpagesize=2"
+        );
+    }
+}
@@ -1,28 +1,65 @@
 use crate::{
-    deserialize_as, path::Format, Connector, DataSource, Error, GetRequest, ListRequest, Page,
-    PathBuilder,
+    check_status,
+    create::bulk_create,
+    operation::retry_after,
+    path::Format,
+    stream::{collect_pages, fetch_all_parallel},
+    Connector, DataSource, Error, GetRequest, ListRequest, Page, PathBuilder,
+};
+use governor::{
+    clock::{Clock, QuantaClock, ReasonablyRealtime},
+    middleware::NoOpMiddleware,
 };
 use hyper::{Body, Response};
-use serde::de::DeserializeOwned;
-use std::{num::NonZeroU32, sync::Arc};
+use log::debug;
+use serde::{de::DeserializeOwned, Serialize};
+use std::{num::NonZeroU32, sync::Arc, time::Duration};
 
-pub type RateLimiter = governor::RateLimiter<
+pub type RateLimiter<K = QuantaClock> = governor::RateLimiter<
     governor::state::NotKeyed,
     governor::state::InMemoryState,
-    governor::clock::QuantaClock,
+    K,
+    NoOpMiddleware<<K as Clock>::Instant>,
 >;
-pub use governor::Quota;
+pub use governor::{Jitter, Quota};
 
 /// Rate-limited wrapper around a DataSource. Requires the 'rate-limiter' feature to be enabled.
 /// Cloning the RateLimitedDataSource shares the rate-limiting mechanism between the two copies,
 /// preserving the rate-limiting guarantees across all of them.
+///
+/// The rate limiter measures time using the [`Clock`] `K`, which can be replaced using
+/// [`RateLimitedDataSource::with_clock`], for example with a `governor::clock::FakeRelativeClock`
+/// to test rate-limiting behaviour deterministically.
 #[derive(Debug, Clone)]
-pub struct RateLimitedDataSource<C>
+pub struct RateLimitedDataSource<C, K = QuantaClock>
 where
     C: Connector,
+    K: Clock,
 {
     datasource: DataSource<C>,
-    rate_limiter: Arc<RateLimiter>,
+    rate_limiter: Arc<RateLimiter<K>>,
+    jitter: Jitter,
+    retry: Option<Retry>,
+}
+
+/// Retry policy set using [`RateLimitedDataSource::with_retry`].
+#[derive(Debug, Clone, Copy)]
+struct Retry {
+    max_attempts: u32,
+    base_delay: Duration,
+}
+
+impl Retry {
+    /// Delay before sending the request again after `attempt` attempts, doubling with every attempt.
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+
+    /// Longest delay before a retry, which also caps the delay asked for by the `Retry-After` header of a response.
+    fn max_delay(&self) -> Duration {
+        self.backoff(self.max_attempts.saturating_add(1))
+    }
 }
 
 impl<C> RateLimitedDataSource<C>
@@ -34,6 +71,8 @@ where
         Self {
             datasource,
             rate_limiter: Arc::new(RateLimiter::direct(quota)),
+            jitter: Jitter::default(),
+            retry: None,
         }
     }
 
@@ -43,13 +82,133 @@ where
     pub fn per_second(datasource: DataSource<C>, per_second: NonZeroU32) -> Self {
         Self::new(datasource, Quota::per_second(per_second))
     }
+}
+
+impl<C, K> RateLimitedDataSource<C, K>
+where
+    C: Connector,
+    K: Clock,
+{
+    /// Construct a RateLimitedDataSource from an existing [`DataSource`] and a [`Quota`],
+    /// measuring time using a custom `clock`.
+    ///
+    /// Only [`RateLimitedDataSource::try_fetch`] is available for clocks which are not
+    /// [`ReasonablyRealtime`], since waiting for the rate limiter requires a real clock.
+    pub fn with_clock(datasource: DataSource<C>, quota: Quota, clock: &K) -> Self {
+        Self {
+            datasource,
+            rate_limiter: Arc::new(RateLimiter::direct_with_clock(quota, clock)),
+            jitter: Jitter::default(),
+            retry: None,
+        }
+    }
+
+    /// Add a random [`Jitter`] to the time spent waiting for the rate limiter,
+    /// to avoid multiple waiting requests being sent at the exact same time.
+    pub fn with_jitter(mut self, jitter: Jitter) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Retry requests failing with a retriable status, such as `429 Too Many Requests` or `503 Service Unavailable`,
+    /// until they have been sent `max_attempts` times in total. Each retry waits for the rate limiter as usual.
+    ///
+    /// The delay before each retry is given by the `Retry-After` header of the response if present, and otherwise
+    /// starts at `base_delay`, doubling with every attempt. The `Retry-After` header is capped at `base_delay`
+    /// times `2^max_attempts`, so a server asking for a delay of hours doesn't stall the caller for as long.
+    ///
+    /// Which statuses are retriable is decided by [`DataSource::is_retriable`], which includes `429`, `502`, `503`
+    /// and `504`, and any added using [`DataSource::with_retry_on_status`]. Other statuses, such as `404 Not Found`
+    /// or `500 Internal Server Error`, fail immediately.
+    /// If every attempt fails, the status of the last response is returned as [`Error::Status`].
+    pub fn with_retry(mut self, max_attempts: u32, base_delay: Duration) -> Self {
+        self.retry = Some(Retry {
+            max_attempts,
+            base_delay,
+        });
+        self
+    }
+
+    /// Fetch a single resource using a [`GetRequest`], without waiting for the rate limiter.
+    ///
+    /// Returns [`Error::RateLimited`] immediately if the request would exceed the rate limit.
+    pub async fn try_fetch<T>(&self, request: GetRequest) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        if self.rate_limiter.check().is_err() {
+            return Err(Error::RateLimited);
+        }
+
+        self.datasource.fetch(request).await
+    }
+
+    /// Fetch a single resource using a [`GetRequest`] immediately, ahead of any requests waiting for the rate limiter.
+    ///
+    /// A permit is taken from the rate limiter if one is available, delaying the requests which are waiting,
+    /// but the request is sent regardless. Priority requests therefore count towards the rate limit,
+    /// but aren't bound by it, so they should be reserved for the occasional interactive request,
+    /// as a steady stream of them can exceed the quota of the API and starve all other requests.
+    pub async fn fetch_priority<T>(&self, request: GetRequest) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let _ = self.rate_limiter.check();
+        self.datasource.fetch(request).await
+    }
+
+    /// Fetch a [`Page`]d list of resources using a [`ListRequest`] immediately, ahead of any requests waiting
+    /// for the rate limiter. See [`RateLimitedDataSource::fetch_priority`].
+    pub async fn fetch_paged_priority<T>(&self, request: ListRequest) -> Result<Page<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let _ = self.rate_limiter.check();
+        self.datasource.fetch_paged(request).await
+    }
+}
+
+impl<C, K> RateLimitedDataSource<C, K>
+where
+    C: Connector,
+    K: ReasonablyRealtime,
+{
+    async fn until_ready(&self) {
+        self.rate_limiter.until_ready_with_jitter(self.jitter).await;
+    }
 
     async fn execute<R>(&self, request: R) -> Result<Response<Body>, Error>
     where
         R: Into<PathBuilder>,
     {
-        self.rate_limiter.until_ready().await;
-        self.datasource.execute(request).await
+        let builder: PathBuilder = request.into();
+        let mut attempt = 1;
+        loop {
+            self.until_ready().await;
+            let response = self.datasource.execute(builder.clone()).await?;
+
+            let delay = match self.retry {
+                Some(retry)
+                    if attempt < retry.max_attempts
+                        && self.datasource.is_retriable_status(response.status()) =>
+                {
+                    retry_after(&response).map_or_else(
+                        || retry.backoff(attempt),
+                        |delay| delay.min(retry.max_delay()),
+                    )
+                }
+                _ => return Ok(response),
+            };
+
+            debug!(
+                "retrying request after {} in {:?}, attempt {}",
+                response.status(),
+                delay,
+                attempt
+            );
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
     }
 
     /// Fetch two resources on a datasource rate-limited to one per second,
@@ -92,14 +251,21 @@ where
     /// # assert_eq!(second.titel, "Revideret grund- og nærhedsnotat om sanktioner på toldområdet\n");
     /// # });
     /// ```
-    pub async fn fetch<T>(&self, request: GetRequest) -> Result<T, Error>
+    pub async fn fetch<T>(&self, request: impl Into<GetRequest>) -> Result<T, Error>
     where
         T: DeserializeOwned,
     {
-        let response = self
-            .execute(Into::<PathBuilder>::into(request).format(Format::Json))
-            .await?;
-        deserialize_as::<T>(response).await
+        let builder = Into::<PathBuilder>::into(request.into());
+        let is_count = builder.is_count();
+
+        let response = self.execute(builder.format(Format::Json)).await?;
+        if is_count {
+            self.datasource
+                .deserialize_as::<T>(check_status(response).await?)
+                .await
+        } else {
+            self.datasource.deserialize_entity::<T>(response).await
+        }
     }
 
     /// Fetch a [`Page`]d list of resources using a [`ListRequest`]
@@ -140,6 +306,272 @@ where
         let response = self
             .execute(Into::<PathBuilder>::into(request).format(Format::Json))
             .await?;
-        deserialize_as::<Page<T>>(response).await
+        self.datasource.deserialize_page::<T>(response).await
+    }
+
+    /// Fetch up to `max_pages` [`Page`]s of resources using a [`ListRequest`], following the `next_link` of each page.
+    /// Each page counts towards the rate limit. See [`DataSource::fetch_pages`]
+    pub async fn fetch_pages<T>(
+        &self,
+        request: ListRequest,
+        max_pages: usize,
+    ) -> Result<Vec<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        if max_pages == 0 {
+            return Ok(Vec::new());
+        }
+
+        let page = self.fetch_paged(request).await?;
+        collect_pages(page, max_pages, |next_link| self.fetch_next(next_link)).await
+    }
+
+    /// Fetch every resource of a [`ListRequest`], fetching up to `concurrency` pages at a time.
+    /// Each page counts towards the rate limit, so the concurrency is also bounded by the [`Quota`].
+    /// See [`DataSource::fetch_all_parallel`]
+    pub async fn fetch_all_parallel<T>(
+        &self,
+        request: ListRequest,
+        concurrency: usize,
+    ) -> Result<Vec<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        fetch_all_parallel(
+            request,
+            concurrency,
+            |request| self.fetch_paged(request),
+            |next_link| self.fetch_next(next_link),
+        )
+        .await
+    }
+
+    /// Create each of `items` as a resource of `resource_type`, posting up to `concurrency` of them at a time,
+    /// like [`DataSource::bulk_create`]. Each item counts towards the rate limit.
+    pub async fn bulk_create<T>(
+        &self,
+        resource_type: &str,
+        items: &[T],
+        concurrency: usize,
+    ) -> Vec<Result<(), Error>>
+    where
+        T: Serialize,
+    {
+        bulk_create(items, concurrency, |item| async move {
+            self.until_ready().await;
+            self.datasource
+                .create_without_response(resource_type, item)
+                .await
+        })
+        .await
+    }
+
+    /// Fetch the page at `next_link` once the rate limit allows it.
+    async fn fetch_next<T>(&self, next_link: String) -> Result<Page<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        self.until_ready().await;
+        self.datasource.fetch_next(&next_link).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Quota, RateLimitedDataSource};
+    use crate::{mock, Error, GetRequest, HeaderName, HeaderValue, ListRequest};
+    use governor::clock::FakeRelativeClock;
+    use hyper::StatusCode;
+    use serde::Deserialize;
+    use std::{
+        num::NonZeroU32,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+
+    #[derive(Deserialize, Debug)]
+    struct Dokument {
+        id: usize,
+    }
+
+    #[tokio::test]
+    async fn test_try_fetch_rate_limited() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"id": 24}"#));
+        let datasource = RateLimitedDataSource::per_second(datasource, NonZeroU32::new(1).unwrap());
+
+        let dokument: Dokument = datasource
+            .try_fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(dokument.id, 24);
+
+        match datasource
+            .try_fetch::<Dokument>(GetRequest::new("Dokument", 24))
+            .await
+        {
+            Err(Error::RateLimited) => {}
+            other => panic!("expected rate limit error, got {:?}", other.map(|d| d.id)),
+        }
+        assert_eq!(connector.requests().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_default_headers() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"id": 24}"#));
+        let datasource = datasource.with_header(
+            HeaderName::from_static("x-api-key"),
+            HeaderValue::from_static("secret"),
+        );
+        let datasource = RateLimitedDataSource::per_second(datasource, NonZeroU32::new(1).unwrap());
+
+        let _: Dokument = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(connector.requests()[0].headers["X-Api-Key"], "secret");
+    }
+
+    #[tokio::test]
+    async fn test_fetch_count() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, "42"));
+        let datasource = RateLimitedDataSource::per_second(datasource, NonZeroU32::new(1).unwrap());
+
+        let count: u64 = datasource
+            .fetch(ListRequest::new("Dokument").count_only())
+            .await
+            .unwrap();
+        assert_eq!(count, 42);
+        assert!(connector.requests()[0]
+            .uri
+            .path()
+            .ends_with("/Dokument/$count"));
+    }
+
+    #[tokio::test]
+    async fn test_retry() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let (datasource, connector) = mock::datasource({
+            let attempts = attempts.clone();
+            move |request| match request.uri.path() {
+                "/api/Dokument(404)" => mock::json(404, "{}"),
+                _ if attempts.fetch_add(1, Ordering::SeqCst) < 2 => mock::json(429, "{}"),
+                _ => mock::json(200, r#"{"id": 24}"#),
+            }
+        });
+        let datasource =
+            RateLimitedDataSource::per_second(datasource, NonZeroU32::new(100).unwrap())
+                .with_retry(3, Duration::from_millis(1));
+
+        let dokument: Dokument = datasource
+            .fetch(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap();
+        assert_eq!(dokument.id, 24);
+        assert_eq!(connector.requests().len(), 3);
+
+        let error = datasource
+            .fetch::<Dokument>(GetRequest::new("Dokument", 404))
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Status { code, .. } if code == StatusCode::NOT_FOUND));
+        assert_eq!(connector.requests().len(), 4);
+
+        attempts.store(0, Ordering::SeqCst);
+        let error = datasource
+            .with_retry(2, Duration::from_millis(1))
+            .fetch::<Dokument>(GetRequest::new("Dokument", 24))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, Error::Status { code, .. } if code == StatusCode::TOO_MANY_REQUESTS)
+        );
+        assert_eq!(connector.requests().len(), 6);
+    }
+
+    #[tokio::test]
+    async fn test_retry_statuses_and_delay() {
+        let (datasource, connector) = mock::datasource(|request| match request.uri.path() {
+            "/api/Dokument(500)" => mock::json(500, "{}"),
+            "/api/Dokument(503)" => {
+                let mut response = mock::json(503, "{}");
+                let _ = response
+                    .headers_mut()
+                    .insert("Retry-After", HeaderValue::from_static("86400"));
+                response
+            }
+            _ => mock::json(200, r#"{"id": 24}"#),
+        });
+        let datasource =
+            RateLimitedDataSource::per_second(datasource, NonZeroU32::new(100).unwrap())
+                .with_retry(2, Duration::from_millis(1));
+
+        let error = datasource
+            .fetch::<Dokument>(GetRequest::new("Dokument", 500))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(error, Error::Status { code, .. } if code == StatusCode::INTERNAL_SERVER_ERROR)
+        );
+        assert_eq!(connector.requests().len(), 1);
+
+        let error = tokio::time::timeout(
+            Duration::from_secs(5),
+            datasource.fetch::<Dokument>(GetRequest::new("Dokument", 503)),
+        )
+        .await
+        .expect("Retry-After should be capped")
+        .unwrap_err();
+        assert!(
+            matches!(error, Error::Status { code, .. } if code == StatusCode::SERVICE_UNAVAILABLE)
+        );
+        assert_eq!(connector.requests().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_priority() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"id": 24}"#));
+        let clock = FakeRelativeClock::default();
+        let datasource = RateLimitedDataSource::with_clock(
+            datasource,
+            Quota::per_second(NonZeroU32::new(1).unwrap()),
+            &clock,
+        );
+
+        let request = || GetRequest::new("Dokument", 24);
+        assert!(datasource
+            .fetch_priority::<Dokument>(request())
+            .await
+            .is_ok());
+        assert!(datasource
+            .fetch_priority::<Dokument>(request())
+            .await
+            .is_ok());
+
+        // The priority requests used up the quota.
+        assert!(datasource.try_fetch::<Dokument>(request()).await.is_err());
+        assert_eq!(connector.requests().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fake_clock() {
+        let (datasource, connector) = mock::datasource(|_| mock::json(200, r#"{"id": 24}"#));
+        let clock = FakeRelativeClock::default();
+        let datasource = RateLimitedDataSource::with_clock(
+            datasource,
+            Quota::per_second(NonZeroU32::new(1).unwrap()),
+            &clock,
+        );
+
+        let request = || GetRequest::new("Dokument", 24);
+        assert!(datasource.try_fetch::<Dokument>(request()).await.is_ok());
+        assert!(datasource.try_fetch::<Dokument>(request()).await.is_err());
+
+        clock.advance(Duration::from_secs(1));
+        assert!(datasource.try_fetch::<Dokument>(request()).await.is_ok());
+        assert_eq!(connector.requests().len(), 2);
     }
 }
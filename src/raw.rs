@@ -0,0 +1,189 @@
+use crate::{
+    check_status, path::Format, Connector, DataSource, Error, GetRequest, ListRequest, PathBuilder,
+};
+use hyper::Uri;
+use serde::{de::DeserializeOwned, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+
+/// Serializable representation of a [`GetRequest`] or [`ListRequest`].
+///
+/// The request is represented by its canonical path and query relative to the base path of a [`DataSource`],
+/// such as `/Dokument(24)?$expand=Fil`, which makes it suitable for persisting in a job queue and replaying
+/// later using [`DataSource::fetch_raw`]. Headers set on the request, such as preferences, are not included.
+/// ```rust
+/// use odata_simple_client::{ListRequest, RawRequest};
+///
+/// let request: RawRequest = ListRequest::new("Dokument").top(10).into();
+/// assert_eq!(request.to_string(), "/Dokument?$top=10");
+///
+/// let replayed: RawRequest = "/Dokument?$top=10".parse().unwrap();
+/// assert_eq!(replayed, request);
+/// ```
+///
+/// Complete URLs, such as those found in logs, can be converted from a [`Uri`](`hyper::Uri`). Only their path and
+/// query are kept, so any path preceding the resource type is retained as the base path of the request.
+/// ```rust
+/// use hyper::Uri;
+/// use odata_simple_client::RawRequest;
+///
+/// let uri: Uri = "https://oda.ft.dk/api/Dokument(24)?$expand=Fil".parse().unwrap();
+/// let request = RawRequest::try_from(uri).unwrap();
+/// assert_eq!(request.to_string(), "/api/Dokument(24)?$expand=Fil");
+/// ```
+#[derive(Debug, Clone)]
+pub struct RawRequest {
+    builder: PathBuilder,
+}
+
+impl fmt::Display for RawRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.builder.build() {
+            Ok(path_and_query) => {
+                // Don't emit a dangling question mark for requests without parameters.
+                let rendered = path_and_query.as_str();
+                f.write_str(rendered.strip_suffix('?').unwrap_or(rendered))
+            }
+            Err(_) => Err(fmt::Error),
+        }
+    }
+}
+
+impl PartialEq for RawRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_string() == other.to_string()
+    }
+}
+
+impl FromStr for RawRequest {
+    type Err = Error;
+
+    fn from_str(path_and_query: &str) -> Result<Self, Self::Err> {
+        Ok(RawRequest {
+            builder: PathBuilder::parse(path_and_query)?,
+        })
+    }
+}
+
+impl TryFrom<&Uri> for RawRequest {
+    type Error = Error;
+
+    fn try_from(uri: &Uri) -> Result<Self, Self::Error> {
+        match uri.path_and_query() {
+            Some(path_and_query) => path_and_query.as_str().parse(),
+            None => Err(Error::InvalidRequest(format!("`{uri}` has no path"))),
+        }
+    }
+}
+
+impl TryFrom<Uri> for RawRequest {
+    type Error = Error;
+
+    fn try_from(uri: Uri) -> Result<Self, Self::Error> {
+        RawRequest::try_from(&uri)
+    }
+}
+
+impl Serialize for RawRequest {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for RawRequest {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+impl From<GetRequest> for RawRequest {
+    fn from(request: GetRequest) -> Self {
+        RawRequest {
+            builder: request.into(),
+        }
+    }
+}
+
+impl From<ListRequest> for RawRequest {
+    fn from(request: ListRequest) -> Self {
+        RawRequest {
+            builder: request.into(),
+        }
+    }
+}
+
+impl From<RawRequest> for PathBuilder {
+    fn from(request: RawRequest) -> Self {
+        request.builder
+    }
+}
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    /// Fetch a [`RawRequest`], deserializing the response as `T`.
+    ///
+    /// Use a [`Page`](`crate::Page`) as `T` if the request originated from a [`ListRequest`].
+    pub async fn fetch_raw<T>(&self, request: RawRequest) -> Result<T, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let response = self
+            .execute(Into::<PathBuilder>::into(request).format(Format::Json))
+            .await?;
+        self.deserialize_as::<T>(check_status(response).await?)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RawRequest;
+    use crate::{Comparison, Direction, GetRequest, InlineCount, ListRequest, Literal};
+    use hyper::Uri;
+
+    #[test]
+    fn test_serde_round_trip() {
+        let request: RawRequest = ListRequest::new("Dokument")
+            .filter("titel", Comparison::Equal, Literal::string("Grund"))
+            .skip(20)
+            .into();
+
+        let serialized = serde_json::to_string(&request).unwrap();
+        assert_eq!(
+            serialized,
+            r#""/Dokument?$filter=titel%20eq%20%27Grund%27&$skip=20""#
+        );
+
+        let deserialized: RawRequest = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, request);
+    }
+
+    #[test]
+    fn test_get_request() {
+        let request: RawRequest = GetRequest::new("Dokument", 24).into();
+        assert_eq!(request.to_string(), "/Dokument(24)");
+        assert_eq!("/Dokument(24)".parse::<RawRequest>().unwrap(), request);
+    }
+
+    #[test]
+    fn test_uri_round_trip() {
+        let request: RawRequest = ListRequest::new("Dokument")
+            .base_path("/api")
+            .filter("titel", Comparison::Equal, Literal::string("Grund"))
+            .order_by("id", Direction::Descending)
+            .inline_count(InlineCount::AllPages)
+            .top(10)
+            .into();
+
+        let uri: Uri = format!("https://oda.ft.dk{request}").parse().unwrap();
+        let parsed = RawRequest::try_from(&uri).unwrap();
+        assert_eq!(parsed, request);
+        assert_eq!(parsed.to_string(), uri.path_and_query().unwrap().as_str());
+
+        let uri: Uri = "https://oda.ft.dk".parse().unwrap();
+        assert!(RawRequest::try_from(uri).is_err());
+    }
+}
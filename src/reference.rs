@@ -0,0 +1,164 @@
+use crate::{check_status, Connector, DataSource, Error, PathBuilder};
+use hyper::Method;
+use serde_json::json;
+
+/// Build the path of the references held by the `navigation` property of the resource `from`.
+fn references((resource_type, id): (&str, usize), navigation: &str) -> PathBuilder {
+    PathBuilder::new(resource_type.to_string())
+        .id(id)
+        .segment(navigation)
+        .segment("$ref")
+}
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    /// Relate the resource `from`, given by its resource type and id, to an existing resource through its
+    /// `navigation` property, without fetching either of them, by posting a reference to `from/navigation/$ref`
+    /// as defined by OData 4.0.
+    ///
+    /// The related resource is identified by its id URL `to_id_url`, such as `Aktør(12)` or an absolute URL.
+    /// Fails with [`Error::Status`] if the server rejects the reference.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::DataSource;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// # tokio_test::block_on(async {
+    /// datasource
+    ///     .add_reference(("Dokument", 24), "Aktør", "Aktør(12)")
+    ///     .await
+    ///     .unwrap();
+    /// # });
+    /// ```
+    pub async fn add_reference(
+        &self,
+        from: (&str, usize),
+        navigation: &str,
+        to_id_url: &str,
+    ) -> Result<(), Error> {
+        let body = json!({ "@odata.id": to_id_url }).to_string().into_bytes();
+        let response = self
+            .execute_with_body(references(from, navigation), Method::POST, Some(body))
+            .await?;
+        let _ = check_status(response).await?;
+        Ok(())
+    }
+
+    /// Remove the relation between the resource `from` and the resource identified by `to_id_url` through
+    /// its `navigation` property, by deleting the reference, like [`DataSource::add_reference`].
+    ///
+    /// Neither of the resources themselves are deleted.
+    pub async fn remove_reference(
+        &self,
+        from: (&str, usize),
+        navigation: &str,
+        to_id_url: &str,
+    ) -> Result<(), Error> {
+        let request = references(from, navigation).raw_param("$id", to_id_url);
+        let response = self.execute_method(request, Method::DELETE).await?;
+        let _ = check_status(response).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock, Error, InlineCount};
+    use hyper::{Body, Method, Response, StatusCode};
+
+    #[tokio::test]
+    async fn test_references() {
+        let (datasource, connector) = mock::datasource(|request| {
+            let status = match request.uri.path() {
+                "/api/Dokument(1)/Akt%C3%B8r/$ref" => 404,
+                _ => 204,
+            };
+            Response::builder()
+                .status(status)
+                .body(Body::empty())
+                .unwrap()
+        });
+
+        datasource
+            .add_reference(("Dokument", 24), "Aktør", "Aktør(12)")
+            .await
+            .unwrap();
+        datasource
+            .remove_reference(("Dokument", 24), "Aktør", "Aktør(12)")
+            .await
+            .unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].method, Method::POST);
+        assert_eq!(requests[0].uri, "/api/Dokument(24)/Akt%C3%B8r/$ref");
+        assert_eq!(requests[0].headers["Content-Type"], "application/json");
+        assert_eq!(requests[0].body, r#"{"@odata.id":"Aktør(12)"}"#.as_bytes());
+        assert_eq!(requests[1].method, Method::DELETE);
+        assert_eq!(
+            requests[1].uri,
+            "/api/Dokument(24)/Akt%C3%B8r/$ref?$id=Akt%C3%B8r%2812%29"
+        );
+
+        let error = datasource
+            .add_reference(("Dokument", 1), "Aktør", "Aktør(12)")
+            .await
+            .unwrap_err();
+        assert!(matches!(error, Error::Status { code, .. } if code == StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test]
+    async fn test_method_override() {
+        let (datasource, connector) =
+            mock::datasource(|_| Response::builder().status(204).body(Body::empty()).unwrap());
+        let datasource = datasource.with_method_override(true);
+
+        datasource
+            .add_reference(("Dokument", 24), "Aktør", "Aktør(12)")
+            .await
+            .unwrap();
+        datasource
+            .remove_reference(("Dokument", 24), "Aktør", "Aktør(12)")
+            .await
+            .unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].method, Method::POST);
+        assert!(!requests[0].headers.contains_key("X-HTTP-Method-Override"));
+        assert_eq!(requests[1].method, Method::POST);
+        assert_eq!(requests[1].headers["X-HTTP-Method-Override"], "DELETE");
+        assert_eq!(
+            requests[1].uri,
+            "/api/Dokument(24)/Akt%C3%B8r/$ref?$id=Akt%C3%B8r%2812%29"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_references_without_defaults() {
+        let (datasource, connector) =
+            mock::datasource(|_| Response::builder().status(204).body(Body::empty()).unwrap());
+        let datasource = datasource
+            .with_default_select(vec![String::from("id")])
+            .with_default_inline_count(InlineCount::AllPages)
+            .with_max_top(100);
+
+        datasource
+            .add_reference(("Dokument", 24), "Aktør", "Aktør(12)")
+            .await
+            .unwrap();
+        datasource
+            .remove_reference(("Dokument", 24), "Aktør", "Aktør(12)")
+            .await
+            .unwrap();
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/api/Dokument(24)/Akt%C3%B8r/$ref");
+        assert_eq!(
+            requests[1].uri,
+            "/api/Dokument(24)/Akt%C3%B8r/$ref?$id=Akt%C3%B8r%2812%29"
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use crate::{Direction, ListRequest, ResourceType};
+use serde::{Deserialize, Serialize};
+
+/// Declarative description of a [`ListRequest`], such as one received from another service,
+/// which can be turned into a request using [`ListRequest::from_spec`].
+///
+/// Every field is optional when deserializing, and filters are written in OData syntax,
+/// so a spec can be described entirely in JSON:
+/// ```rust
+/// use odata_simple_client::{ListRequest, QuerySpec};
+///
+/// let spec: QuerySpec = serde_json::from_str(r#"{
+///     "select": ["id", "titel"],
+///     "filter": ["typeid eq 5"],
+///     "order_by": [["dato", "descending"]],
+///     "top": 10
+/// }"#).unwrap();
+///
+/// let request = ListRequest::from_spec("Dokument", &spec);
+/// assert_eq!(
+///     request.to_query_string("/api").unwrap(),
+///     "/api/Dokument?$filter=typeid%20eq%205&$orderby=dato%20desc&$select=id,titel&$top=10"
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct QuerySpec {
+    /// Properties to retrieve. See [`ListRequest::select`].
+    pub select: Vec<String>,
+    /// Navigation properties to expand. See [`ListRequest::expand`].
+    pub expand: Vec<String>,
+    /// Filter expressions in OData syntax, which must all be true. See [`ListRequest::filter_raw`].
+    pub filter: Vec<String>,
+    /// Properties to order the resources by, in order of precedence. See [`ListRequest::order_by`].
+    pub order_by: Vec<(String, Direction)>,
+    /// Maximum number of resources to retrieve. See [`ListRequest::top`].
+    pub top: Option<u32>,
+    /// Number of resources to skip. See [`ListRequest::skip`].
+    pub skip: Option<u32>,
+}
+
+impl ListRequest {
+    /// Construct a request for `resource_type` described by a [`QuerySpec`].
+    ///
+    /// Empty parts of the spec are left out of the request entirely.
+    pub fn from_spec<R>(resource_type: R, spec: &QuerySpec) -> Self
+    where
+        R: Into<ResourceType>,
+    {
+        let mut request = ListRequest::new(resource_type);
+        if !spec.select.is_empty() {
+            request = request.select(spec.select.iter().map(String::as_str));
+        }
+        if !spec.expand.is_empty() {
+            request = request.expand(spec.expand.iter().map(String::as_str));
+        }
+        for filter in &spec.filter {
+            request = request.filter_raw(filter);
+        }
+        for (field, direction) in &spec.order_by {
+            request = request.order_by(field.as_str(), *direction);
+        }
+        if let Some(top) = spec.top {
+            request = request.top(top);
+        }
+        if let Some(skip) = spec.skip {
+            request = request.skip(skip);
+        }
+        request
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::QuerySpec;
+    use crate::{Direction, ListRequest};
+
+    #[test]
+    fn test_from_spec() {
+        let request = ListRequest::from_spec("Dokument", &QuerySpec::default());
+        assert_eq!(request.to_query_string("/api").unwrap(), "/api/Dokument");
+
+        let spec = QuerySpec {
+            expand: vec![String::from("Fil")],
+            filter: vec![
+                String::from("typeid eq 5"),
+                String::from("year(dato) eq 2020"),
+            ],
+            order_by: vec![
+                (String::from("dato"), Direction::Descending),
+                (String::from("id"), Direction::Ascending),
+            ],
+            skip: Some(20),
+            ..QuerySpec::default()
+        };
+        let request = ListRequest::from_spec("Dokument", &spec);
+        assert_eq!(
+            request.to_query_string("/api").unwrap(),
+            "/api/Dokument?$expand=Fil&$filter=%28typeid%20eq%205%29%20and%20%28year%28dato%29%20eq%202020%29&$orderby=dato%20desc,id%20asc&$skip=20"
+        );
+
+        let json = serde_json::to_string(&spec).unwrap();
+        assert_eq!(serde_json::from_str::<QuerySpec>(&json).unwrap(), spec);
+    }
+}
@@ -0,0 +1,723 @@
+use crate::{
+    Comparison, Connector, DataSource, Direction, Error, FilterExpr, Format, InlineCount,
+    ListRequest, Literal, ODataVersion, Page,
+};
+use futures_util::{
+    stream::{self, Stream, StreamExt},
+    TryStreamExt,
+};
+use serde::de::{self, DeserializeOwned};
+use serde_json::{Map, Value};
+use std::future::Future;
+
+/// Length of the path and query above which [`DataSource::batch_get`] splits the ids into several requests,
+/// unless a lower limit is set using [`DataSource::with_query_length_limit`].
+const BATCH_QUERY_LENGTH: usize = 2048;
+
+/// How an API splits large collections into pages, as detected by [`DataSource::probe_paging_style`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PagingStyle {
+    /// The server limits the size of each page, and links to the next page using a `next_link`,
+    /// which is followed by [`DataSource::stream`] and [`DataSource::fetch_pages`].
+    ServerDriven,
+    /// The server returns every matching resource at once, so the client must page through large
+    /// collections using [`ListRequest::top`] and [`ListRequest::skip`], as [`DataSource::fetch_all_parallel`] does.
+    ClientDriven,
+}
+
+/// Position of a [`DataSource::stream_pages`] within the sequence of pages.
+enum Cursor {
+    First(Box<ListRequest>),
+    Next(String),
+    Done,
+}
+
+/// Build the requests for each page following `first`, by offsetting `request` by the size of the first page,
+/// until the inline count of `first` is reached.
+///
+/// Returns `None` if the page count can't be computed, because the API didn't report an inline count.
+pub(crate) fn remaining_pages<T>(
+    request: &ListRequest,
+    first: &Page<T>,
+) -> Option<Vec<ListRequest>> {
+    if first.next_link.is_none() || first.is_empty() {
+        return Some(Vec::new());
+    }
+
+    let page_size = first.len() as u64;
+    let total = first.info().total?;
+
+    Some(
+        (1..total.div_ceil(page_size))
+            .map_while(|page| u32::try_from(page * page_size).ok())
+            .map(|offset| request.clone().skip(offset))
+            .collect(),
+    )
+}
+
+/// Collect the values of `first` and of the pages following it, up to `max_pages` pages in total,
+/// fetching each page after the first by its `next_link` using `fetch_next`.
+pub(crate) async fn collect_pages<T, N, F>(
+    first: Page<T>,
+    max_pages: usize,
+    mut fetch_next: N,
+) -> Result<Vec<T>, Error>
+where
+    N: FnMut(String) -> F,
+    F: Future<Output = Result<Page<T>, Error>>,
+{
+    let mut values = Vec::new();
+    let mut page = first;
+    for fetched in 1.. {
+        values.append(&mut page.value);
+
+        match page.next_link {
+            Some(next_link) if fetched < max_pages => page = fetch_next(next_link).await?,
+            _ => break,
+        }
+    }
+
+    Ok(values)
+}
+
+/// Fetch every resource of `request`, fetching up to `concurrency` pages at a time using `fetch_paged`,
+/// or following each `next_link` in turn using `fetch_next` if the page count can't be computed.
+/// See [`DataSource::fetch_all_parallel`].
+pub(crate) async fn fetch_all_parallel<T, P, PF, N, NF>(
+    request: ListRequest,
+    concurrency: usize,
+    fetch_paged: P,
+    fetch_next: N,
+) -> Result<Vec<T>, Error>
+where
+    P: Fn(ListRequest) -> PF,
+    PF: Future<Output = Result<Page<T>, Error>>,
+    N: FnMut(String) -> NF,
+    NF: Future<Output = Result<Page<T>, Error>>,
+{
+    let page = fetch_paged(request.clone().inline_count(InlineCount::AllPages)).await?;
+
+    let Some(requests) = remaining_pages(&request, &page) else {
+        return collect_pages(page, usize::MAX, fetch_next).await;
+    };
+
+    let pages: Vec<Page<T>> = stream::iter(requests)
+        .map(fetch_paged)
+        .buffered(concurrency.max(1))
+        .try_collect()
+        .await?;
+
+    Ok(std::iter::once(page)
+        .chain(pages)
+        .flat_map(Page::into_values)
+        .collect())
+}
+
+impl<C> DataSource<C>
+where
+    C: Connector,
+{
+    /// Fetch every resource of a [`ListRequest`], fetching up to `concurrency` pages at a time.
+    ///
+    /// The first page is requested with an [`InlineCount::AllPages`], from which the number of remaining pages
+    /// is computed using the size of the first page, so they can be requested concurrently using `$skip`,
+    /// instead of following the `next_link` of each page in turn. The resources are returned in order.
+    ///
+    /// If the API doesn't report an inline count, the remaining pages are fetched in sequence like
+    /// [`DataSource::fetch_pages`]. Since the page size is determined by the server, the `request`
+    /// shouldn't use [`ListRequest::skip`] or [`ListRequest::top`] itself.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, ListRequest};
+    /// # use serde::Deserialize;
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Deserialize)]
+    /// struct Afstemning {
+    ///     id: usize,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let afstemninger: Vec<Afstemning> = datasource
+    ///     .fetch_all_parallel(ListRequest::new("Afstemning"), 4)
+    ///     .await
+    ///     .unwrap();
+    /// # });
+    /// ```
+    pub async fn fetch_all_parallel<T>(
+        &self,
+        request: ListRequest,
+        concurrency: usize,
+    ) -> Result<Vec<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        fetch_all_parallel(
+            request,
+            concurrency,
+            |request| self.fetch_paged(request),
+            |next_link| async move { self.fetch_next(&next_link).await },
+        )
+        .await
+    }
+
+    /// Fetch each [`Page`] of `request` in sequence, following the `next_link` of each page.
+    ///
+    /// Unlike [`DataSource::fetch_pages`], every page is yielded as soon as it arrives, along with its metadata,
+    /// such as the inline count. The stream ends after the first error, since there is no next link to follow.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, InlineCount, ListRequest, Page};
+    /// # use serde::Deserialize;
+    /// use futures_util::TryStreamExt;
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let request = ListRequest::new("Dokument").inline_count(InlineCount::AllPages);
+    /// let mut pages = Box::pin(datasource.stream_pages::<Dokument>(request));
+    ///
+    /// while let Some(page) = pages.try_next().await.unwrap() {
+    ///     println!("{} of {:?}", page.len(), page.info().total);
+    /// }
+    /// # });
+    /// ```
+    pub fn stream_pages<T>(
+        &self,
+        request: ListRequest,
+    ) -> impl Stream<Item = Result<Page<T>, Error>> + '_
+    where
+        T: DeserializeOwned,
+    {
+        self.stream_cursor(Cursor::First(Box::new(request)))
+    }
+
+    fn stream_cursor<T>(&self, cursor: Cursor) -> impl Stream<Item = Result<Page<T>, Error>> + '_
+    where
+        T: DeserializeOwned,
+    {
+        stream::unfold(cursor, move |cursor| async move {
+            let page = match cursor {
+                Cursor::First(request) => self.fetch_paged::<T>(*request).await,
+                Cursor::Next(next_link) => self.fetch_next::<T>(&next_link).await,
+                Cursor::Done => return None,
+            };
+
+            match page {
+                Ok(page) => {
+                    let cursor = page.next_link.clone().map_or(Cursor::Done, Cursor::Next);
+                    Some((Ok(page), cursor))
+                }
+                Err(e) => Some((Err(e), Cursor::Done)),
+            }
+        })
+    }
+
+    /// Resume a sequence of [`Page`]s started by [`DataSource::stream_pages`] from a saved `next_link`,
+    /// following the `next_link` of each page until the end of the collection.
+    ///
+    /// The `next_link` of every page yielded by either stream serves as a checkpoint: once the values of a page
+    /// have been processed, storing its `next_link` allows resuming from the following page after a restart,
+    /// since the link carries the entire state of the query, including any `$skiptoken`.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{DataSource, ListRequest, Page};
+    /// # use serde::Deserialize;
+    /// use futures_util::{StreamExt, TryStreamExt};
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// # fn load_checkpoint() -> Option<String> { None }
+    /// # fn save_checkpoint(_: &str) {}
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let mut pages = match load_checkpoint() {
+    ///     Some(next_link) => Box::pin(datasource.stream_from::<Dokument>(&next_link)).left_stream(),
+    ///     None => Box::pin(datasource.stream_pages::<Dokument>(ListRequest::new("Dokument"))).right_stream(),
+    /// };
+    ///
+    /// while let Some(page) = pages.try_next().await.unwrap() {
+    ///     println!("exported {} documents", page.len());
+    ///     if let Some(next_link) = &page.next_link {
+    ///         save_checkpoint(next_link);
+    ///     }
+    /// }
+    /// # });
+    /// ```
+    pub fn stream_from<T>(&self, next_link: &str) -> impl Stream<Item = Result<Page<T>, Error>> + '_
+    where
+        T: DeserializeOwned,
+    {
+        self.stream_cursor(Cursor::Next(next_link.to_string()))
+    }
+
+    /// Stream every resource of a [`ListRequest`], following the `next_link` of each [`Page`] until the end
+    /// of the collection, and yielding the values of each page in turn.
+    ///
+    /// Each page is deserialized directly as a [`Page`] of `T`, so a value which doesn't match `T` fails its entire page.
+    /// Errors fetching or deserializing a page end the stream, since its next link is then unknown.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::{Comparison, DataSource, ListRequest};
+    /// # use serde::Deserialize;
+    /// use futures_util::TryStreamExt;
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let request = ListRequest::new("Dokument").filter("typeid", Comparison::Equal, 5);
+    /// let dokumenter: Vec<Dokument> = datasource.stream(request).try_collect().await.unwrap();
+    /// # });
+    /// ```
+    pub fn stream<T>(&self, request: ListRequest) -> impl Stream<Item = Result<T, Error>> + '_
+    where
+        T: DeserializeOwned,
+    {
+        self.stream_pages::<T>(request)
+            .map_ok(|page| stream::iter(page.into_values().into_iter().map(Ok)))
+            .try_flatten()
+    }
+
+    /// Enumerate the ids of every resource of type `resource_type`, by selecting only the `key_field`
+    /// of each resource, ordered by the key, and following paging until the entire collection has been traversed.
+    ///
+    /// Useful for planning a full export, where the details of each resource can then be fetched on demand.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::DataSource;
+    /// use futures_util::TryStreamExt;
+    /// #
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// #
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    ///
+    /// # tokio_test::block_on(async {
+    /// let ids: Vec<usize> = datasource
+    ///     .stream_ids("Dokument", "id")
+    ///     .try_collect()
+    ///     .await
+    ///     .unwrap();
+    /// # });
+    /// ```
+    pub fn stream_ids<'a>(
+        &'a self,
+        resource_type: &str,
+        key_field: &'a str,
+    ) -> impl Stream<Item = Result<usize, Error>> + 'a {
+        let mut request = ListRequest::new(resource_type).order_by(key_field, Direction::Ascending);
+        request.builder = request.builder.select([key_field]);
+
+        self.stream_pages::<Map<String, Value>>(request)
+            .map_ok(move |page| {
+                stream::iter(page.value.into_iter().map(move |resource| {
+                    resource
+                        .get(key_field)
+                        .and_then(Value::as_u64)
+                        .and_then(|id| usize::try_from(id).ok())
+                        .ok_or_else(|| {
+                            let content = Value::Object(resource).to_string();
+                            Error::Serde(
+                                de::Error::custom(format!(
+                                    "missing numeric key field `{key_field}`"
+                                )),
+                                content,
+                            )
+                        })
+                }))
+            })
+            .try_flatten()
+    }
+
+    /// Detect whether the API uses server-driven or client-driven paging for `resource_type`, by requesting
+    /// the first page of the collection, selecting only its `key_field` to keep the page small.
+    ///
+    /// Pages of a single resource are asked for using [`ListRequest::max_page_size`], but no `$top` is set,
+    /// since OData 3.0 services such as oda.ft.dk ignore the preference, and only link to a next page once
+    /// their own page size is exceeded. Collections fitting within a single page can't be told apart,
+    /// and are reported as [`PagingStyle::ClientDriven`], since paging by offset works either way.
+    pub async fn probe_paging_style(
+        &self,
+        resource_type: &str,
+        key_field: &str,
+    ) -> Result<PagingStyle, Error> {
+        let mut request = ListRequest::new(resource_type).max_page_size(1);
+        request.builder = request.builder.select([key_field]);
+        let page = self.fetch_paged::<de::IgnoredAny>(request).await?;
+
+        Ok(match page.next_link {
+            Some(_) => PagingStyle::ServerDriven,
+            None => PagingStyle::ClientDriven,
+        })
+    }
+
+    /// Fetch the resources of `resource_type` whose `key_field` matches any of `ids`, following the `next_link`
+    /// of each page until all matching resources have been returned.
+    ///
+    /// The ids are matched with the `in` operator if the DataSource is configured for [`ODataVersion::V4`],
+    /// and with a chain of `eq` comparisons otherwise. If the filter would make the path and query longer than
+    /// 2048 characters, or the limit set using [`DataSource::with_query_length_limit`], the ids are split across
+    /// several requests. The resources are returned in the order the server returns them, not the order of `ids`.
+    /// ```no_run
+    /// # use hyper::{Client, client::HttpConnector};
+    /// # use hyper_openssl::{HttpsConnector};
+    /// # use odata_simple_client::DataSource;
+    /// # use serde::Deserialize;
+    /// # let client: Client<HttpsConnector<HttpConnector>> =
+    /// #   Client::builder().build(HttpsConnector::<HttpConnector>::new().unwrap());
+    /// # let datasource = DataSource::new(client, "oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// #[derive(Deserialize)]
+    /// struct Dokument {
+    ///     id: usize,
+    ///     titel: String,
+    /// }
+    ///
+    /// # tokio_test::block_on(async {
+    /// let dokumenter: Vec<Dokument> = datasource
+    ///     .batch_get("Dokument", "id", &[24, 25, 1016])
+    ///     .await
+    ///     .unwrap();
+    /// # });
+    /// ```
+    pub async fn batch_get<T>(
+        &self,
+        resource_type: &str,
+        key_field: &str,
+        ids: &[usize],
+    ) -> Result<Vec<T>, Error>
+    where
+        T: DeserializeOwned,
+    {
+        let limit = self
+            .query_length_limit
+            .map_or(BATCH_QUERY_LENGTH, |limit| limit.min(BATCH_QUERY_LENGTH));
+        let service_root = self.service_root();
+
+        let mut chunks: Vec<&[usize]> = Vec::new();
+        let mut start = 0;
+        for end in 1..=ids.len() {
+            let request = self.batch_request(resource_type, key_field, &ids[start..end]);
+            let length = request
+                .format(Format::Json)
+                .to_query_string(&service_root)?
+                .len();
+            if end - start > 1 && length > limit {
+                chunks.push(&ids[start..end - 1]);
+                start = end - 1;
+            }
+        }
+        if start < ids.len() {
+            chunks.push(&ids[start..]);
+        }
+
+        let mut resources = Vec::new();
+        for chunk in chunks {
+            let request = self.batch_request(resource_type, key_field, chunk);
+            let mut pages = Box::pin(self.stream_pages::<T>(request));
+            while let Some(page) = pages.try_next().await? {
+                resources.extend(page.value);
+            }
+        }
+
+        Ok(resources)
+    }
+
+    /// Build the request for the resources of `resource_type` whose `key_field` matches any of the non-empty `ids`.
+    fn batch_request(&self, resource_type: &str, key_field: &str, ids: &[usize]) -> ListRequest {
+        let is_id =
+            |id: usize| FilterExpr::field(key_field).compare(Comparison::Equal, Literal::from(id));
+        let filter = match self.odata_version {
+            Some(ODataVersion::V4) => {
+                FilterExpr::field(key_field).is_in(ids.iter().map(|id| Literal::from(*id)))
+            }
+            _ => ids[1..]
+                .iter()
+                .fold(is_id(ids[0]), |filter, id| filter.or(is_id(*id))),
+        };
+
+        ListRequest::new(resource_type).filter_expr(filter)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PagingStyle;
+    use crate::{mock, Error, ListRequest, ODataVersion, Page};
+    use futures_util::{StreamExt, TryStreamExt};
+    use serde_json::Value;
+
+    #[tokio::test]
+    async fn test_stream_pages() {
+        let (datasource, _) = mock::datasource(|request| {
+            match request.uri.query().unwrap_or_default() {
+                query if query.contains("skip") => mock::json(200, r#"{"value": [{"id": 3}]}"#),
+                _ => mock::json(
+                    200,
+                    r#"{"value": [{"id": 1}, {"id": 2}], "odata.count": "3", "odata.nextLink": "Dokument?$skip=2"}"#,
+                ),
+            }
+        });
+
+        let pages: Vec<Page<Value>> = datasource
+            .stream_pages(ListRequest::new("Dokument"))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].len(), 2);
+        assert_eq!(pages[0].info().total, Some(3));
+        assert!(!pages[1].info().has_next);
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_parallel() {
+        let (datasource, connector) = mock::datasource(|request| {
+            match request.uri.query().unwrap_or_default() {
+                query if query.contains("$skip=4") => mock::json(200, r#"{"value": [5]}"#),
+                query if query.contains("$skip=2") => mock::json(
+                    200,
+                    r#"{"value": [3, 4], "odata.nextLink": "Dokument?$skip=4"}"#,
+                ),
+                _ => mock::json(
+                    200,
+                    r#"{"value": [1, 2], "odata.count": "5", "odata.nextLink": "Dokument?$skip=2"}"#,
+                ),
+            }
+        });
+
+        let all: Vec<u32> = datasource
+            .fetch_all_parallel(ListRequest::new("Dokument"), 2)
+            .await
+            .unwrap();
+        assert_eq!(all, vec![1, 2, 3, 4, 5]);
+
+        let requests = connector.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            requests[0].uri,
+            "/api/Dokument?$format=json&$inlinecount=allpages"
+        );
+        assert!(requests.iter().all(|request| request
+            .uri
+            .query()
+            .is_some_and(|query| query.contains("$format=json"))));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_all_parallel_without_count() {
+        let (datasource, connector) =
+            mock::datasource(|request| match request.uri.query().unwrap_or_default() {
+                query if query.contains("skip") => mock::json(200, r#"{"value": [3]}"#),
+                _ => mock::json(
+                    200,
+                    r#"{"value": [1, 2], "odata.nextLink": "Dokument?$skip=2"}"#,
+                ),
+            });
+
+        let all: Vec<u32> = datasource
+            .fetch_all_parallel(ListRequest::new("Dokument"), 2)
+            .await
+            .unwrap();
+        assert_eq!(all, vec![1, 2, 3]);
+        assert_eq!(connector.requests()[1].uri, "/api/Dokument?$skip=2");
+    }
+
+    #[tokio::test]
+    async fn test_stream_from() {
+        let (datasource, connector) =
+            mock::datasource(|request| match request.uri.query().unwrap_or_default() {
+                query if query.contains("$skip=4") => mock::json(200, r#"{"value": [5]}"#),
+                _ => mock::json(
+                    200,
+                    r#"{"value": [3, 4], "odata.nextLink": "Dokument?$skip=4"}"#,
+                ),
+            });
+
+        let pages: Vec<Page<u32>> = datasource
+            .stream_from("Dokument?$skip=2")
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(pages.len(), 2);
+        assert_eq!(pages[0].next_link.as_deref(), Some("Dokument?$skip=4"));
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/api/Dokument?$skip=2");
+        assert_eq!(requests[1].uri, "/api/Dokument?$skip=4");
+    }
+
+    #[tokio::test]
+    async fn test_stream() {
+        let (datasource, connector) =
+            mock::datasource(|request| match request.uri.query().unwrap_or_default() {
+                _ if request.uri.path() == "/api/Sag" => mock::json(
+                    200,
+                    r#"{"value": [1, "two"], "odata.nextLink": "Sag?$skip=2"}"#,
+                ),
+                query if query.contains("skip") => mock::json(200, r#"{"value": [3]}"#),
+                _ => mock::json(
+                    200,
+                    r#"{"value": [1, 2], "odata.nextLink": "Dokument?$skip=2"}"#,
+                ),
+            });
+
+        let values: Vec<u32> = datasource
+            .stream::<u32>(ListRequest::new("Dokument"))
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(values, vec![1, 2, 3]);
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/api/Dokument?$format=json");
+        assert_eq!(requests[1].uri, "/api/Dokument?$skip=2");
+
+        let values: Vec<Result<u32, Error>> =
+            datasource.stream(ListRequest::new("Sag")).collect().await;
+        assert_eq!(values.len(), 1);
+        assert!(matches!(&values[0], Err(Error::Serde(..))));
+    }
+
+    #[tokio::test]
+    async fn test_stream_ids() {
+        let (datasource, connector) = mock::datasource(|request| {
+            match request.uri.query().unwrap_or_default() {
+                query if query.contains("skip") => mock::json(200, r#"{"value": [{"id": 3}]}"#),
+                _ => mock::json(
+                    200,
+                    r#"{"value": [{"id": 1}, {"id": 2}], "odata.nextLink": "Dokument?$select=id&$skip=2"}"#,
+                ),
+            }
+        });
+
+        let ids: Vec<usize> = datasource
+            .stream_ids("Dokument", "id")
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        let requests = connector.requests();
+        assert_eq!(
+            requests[0].uri,
+            "/api/Dokument?$format=json&$orderby=id%20asc&$select=id"
+        );
+        assert_eq!(requests[1].uri, "/api/Dokument?$select=id&$skip=2");
+    }
+
+    #[tokio::test]
+    async fn test_batch_get() {
+        let (datasource, connector) =
+            mock::datasource(|request| match request.uri.query().unwrap_or_default() {
+                query if query.contains("skip") => mock::json(200, r#"{"value": [2]}"#),
+                query if query.contains("%201") => mock::json(
+                    200,
+                    r#"{"value": [1], "odata.nextLink": "Dokument?$skip=1"}"#,
+                ),
+                _ => mock::json(200, r#"{"value": [3, 4]}"#),
+            });
+
+        let empty: Vec<u32> = datasource.batch_get("Dokument", "id", &[]).await.unwrap();
+        assert!(empty.is_empty());
+        assert!(connector.requests().is_empty());
+
+        let datasource = datasource.with_query_length_limit(80);
+        let resources: Vec<u32> = datasource
+            .batch_get("Dokument", "id", &[1, 2, 3, 4])
+            .await
+            .unwrap();
+        assert_eq!(resources, vec![1, 2, 3, 4]);
+
+        let requests = connector.requests();
+        assert_eq!(requests.len(), 3);
+        assert_eq!(
+            requests[0].uri,
+            "/api/Dokument?$filter=id%20eq%201%20or%20id%20eq%202&$format=json"
+        );
+        assert_eq!(requests[1].uri, "/api/Dokument?$skip=1");
+        assert_eq!(
+            requests[2].uri,
+            "/api/Dokument?$filter=id%20eq%203%20or%20id%20eq%204&$format=json"
+        );
+
+        let datasource = datasource.with_odata_version(ODataVersion::V4);
+        let _: Vec<u32> = datasource
+            .batch_get("Dokument", "id", &[3, 4])
+            .await
+            .unwrap();
+        assert_eq!(
+            connector.requests()[3].uri,
+            "/api/Dokument?$filter=id%20in%20%283%2C4%29&$format=json"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_probe_paging_style() {
+        let (datasource, connector) = mock::datasource(|request| match request.uri.path() {
+            "/api/Dokument" => mock::json(
+                200,
+                r#"{"value": [{"id": 1}], "odata.nextLink": "Dokument?$skip=1"}"#,
+            ),
+            // Ignores the preference, like OData 3.0 services, paging at 100 resources instead.
+            "/api/Sag" => {
+                let ids: Vec<_> = (1..=100).map(|id| format!(r#"{{"id": {id}}}"#)).collect();
+                mock::json(
+                    200,
+                    &format!(
+                        r#"{{"value": [{}], "odata.nextLink": "Sag?$skip=100"}}"#,
+                        ids.join(",")
+                    ),
+                )
+            }
+            _ => mock::json(200, r#"{"value": [{"id": 1}, {"id": 2}]}"#),
+        });
+
+        assert_eq!(
+            datasource
+                .probe_paging_style("Dokument", "id")
+                .await
+                .unwrap(),
+            PagingStyle::ServerDriven
+        );
+        assert_eq!(
+            datasource.probe_paging_style("Sag", "id").await.unwrap(),
+            PagingStyle::ServerDriven
+        );
+        assert_eq!(
+            datasource.probe_paging_style("Aktør", "id").await.unwrap(),
+            PagingStyle::ClientDriven
+        );
+
+        let requests = connector.requests();
+        assert_eq!(requests[0].uri, "/api/Dokument?$format=json&$select=id");
+        assert_eq!(requests[0].headers["Prefer"], "odata.maxpagesize=1");
+    }
+}
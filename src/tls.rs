@@ -0,0 +1,131 @@
+use crate::{DataSource, Error};
+use futures_util::future::{self, Ready};
+use hyper::{
+    client::{connect::dns::Name, HttpConnector},
+    http::uri::Authority,
+    service::Service,
+    Client,
+};
+use hyper_openssl::HttpsConnector;
+use openssl::ssl::{SslConnector, SslMethod, SslVerifyMode};
+use std::{
+    convert::{Infallible, TryFrom},
+    iter,
+    net::SocketAddr,
+    task::{Context, Poll},
+};
+
+/// DNS resolver which resolves every hostname to the same address. Requires the `openssl` feature to be enabled.
+///
+/// Only the IP of the address is used, the port is always taken from the request.
+/// Can be used with [`HttpConnector::new_with_resolver`] to build a custom connector,
+/// or through [`DataSource::https_with_resolved_addr`].
+#[derive(Debug, Clone, Copy)]
+pub struct StaticResolver {
+    addr: SocketAddr,
+}
+
+impl StaticResolver {
+    /// Construct a resolver resolving every hostname to `addr`.
+    pub fn new(addr: SocketAddr) -> Self {
+        StaticResolver { addr }
+    }
+}
+
+impl Service<Name> for StaticResolver {
+    type Response = iter::Once<SocketAddr>;
+    type Error = Infallible;
+    type Future = Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _: Name) -> Self::Future {
+        future::ok(iter::once(self.addr))
+    }
+}
+
+impl DataSource<HttpsConnector<HttpConnector<StaticResolver>>> {
+    /// Construct a DataSource communicating over HTTPS with the server at `addr`, rather than the address which
+    /// `domain` resolves to. Requires the `openssl` feature to be enabled.
+    ///
+    /// The `domain` is still used for the `Host` header and for verifying the certificate of the server,
+    /// which makes this suitable for pinning requests to an individual node of a load-balanced cluster.
+    /// ```rust
+    /// use odata_simple_client::DataSource;
+    ///
+    /// let datasource = DataSource::https_with_resolved_addr(
+    ///     "oda.ft.dk",
+    ///     Some(String::from("/api")),
+    ///     "10.0.0.12:443".parse().unwrap(),
+    /// ).unwrap();
+    /// ```
+    pub fn https_with_resolved_addr<A>(
+        domain: A,
+        base_path: Option<String>,
+        addr: SocketAddr,
+    ) -> Result<Self, Error>
+    where
+        Authority: TryFrom<A>,
+        Error: From<<Authority as TryFrom<A>>::Error>,
+    {
+        let mut http = HttpConnector::new_with_resolver(StaticResolver::new(addr));
+        http.enforce_http(false);
+
+        let ssl = SslConnector::builder(SslMethod::tls())?;
+        let client = Client::builder().build(HttpsConnector::with_connector(http, ssl)?);
+        DataSource::new(client, domain, base_path)
+    }
+}
+
+impl DataSource<HttpsConnector<HttpConnector>> {
+    /// Construct a DataSource communicating over HTTPS using a default [`Client`], verifying the certificate
+    /// of the server using the system trust store. Requires the `openssl` feature to be enabled.
+    ///
+    /// Use [`DataSource::new`] instead to customize the connector, such as its connect timeout.
+    /// ```rust
+    /// use odata_simple_client::DataSource;
+    ///
+    /// let datasource = DataSource::https("oda.ft.dk", Some(String::from("/api"))).unwrap();
+    /// ```
+    pub fn https<A>(domain: A, base_path: Option<String>) -> Result<Self, Error>
+    where
+        Authority: TryFrom<A>,
+        Error: From<<Authority as TryFrom<A>>::Error>,
+    {
+        let client = Client::builder().build(HttpsConnector::new()?);
+        DataSource::new(client, domain, base_path)
+    }
+
+    /// Construct a DataSource communicating over HTTPS **without verifying the certificate of the server**.
+    /// Requires the `openssl` feature to be enabled.
+    ///
+    /// # Danger
+    ///
+    /// This makes the connection vulnerable to man-in-the-middle attacks, since anyone can impersonate the server.
+    /// It exists solely for development against servers using self-signed certificates, such as internal staging
+    /// environments, and must never be used in production.
+    /// ```rust
+    /// use odata_simple_client::DataSource;
+    ///
+    /// let datasource = DataSource::dangerous_insecure_https(
+    ///     "staging.internal",
+    ///     Some(String::from("/api"))
+    /// ).unwrap();
+    /// ```
+    pub fn dangerous_insecure_https<A>(domain: A, base_path: Option<String>) -> Result<Self, Error>
+    where
+        Authority: TryFrom<A>,
+        Error: From<<Authority as TryFrom<A>>::Error>,
+    {
+        let mut ssl = SslConnector::builder(SslMethod::tls())?;
+        ssl.set_verify(SslVerifyMode::NONE);
+
+        let mut http = HttpConnector::new();
+        http.enforce_http(false);
+
+        let client = Client::builder().build(HttpsConnector::with_connector(http, ssl)?);
+        DataSource::new(client, domain, base_path)
+    }
+}
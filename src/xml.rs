@@ -0,0 +1,49 @@
+use serde::de::DeserializeOwned;
+
+/// Parse an XML document, such as a single entity requested using [`Format::Xml`](`crate::Format::Xml`).
+///
+/// Like the JSON backends, errors are reported as a [`serde_json::Error`], so the public
+/// [`Error`](`crate::Error`) is unaffected.
+pub(crate) fn from_str<T: DeserializeOwned>(content: &str) -> serde_json::Result<T> {
+    quick_xml::de::from_str(content).map_err(serde::de::Error::custom)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{mock, Format, GetRequest};
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Dokument {
+        id: usize,
+        titel: String,
+    }
+
+    #[tokio::test]
+    async fn test_fetch_xml() {
+        let (datasource, connector) = mock::datasource(|_| {
+            hyper::Response::builder()
+                .status(200)
+                .header("Content-Type", "application/xml")
+                .body(hyper::Body::from(
+                    "<Dokument><id>24</id><titel>Grundnotat</titel></Dokument>",
+                ))
+                .unwrap()
+        });
+
+        let dokument: Dokument = datasource
+            .fetch(GetRequest::new("Dokument", 24).format(Format::Xml))
+            .await
+            .unwrap();
+        assert_eq!(
+            dokument,
+            Dokument {
+                id: 24,
+                titel: String::from("Grundnotat")
+            }
+        );
+        assert_eq!(connector.requests()[0].uri, "/api/Dokument(24)?$format=xml");
+
+        assert!(super::from_str::<Dokument>("<Dokument><id>24</id>").is_err());
+    }
+}